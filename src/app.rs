@@ -1,7 +1,7 @@
 use std::{
     cmp::{max, min},
     collections::HashMap,
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use unicode_segmentation::GraphemeCursor;
@@ -10,7 +10,7 @@ use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 use typed_builder::*;
 
 use data_farmer::*;
-use data_harvester::temperature;
+use data_harvester::{cpu::CpuGrouping, temperature};
 use layout_manager::*;
 pub use states::*;
 
@@ -30,10 +30,12 @@ pub mod frozen_state;
 pub mod layout_manager;
 mod process_killer;
 pub mod query;
+pub mod search_history;
 pub mod states;
 pub mod widgets;
 
 use frozen_state::FrozenState;
+use search_history::SearchHistory;
 
 const MAX_SEARCH_LENGTH: usize = 200;
 
@@ -58,6 +60,14 @@ pub struct AppConfigFields {
     pub use_dot: bool,
     pub left_legend: bool,
     pub show_average_cpu: bool,
+    pub cpu_grouping: Option<CpuGrouping>,
+    pub use_container_limits: bool,
+    pub show_all_mounts: bool,
+    /// How far back disk usage is looked at to forecast when each disk fills up.
+    pub forecast_window: Duration,
+    /// How soon a disk has to be forecast to fill up for it to be highlighted.
+    pub forecast_warning: Duration,
+    pub normalize_load_avg: bool,
     pub use_current_cpu_total: bool,
     pub use_basic_mode: bool,
     pub default_time_value: u64,
@@ -81,6 +91,7 @@ pub struct AppConfigFields {
 pub struct DataFilters {
     pub disk_filter: Option<Filter>,
     pub mount_filter: Option<Filter>,
+    pub fs_filter: Option<Filter>,
     pub temp_filter: Option<Filter>,
     pub net_filter: Option<Filter>,
 }
@@ -109,6 +120,9 @@ pub struct App {
     #[builder(default, setter(skip))]
     pub frozen_state: FrozenState,
 
+    #[builder(default, setter(skip))]
+    pub search_history: SearchHistory,
+
     #[builder(default = Instant::now(), setter(skip))]
     last_key_press: Instant,
 
@@ -141,11 +155,15 @@ pub struct App {
     pub user_table: data_harvester::processes::UserTable,
 
     pub cpu_state: CpuState,
+    pub cpu_heatmap_state: CpuHeatmapState,
     pub mem_state: MemState,
+    pub pressure_state: PressureState,
+    pub kernel_state: KernelState,
     pub net_state: NetState,
     pub proc_state: ProcState,
     pub temp_state: TempState,
     pub disk_state: DiskState,
+    pub disk_io_state: DiskIoState,
     pub battery_state: BatteryState,
     pub basic_table_widget_state: Option<BasicTableWidgetState>,
     pub app_config_fields: AppConfigFields,
@@ -229,6 +247,9 @@ impl App {
                         .get_mut_widget_state(self.current_widget.widget_id)
                     {
                         if pws.is_search_enabled() || pws.is_sort_open {
+                            if pws.is_search_enabled() {
+                                self.search_history.push(pws.get_current_search_query());
+                            }
                             pws.proc_search.search_state.is_enabled = false;
                             pws.is_sort_open = false;
                             self.is_force_redraw = true;
@@ -242,6 +263,7 @@ impl App {
                         .get_mut_widget_state(self.current_widget.widget_id - 1)
                     {
                         if pws.is_search_enabled() {
+                            self.search_history.push(pws.get_current_search_query());
                             pws.proc_search.search_state.is_enabled = false;
                             self.move_widget_selection(&WidgetDirection::Up);
                             self.is_force_redraw = true;
@@ -279,6 +301,13 @@ impl App {
         )
     }
 
+    /// Whether the key to show all mounts applies, which is only in the disk widget and outside
+    /// of dialogs.
+    pub fn can_toggle_all_mounts(&self) -> bool {
+        !self.ignore_normal_keybinds()
+            && matches!(self.current_widget.widget_type, BottomWidgetType::Disk)
+    }
+
     fn reset_multi_tap_keys(&mut self) {
         self.awaiting_second_char = false;
         self.second_char = None;
@@ -293,16 +322,33 @@ impl App {
     }
 
     pub fn on_tab(&mut self) {
-        // Allow usage whilst only in processes
+        // Allow usage whilst only in processes or its search bar
 
         if !self.ignore_normal_keybinds() {
-            if let BottomWidgetType::Proc = self.current_widget.widget_type {
-                if let Some(proc_widget_state) = self
-                    .proc_state
-                    .get_mut_widget_state(self.current_widget.widget_id)
-                {
-                    proc_widget_state.on_tab();
+            match self.current_widget.widget_type {
+                BottomWidgetType::Proc => {
+                    if let Some(proc_widget_state) = self
+                        .proc_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        proc_widget_state.on_tab();
+                    }
                 }
+                BottomWidgetType::ProcSearch => {
+                    if let Some(proc_widget_state) = self
+                        .proc_state
+                        .get_mut_widget_state(self.current_widget.widget_id - 1)
+                    {
+                        if proc_widget_state.is_search_enabled() {
+                            proc_widget_state.complete_search_query(
+                                &self.data_collection,
+                                #[cfg(target_family = "unix")]
+                                &mut self.user_table,
+                            );
+                        }
+                    }
+                }
+                _ => {}
             }
         }
     }
@@ -433,7 +479,15 @@ impl App {
     }
 
     pub fn toggle_tree_mode(&mut self) {
-        if let Some(proc_widget_state) = self
+        if let BottomWidgetType::Disk = self.current_widget.widget_type {
+            if let Some(disk_widget_state) = self
+                .disk_state
+                .get_mut_widget_state(self.current_widget.widget_id)
+            {
+                disk_widget_state.toggle_tree_mode();
+                self.is_force_redraw = true;
+            }
+        } else if let Some(proc_widget_state) = self
             .proc_state
             .widget_states
             .get_mut(&(self.current_widget.widget_id))
@@ -481,16 +535,29 @@ impl App {
             }
             self.is_force_redraw = true;
         } else if !self.is_in_dialog() {
-            if let BottomWidgetType::ProcSort = self.current_widget.widget_type {
-                if let Some(proc_widget_state) = self
-                    .proc_state
-                    .widget_states
-                    .get_mut(&(self.current_widget.widget_id - 2))
-                {
-                    proc_widget_state.use_sort_table_value();
-                    self.move_widget_selection(&WidgetDirection::Right);
-                    self.is_force_redraw = true;
+            match self.current_widget.widget_type {
+                BottomWidgetType::ProcSort => {
+                    if let Some(proc_widget_state) = self
+                        .proc_state
+                        .widget_states
+                        .get_mut(&(self.current_widget.widget_id - 2))
+                    {
+                        proc_widget_state.use_sort_table_value();
+                        self.move_widget_selection(&WidgetDirection::Right);
+                        self.is_force_redraw = true;
+                    }
                 }
+                BottomWidgetType::ProcSearch => {
+                    if let Some(proc_widget_state) = self
+                        .proc_state
+                        .widget_states
+                        .get(&(self.current_widget.widget_id - 1))
+                    {
+                        self.search_history
+                            .push(proc_widget_state.get_current_search_query());
+                    }
+                }
+                _ => {}
             }
         }
     }
@@ -635,7 +702,11 @@ impl App {
 
     pub fn on_up_key(&mut self) {
         if !self.is_in_dialog() {
-            self.decrement_position_count();
+            if self.is_in_search_widget() {
+                self.recall_older_search();
+            } else {
+                self.decrement_position_count();
+            }
         } else if self.help_dialog_state.is_showing_help {
             self.help_scroll_up();
         } else if self.delete_dialog_state.is_showing_dd {
@@ -656,7 +727,11 @@ impl App {
 
     pub fn on_down_key(&mut self) {
         if !self.is_in_dialog() {
-            self.increment_position_count();
+            if self.is_in_search_widget() {
+                self.recall_newer_search();
+            } else {
+                self.increment_position_count();
+            }
         } else if self.help_dialog_state.is_showing_help {
             self.help_scroll_down();
         } else if self.delete_dialog_state.is_showing_dd {
@@ -994,11 +1069,52 @@ impl App {
                 .widget_states
                 .get_mut(&(self.current_widget.widget_id - 1))
             {
+                // Keep the query around in the history, so clearing it isn't permanent.
+                self.search_history
+                    .push(proc_widget_state.get_current_search_query());
                 proc_widget_state.clear_search();
             }
         }
     }
 
+    fn recall_older_search(&mut self) {
+        if let Some(proc_widget_state) = self
+            .proc_state
+            .widget_states
+            .get_mut(&(self.current_widget.widget_id - 1))
+        {
+            if proc_widget_state.is_search_enabled() {
+                proc_widget_state.recall_older_search(&self.search_history);
+            }
+        }
+    }
+
+    fn recall_newer_search(&mut self) {
+        if let Some(proc_widget_state) = self
+            .proc_state
+            .widget_states
+            .get_mut(&(self.current_widget.widget_id - 1))
+        {
+            if proc_widget_state.is_search_enabled() {
+                proc_widget_state.recall_newer_search(&self.search_history);
+            }
+        }
+    }
+
+    pub fn reverse_search_history(&mut self) {
+        if let BottomWidgetType::ProcSearch = self.current_widget.widget_type {
+            if let Some(proc_widget_state) = self
+                .proc_state
+                .widget_states
+                .get_mut(&(self.current_widget.widget_id - 1))
+            {
+                if proc_widget_state.is_search_enabled() {
+                    proc_widget_state.reverse_search_history(&self.search_history);
+                }
+            }
+        }
+    }
+
     pub fn clear_previous_word(&mut self) {
         if let BottomWidgetType::ProcSearch = self.current_widget.widget_type {
             if let Some(proc_widget_state) = self
@@ -1302,6 +1418,39 @@ impl App {
                     }
                 }
             }
+            'F' => {
+                if let BottomWidgetType::Proc = self.current_widget.widget_type {
+                    if let Some(proc_widget_state) = self
+                        .proc_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        proc_widget_state.cycle_saved_filter();
+                    }
+                }
+            }
+            'b' => self.cycle_graph_mode(),
+            'x' => {
+                if let BottomWidgetType::Disk = self.current_widget.widget_type {
+                    if let Some(disk_widget_state) = self
+                        .disk_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        disk_widget_state.toggle_extended_stats();
+                        self.is_force_redraw = true;
+                    }
+                }
+            }
+            'i' => {
+                if let BottomWidgetType::Disk = self.current_widget.widget_type {
+                    if let Some(disk_widget_state) = self
+                        .disk_state
+                        .get_mut_widget_state(self.current_widget.widget_id)
+                    {
+                        disk_widget_state.toggle_fs_details();
+                        self.is_force_redraw = true;
+                    }
+                }
+            }
             '?' => {
                 self.help_dialog_state.is_showing_help = true;
                 self.is_force_redraw = true;
@@ -1361,6 +1510,60 @@ impl App {
         self.to_delete_process_list.clone()
     }
 
+    fn cycle_graph_mode(&mut self) {
+        let cpu_widget_id = match self.current_widget.widget_type {
+            BottomWidgetType::Cpu => self.current_widget.widget_id,
+            BottomWidgetType::CpuLegend => self.current_widget.widget_id - 1,
+            BottomWidgetType::CpuHeatmap => {
+                if let Some(heatmap_widget_state) = self
+                    .cpu_heatmap_state
+                    .get_mut_widget_state(self.current_widget.widget_id)
+                {
+                    heatmap_widget_state.toggle_mode();
+                    self.is_force_redraw = true;
+                }
+                return;
+            }
+            BottomWidgetType::Pressure => {
+                if let Some(pressure_widget_state) = self
+                    .pressure_state
+                    .get_mut_widget_state(self.current_widget.widget_id)
+                {
+                    pressure_widget_state.show_full = !pressure_widget_state.show_full;
+                    self.is_force_redraw = true;
+                }
+                return;
+            }
+            BottomWidgetType::Mem => {
+                if let Some(mem_widget_state) = self
+                    .mem_state
+                    .widget_states
+                    .get_mut(&self.current_widget.widget_id)
+                {
+                    mem_widget_state.graph_mode = mem_widget_state.graph_mode.next();
+                    self.is_force_redraw = true;
+                }
+                return;
+            }
+            BottomWidgetType::DiskIo => {
+                if let Some(disk_io_widget_state) = self
+                    .disk_io_state
+                    .get_mut_widget_state(self.current_widget.widget_id)
+                {
+                    disk_io_widget_state.graph_mode = disk_io_widget_state.graph_mode.next();
+                    self.is_force_redraw = true;
+                }
+                return;
+            }
+            _ => return,
+        };
+
+        if let Some(cpu_widget_state) = self.cpu_state.widget_states.get_mut(&cpu_widget_id) {
+            cpu_widget_state.graph_mode = cpu_widget_state.graph_mode.next();
+            self.is_force_redraw = true;
+        }
+    }
+
     fn toggle_expand_widget(&mut self) {
         if self.is_expanded {
             self.is_expanded = false;
@@ -2078,20 +2281,20 @@ impl App {
     }
 
     fn on_plus(&mut self) {
-        if let BottomWidgetType::Proc = self.current_widget.widget_type {
+        match self.current_widget.widget_type {
             // Toggle collapsing if tree
-            self.toggle_collapsing_process_branch();
-        } else {
-            self.zoom_in();
+            BottomWidgetType::Proc => self.toggle_collapsing_process_branch(),
+            BottomWidgetType::CpuLegend => self.toggle_collapsing_cpu_group(),
+            _ => self.zoom_in(),
         }
     }
 
     fn on_minus(&mut self) {
-        if let BottomWidgetType::Proc = self.current_widget.widget_type {
+        match self.current_widget.widget_type {
             // Toggle collapsing if tree
-            self.toggle_collapsing_process_branch();
-        } else {
-            self.zoom_out();
+            BottomWidgetType::Proc => self.toggle_collapsing_process_branch(),
+            BottomWidgetType::CpuLegend => self.toggle_collapsing_cpu_group(),
+            _ => self.zoom_out(),
         }
     }
 
@@ -2105,6 +2308,16 @@ impl App {
         }
     }
 
+    fn toggle_collapsing_cpu_group(&mut self) {
+        if let Some(cpu_widget_state) = self
+            .cpu_state
+            .widget_states
+            .get_mut(&(self.current_widget.widget_id - 1))
+        {
+            cpu_widget_state.toggle_current_group(&self.converted_data.cpu_data);
+        }
+    }
+
     fn zoom_out(&mut self) {
         match self.current_widget.widget_type {
             BottomWidgetType::Cpu => {
@@ -2132,6 +2345,60 @@ impl App {
                     }
                 }
             }
+            BottomWidgetType::CpuHeatmap => {
+                if let Some(heatmap_widget_state) = self
+                    .cpu_heatmap_state
+                    .widget_states
+                    .get_mut(&self.current_widget.widget_id)
+                {
+                    let new_time = heatmap_widget_state.current_display_time
+                        + self.app_config_fields.time_interval;
+                    heatmap_widget_state.current_display_time =
+                        min(new_time, constants::STALE_MAX_MILLISECONDS);
+                }
+            }
+            BottomWidgetType::Pressure => {
+                if let Some(pressure_widget_state) = self
+                    .pressure_state
+                    .widget_states
+                    .get_mut(&self.current_widget.widget_id)
+                {
+                    let new_time = pressure_widget_state.current_display_time
+                        + self.app_config_fields.time_interval;
+                    pressure_widget_state.current_display_time =
+                        min(new_time, constants::STALE_MAX_MILLISECONDS);
+                    if self.app_config_fields.autohide_time {
+                        pressure_widget_state.autohide_timer = Some(Instant::now());
+                    }
+                }
+            }
+            BottomWidgetType::DiskIo => {
+                if let Some(disk_io_widget_state) = self
+                    .disk_io_state
+                    .widget_states
+                    .get_mut(&self.current_widget.widget_id)
+                {
+                    let new_time = disk_io_widget_state.current_display_time
+                        + self.app_config_fields.time_interval;
+                    disk_io_widget_state.current_display_time =
+                        min(new_time, constants::STALE_MAX_MILLISECONDS);
+                    if self.app_config_fields.autohide_time {
+                        disk_io_widget_state.autohide_timer = Some(Instant::now());
+                    }
+                }
+            }
+            BottomWidgetType::Kernel => {
+                if let Some(kernel_widget_state) = self
+                    .kernel_state
+                    .widget_states
+                    .get_mut(&self.current_widget.widget_id)
+                {
+                    let new_time = kernel_widget_state.current_display_time
+                        + self.app_config_fields.time_interval;
+                    kernel_widget_state.current_display_time =
+                        min(new_time, constants::STALE_MAX_MILLISECONDS);
+                }
+            }
             BottomWidgetType::Mem => {
                 if let Some(mem_widget_state) = self
                     .mem_state
@@ -2213,6 +2480,64 @@ impl App {
                     }
                 }
             }
+            BottomWidgetType::CpuHeatmap => {
+                if let Some(heatmap_widget_state) = self
+                    .cpu_heatmap_state
+                    .widget_states
+                    .get_mut(&self.current_widget.widget_id)
+                {
+                    let new_time = heatmap_widget_state
+                        .current_display_time
+                        .saturating_sub(self.app_config_fields.time_interval);
+                    heatmap_widget_state.current_display_time =
+                        max(new_time, constants::STALE_MIN_MILLISECONDS);
+                }
+            }
+            BottomWidgetType::Pressure => {
+                if let Some(pressure_widget_state) = self
+                    .pressure_state
+                    .widget_states
+                    .get_mut(&self.current_widget.widget_id)
+                {
+                    let new_time = pressure_widget_state
+                        .current_display_time
+                        .saturating_sub(self.app_config_fields.time_interval);
+                    pressure_widget_state.current_display_time =
+                        max(new_time, constants::STALE_MIN_MILLISECONDS);
+                    if self.app_config_fields.autohide_time {
+                        pressure_widget_state.autohide_timer = Some(Instant::now());
+                    }
+                }
+            }
+            BottomWidgetType::DiskIo => {
+                if let Some(disk_io_widget_state) = self
+                    .disk_io_state
+                    .widget_states
+                    .get_mut(&self.current_widget.widget_id)
+                {
+                    let new_time = disk_io_widget_state
+                        .current_display_time
+                        .saturating_sub(self.app_config_fields.time_interval);
+                    disk_io_widget_state.current_display_time =
+                        max(new_time, constants::STALE_MIN_MILLISECONDS);
+                    if self.app_config_fields.autohide_time {
+                        disk_io_widget_state.autohide_timer = Some(Instant::now());
+                    }
+                }
+            }
+            BottomWidgetType::Kernel => {
+                if let Some(kernel_widget_state) = self
+                    .kernel_state
+                    .widget_states
+                    .get_mut(&self.current_widget.widget_id)
+                {
+                    let new_time = kernel_widget_state
+                        .current_display_time
+                        .saturating_sub(self.app_config_fields.time_interval);
+                    kernel_widget_state.current_display_time =
+                        max(new_time, constants::STALE_MIN_MILLISECONDS);
+                }
+            }
             BottomWidgetType::Mem => {
                 if let Some(mem_widget_state) = self
                     .mem_state
@@ -2281,6 +2606,16 @@ impl App {
         }
     }
 
+    fn reset_cpu_heatmap_zoom(&mut self) {
+        if let Some(heatmap_widget_state) = self
+            .cpu_heatmap_state
+            .widget_states
+            .get_mut(&self.current_widget.widget_id)
+        {
+            heatmap_widget_state.current_display_time = self.app_config_fields.default_time_value;
+        }
+    }
+
     fn reset_mem_zoom(&mut self) {
         if let Some(mem_widget_state) = self
             .mem_state
@@ -2295,6 +2630,42 @@ impl App {
         }
     }
 
+    fn reset_pressure_zoom(&mut self) {
+        if let Some(pressure_widget_state) = self
+            .pressure_state
+            .widget_states
+            .get_mut(&self.current_widget.widget_id)
+        {
+            pressure_widget_state.current_display_time = self.app_config_fields.default_time_value;
+            if self.app_config_fields.autohide_time {
+                pressure_widget_state.autohide_timer = Some(Instant::now());
+            }
+        }
+    }
+
+    fn reset_kernel_zoom(&mut self) {
+        if let Some(kernel_widget_state) = self
+            .kernel_state
+            .widget_states
+            .get_mut(&self.current_widget.widget_id)
+        {
+            kernel_widget_state.current_display_time = self.app_config_fields.default_time_value;
+        }
+    }
+
+    fn reset_disk_io_zoom(&mut self) {
+        if let Some(disk_io_widget_state) = self
+            .disk_io_state
+            .widget_states
+            .get_mut(&self.current_widget.widget_id)
+        {
+            disk_io_widget_state.current_display_time = self.app_config_fields.default_time_value;
+            if self.app_config_fields.autohide_time {
+                disk_io_widget_state.autohide_timer = Some(Instant::now());
+            }
+        }
+    }
+
     fn reset_net_zoom(&mut self) {
         if let Some(net_widget_state) = self
             .net_state
@@ -2312,7 +2683,11 @@ impl App {
     fn reset_zoom(&mut self) {
         match self.current_widget.widget_type {
             BottomWidgetType::Cpu => self.reset_cpu_zoom(),
+            BottomWidgetType::CpuHeatmap => self.reset_cpu_heatmap_zoom(),
             BottomWidgetType::Mem => self.reset_mem_zoom(),
+            BottomWidgetType::Pressure => self.reset_pressure_zoom(),
+            BottomWidgetType::Kernel => self.reset_kernel_zoom(),
+            BottomWidgetType::DiskIo => self.reset_disk_io_zoom(),
             BottomWidgetType::Net => self.reset_net_zoom(),
             _ => {}
         }
@@ -13,36 +13,57 @@
 //! memory usage and higher CPU usage - you will be trying to process more and
 //! more points as this is used!
 
-use once_cell::sync::Lazy;
-
 use fxhash::FxHashMap;
 use itertools::Itertools;
 
-use std::{time::Instant, vec::Vec};
+use std::{
+    time::{Duration, Instant},
+    vec::Vec,
+};
 
 #[cfg(feature = "battery")]
 use crate::data_harvester::batteries;
 
 use crate::{
-    data_harvester::{cpu, disks, memory, network, processes::ProcessHarvest, temperature, Data},
-    utils::gen_util::{get_decimal_bytes, GIGA_LIMIT},
+    data_conversion::dec_bytes_per_second_string,
+    data_harvester::{
+        cgroup, cpu, disks, kernel, memory, network, pressure, processes::ProcessHarvest,
+        temperature, Data,
+    },
     Pid,
 };
-use regex::Regex;
 
 pub type TimeOffset = f64;
 pub type Value = f64;
 
+/// How often the free space of each disk is sampled for forecasting.
+const FREE_SPACE_SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+
 #[derive(Debug, Default, Clone)]
 pub struct TimedData {
     pub rx_data: Value,
     pub tx_data: Value,
     pub cpu_data: Vec<Value>,
+    pub cpu_state_data: Vec<Option<cpu::CpuStates>>,
+    pub cpu_freq_data: Vec<Option<Value>>,
     pub load_avg_data: [f32; 3],
+    /// The stall percentages of each of [`pressure::PressureResource::ALL`], if available.
+    pub pressure_data: [Option<pressure::PressureStall>; 3],
+    pub kernel_data: Option<kernel::KernelActivity>,
     pub mem_data: Option<Value>,
+    pub mem_breakdown_data: Option<memory::MemBreakdown>,
     pub swap_data: Option<Value>,
+    pub paging_data: Option<memory::paging::PagingActivity>,
+    pub numa_data: Vec<Option<Value>>,
+    /// The RAM used by each store of compressed memory, as a percentage of all RAM.
+    pub compressed_data: Vec<Option<Value>>,
+    pub huge_pages_data: Option<Value>,
+    /// The read and write rates of the devices backing each disk, keyed by device name.
+    pub io_data: FxHashMap<String, disks::IoRate>,
     #[cfg(feature = "zfs")]
     pub arc_data: Option<Value>,
+    #[cfg(feature = "zfs")]
+    pub arc_stats_data: Option<memory::arc::ArcActivity>,
     #[cfg(feature = "gpu")]
     pub gpu_data: Vec<Option<Value>>,
 }
@@ -123,18 +144,36 @@ pub struct DataCollection {
     pub network_harvest: network::NetworkHarvest,
     pub memory_harvest: memory::MemHarvest,
     pub swap_harvest: memory::MemHarvest,
+    pub paging_harvest: Option<memory::paging::PagingHarvest>,
+    pub numa_harvest: Vec<(usize, memory::MemHarvest)>,
+    pub compressed_harvest: Vec<memory::compression::CompressedHarvest>,
+    pub huge_pages_harvest: Option<memory::huge_pages::HugePagesHarvest>,
     pub cpu_harvest: cpu::CpuHarvest,
     pub load_avg_harvest: cpu::LoadAvgHarvest,
+    pub pressure_harvest: Option<pressure::PressureHarvest>,
+    pub kernel_harvest: Option<kernel::KernelHarvest>,
+    /// The limits of the container that bottom runs in, if memory and CPU usage are measured
+    /// against them.
+    pub cgroup_harvest: Option<cgroup::CgroupHarvest>,
     pub process_data: ProcessData,
     pub disk_harvest: Vec<disks::DiskHarvest>,
+    /// The latest IO counters of each device, keyed by device name.  The next harvest's rates are
+    /// worked out from these, so devices that stop being reported are dropped along with them.
     pub io_harvest: disks::IoHarvest,
-    pub io_labels_and_prev: Vec<((u64, u64), (u64, u64))>,
+    /// The read and write rate labels of each disk, in the same order as `disk_harvest`.
     pub io_labels: Vec<(String, String)>,
+    /// The block devices that disks are built on, which are only collected on Linux.
+    pub block_harvest: Vec<disks::BlockDevice>,
+    /// Samples of the free space of each mount point over time, used to forecast when it will
+    /// fill up.
+    pub free_space_history: FxHashMap<String, Vec<(Instant, u64)>>,
     pub temp_harvest: Vec<temperature::TempHarvest>,
     #[cfg(feature = "battery")]
     pub battery_harvest: Vec<batteries::BatteryHarvest>,
     #[cfg(feature = "zfs")]
     pub arc_harvest: memory::MemHarvest,
+    #[cfg(feature = "zfs")]
+    pub arc_stats_harvest: Option<memory::arc::ArcHarvest>,
     #[cfg(feature = "gpu")]
     pub gpu_harvest: Vec<(String, memory::MemHarvest)>,
 }
@@ -147,18 +186,28 @@ impl Default for DataCollection {
             network_harvest: network::NetworkHarvest::default(),
             memory_harvest: memory::MemHarvest::default(),
             swap_harvest: memory::MemHarvest::default(),
+            paging_harvest: None,
+            numa_harvest: Vec::default(),
+            compressed_harvest: Vec::default(),
+            huge_pages_harvest: None,
             cpu_harvest: cpu::CpuHarvest::default(),
             load_avg_harvest: cpu::LoadAvgHarvest::default(),
+            pressure_harvest: None,
+            kernel_harvest: None,
+            cgroup_harvest: None,
             process_data: Default::default(),
             disk_harvest: Vec::default(),
             io_harvest: disks::IoHarvest::default(),
-            io_labels_and_prev: Vec::default(),
             io_labels: Vec::default(),
+            block_harvest: Vec::default(),
+            free_space_history: FxHashMap::default(),
             temp_harvest: Vec::default(),
             #[cfg(feature = "battery")]
             battery_harvest: Vec::default(),
             #[cfg(feature = "zfs")]
             arc_harvest: memory::MemHarvest::default(),
+            #[cfg(feature = "zfs")]
+            arc_stats_harvest: None,
             #[cfg(feature = "gpu")]
             gpu_harvest: Vec::default(),
         }
@@ -171,11 +220,20 @@ impl DataCollection {
         self.network_harvest = network::NetworkHarvest::default();
         self.memory_harvest = memory::MemHarvest::default();
         self.swap_harvest = memory::MemHarvest::default();
+        self.paging_harvest = None;
+        self.numa_harvest = Vec::default();
+        self.compressed_harvest = Vec::default();
+        self.huge_pages_harvest = None;
         self.cpu_harvest = cpu::CpuHarvest::default();
+        self.pressure_harvest = None;
+        self.kernel_harvest = None;
+        self.cgroup_harvest = None;
         self.process_data = Default::default();
         self.disk_harvest = Vec::default();
         self.io_harvest = disks::IoHarvest::default();
-        self.io_labels_and_prev = Vec::default();
+        self.io_labels = Vec::default();
+        self.block_harvest = Vec::default();
+        self.free_space_history = FxHashMap::default();
         self.temp_harvest = Vec::default();
         #[cfg(feature = "battery")]
         {
@@ -184,6 +242,7 @@ impl DataCollection {
         #[cfg(feature = "zfs")]
         {
             self.arc_harvest = memory::MemHarvest::default();
+            self.arc_stats_harvest = None;
         }
         #[cfg(feature = "gpu")]
         {
@@ -210,6 +269,19 @@ impl DataCollection {
         self.timed_data_vec.drain(0..remove_index);
     }
 
+    /// Drops the free space samples that are older than the forecast window, along with mount
+    /// points that have no samples left.
+    pub fn clean_free_space_history(&mut self, forecast_window: Duration) {
+        let current_time = Instant::now();
+
+        self.free_space_history.retain(|_mount_point, history| {
+            history.retain(|(instant, _free)| {
+                current_time.duration_since(*instant) <= forecast_window
+            });
+            !history.is_empty()
+        });
+    }
+
     pub fn eat_data(&mut self, harvested_data: Box<Data>) {
         let harvested_time = harvested_data.last_collection_time;
         // trace!("Harvested time: {:?}", harvested_time);
@@ -226,11 +298,35 @@ impl DataCollection {
             self.eat_memory_and_swap(memory, swap, &mut new_entry);
         }
 
+        // Paging
+        if let Some(paging) = harvested_data.paging {
+            self.eat_paging(paging, harvested_time, &mut new_entry);
+        }
+
+        // NUMA nodes
+        if let Some(numa) = harvested_data.numa {
+            self.eat_numa(numa, &mut new_entry);
+        }
+
+        // Compressed memory
+        if let Some(compressed) = harvested_data.compressed {
+            self.eat_compressed(compressed, &mut new_entry);
+        }
+
+        // Huge pages
+        if let Some(huge_pages) = harvested_data.huge_pages {
+            self.eat_huge_pages(huge_pages, &mut new_entry);
+        }
+
         #[cfg(feature = "zfs")]
         {
             if let Some(arc) = harvested_data.arc {
                 self.eat_arc(arc, &mut new_entry);
             }
+
+            if let Some(arc_stats) = harvested_data.arc_stats {
+                self.eat_arc_stats(arc_stats, harvested_time, &mut new_entry);
+            }
         }
 
         #[cfg(feature = "gpu")]
@@ -250,6 +346,19 @@ impl DataCollection {
             self.eat_load_avg(load_avg, &mut new_entry);
         }
 
+        // Pressure
+        if let Some(pressure) = harvested_data.pressure {
+            self.eat_pressure(pressure, harvested_time, &mut new_entry);
+        }
+
+        // Kernel activity
+        if let Some(kernel) = harvested_data.kernel {
+            self.eat_kernel(kernel, harvested_time, &mut new_entry);
+        }
+
+        // Container limits
+        self.cgroup_harvest = harvested_data.cgroup;
+
         // Temp
         if let Some(temperature_sensors) = harvested_data.temperature_sensors {
             self.eat_temp(temperature_sensors);
@@ -258,9 +367,12 @@ impl DataCollection {
         // Disks
         if let Some(disks) = harvested_data.disks {
             if let Some(io) = harvested_data.io {
-                self.eat_disks(disks, io, harvested_time);
+                self.eat_disks(disks, io, harvested_time, &mut new_entry);
             }
         }
+        if let Some(block_devices) = harvested_data.block_devices {
+            self.block_harvest = block_devices;
+        }
 
         // Processes
         if let Some(list_of_processes) = harvested_data.list_of_processes {
@@ -285,6 +397,7 @@ impl DataCollection {
     ) {
         // Memory
         new_entry.mem_data = memory.use_percent;
+        new_entry.mem_breakdown_data = memory.breakdown;
 
         // Swap
         new_entry.swap_data = swap.use_percent;
@@ -294,6 +407,52 @@ impl DataCollection {
         self.swap_harvest = swap;
     }
 
+    fn eat_paging(
+        &mut self, paging: memory::paging::PagingHarvest, harvested_time: Instant,
+        new_entry: &mut TimedData,
+    ) {
+        // The counters are cumulative, so the first harvest only gives us a baseline.
+        if let Some(previous) = &self.paging_harvest {
+            let elapsed = harvested_time.duration_since(self.current_instant);
+            new_entry.paging_data = Some(paging.activity_since(previous, elapsed));
+        }
+
+        self.paging_harvest = Some(paging);
+    }
+
+    fn eat_numa(&mut self, numa: Vec<(usize, memory::MemHarvest)>, new_entry: &mut TimedData) {
+        new_entry.numa_data = numa.iter().map(|(_, mem)| mem.use_percent).collect();
+
+        self.numa_harvest = numa;
+    }
+
+    fn eat_compressed(
+        &mut self, compressed: Vec<memory::compression::CompressedHarvest>,
+        new_entry: &mut TimedData,
+    ) {
+        let mem_total_in_kib = self.memory_harvest.mem_total_in_kib;
+        new_entry.compressed_data = compressed
+            .iter()
+            .map(|compressed| {
+                if mem_total_in_kib > 0 {
+                    Some(compressed.compressed_in_kib as f64 / mem_total_in_kib as f64 * 100.0)
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        self.compressed_harvest = compressed;
+    }
+
+    fn eat_huge_pages(
+        &mut self, huge_pages: memory::huge_pages::HugePagesHarvest, new_entry: &mut TimedData,
+    ) {
+        new_entry.huge_pages_data = huge_pages.use_percent();
+
+        self.huge_pages_harvest = Some(huge_pages);
+    }
+
     fn eat_network(&mut self, network: network::NetworkHarvest, new_entry: &mut TimedData) {
         // RX
         if network.rx > 0 {
@@ -313,8 +472,13 @@ impl DataCollection {
         // Note this only pre-calculates the data points - the names will be
         // within the local copy of cpu_harvest.  Since it's all sequential
         // it probably doesn't matter anyways.
-        cpu.iter()
-            .for_each(|cpu| new_entry.cpu_data.push(cpu.cpu_usage));
+        cpu.iter().for_each(|cpu| {
+            new_entry.cpu_data.push(cpu.cpu_usage);
+            new_entry.cpu_state_data.push(cpu.state_usage);
+            new_entry
+                .cpu_freq_data
+                .push(cpu.frequency.map(|frequency| frequency.current as f64));
+        });
 
         self.cpu_harvest = cpu.to_vec();
     }
@@ -325,6 +489,51 @@ impl DataCollection {
         self.load_avg_harvest = load_avg;
     }
 
+    fn eat_pressure(
+        &mut self, pressure: pressure::PressureHarvest, harvested_time: Instant,
+        new_entry: &mut TimedData,
+    ) {
+        // The kernel's own averages only update every two seconds, so work out how long tasks were
+        // stalled since the last harvest from the totals instead.
+        let elapsed = harvested_time.duration_since(self.current_instant);
+        let previous = self.pressure_harvest.as_ref();
+
+        for (resource, stall) in pressure::PressureResource::ALL
+            .iter()
+            .zip(new_entry.pressure_data.iter_mut())
+        {
+            if let Some(current) = pressure.get(*resource) {
+                let previous = previous.and_then(|previous| previous.get(*resource));
+                *stall = Some(pressure::PressureStall {
+                    some: current
+                        .some
+                        .stall_since(previous.map(|previous| &previous.some), elapsed),
+                    full: current.full.map(|full| {
+                        full.stall_since(
+                            previous.and_then(|previous| previous.full.as_ref()),
+                            elapsed,
+                        )
+                    }),
+                });
+            }
+        }
+
+        self.pressure_harvest = Some(pressure);
+    }
+
+    fn eat_kernel(
+        &mut self, kernel: kernel::KernelHarvest, harvested_time: Instant,
+        new_entry: &mut TimedData,
+    ) {
+        // Most of the counters are cumulative, so the first harvest only gives us a baseline.
+        if let Some(previous) = &self.kernel_harvest {
+            let elapsed = harvested_time.duration_since(self.current_instant);
+            new_entry.kernel_data = Some(kernel.activity_since(previous, elapsed));
+        }
+
+        self.kernel_harvest = Some(kernel);
+    }
+
     fn eat_temp(&mut self, temperature_sensors: Vec<temperature::TempHarvest>) {
         // TODO: [PO] To implement
         self.temp_harvest = temperature_sensors.to_vec();
@@ -332,82 +541,66 @@ impl DataCollection {
 
     fn eat_disks(
         &mut self, disks: Vec<disks::DiskHarvest>, io: disks::IoHarvest, harvested_time: Instant,
+        new_entry: &mut TimedData,
     ) {
-        // TODO: [PO] To implement
-
-        let time_since_last_harvest = harvested_time
-            .duration_since(self.current_instant)
-            .as_secs_f64();
-
-        for (itx, device) in disks.iter().enumerate() {
-            if let Some(trim) = device.name.split('/').last() {
-                let io_device = if cfg!(target_os = "macos") {
-                    // Must trim one level further for macOS!
-                    static DISK_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"disk\d+").unwrap());
-                    if let Some(disk_trim) = DISK_REGEX.find(trim) {
-                        io.get(disk_trim.as_str())
-                    } else {
-                        None
-                    }
-                } else {
-                    io.get(trim)
-                };
-
-                if let Some(io_device) = io_device {
-                    let (io_r_pt, io_w_pt) = if let Some(io) = io_device {
-                        (io.read_bytes, io.write_bytes)
-                    } else {
-                        (0, 0)
-                    };
-
-                    if self.io_labels.len() <= itx {
-                        self.io_labels.push((String::default(), String::default()));
-                    }
-
-                    if self.io_labels_and_prev.len() <= itx {
-                        self.io_labels_and_prev.push(((0, 0), (io_r_pt, io_w_pt)));
-                    }
-
-                    if let Some((io_curr, io_prev)) = self.io_labels_and_prev.get_mut(itx) {
-                        let r_rate = ((io_r_pt.saturating_sub(io_prev.0)) as f64
-                            / time_since_last_harvest)
-                            .round() as u64;
-                        let w_rate = ((io_w_pt.saturating_sub(io_prev.1)) as f64
-                            / time_since_last_harvest)
-                            .round() as u64;
-
-                        *io_curr = (r_rate, w_rate);
-                        *io_prev = (io_r_pt, io_w_pt);
-
-                        if let Some(io_labels) = self.io_labels.get_mut(itx) {
-                            let converted_read = get_decimal_bytes(r_rate);
-                            let converted_write = get_decimal_bytes(w_rate);
-                            *io_labels = (
-                                if r_rate >= GIGA_LIMIT {
-                                    format!("{:.*}{}/s", 1, converted_read.0, converted_read.1)
-                                } else {
-                                    format!("{:.*}{}/s", 0, converted_read.0, converted_read.1)
-                                },
-                                if w_rate >= GIGA_LIMIT {
-                                    format!("{:.*}{}/s", 1, converted_write.0, converted_write.1)
-                                } else {
-                                    format!("{:.*}{}/s", 0, converted_write.0, converted_write.1)
-                                },
-                            );
-                        }
-                    }
-                } else {
-                    if self.io_labels.len() <= itx {
-                        self.io_labels.push((String::default(), String::default()));
+        let elapsed = harvested_time.duration_since(self.current_instant);
+
+        // Rates are worked out per device rather than per row, so disks being mounted, unmounted,
+        // or reordered between harvests can't mix up their counters.  A device that wasn't in the
+        // previous harvest only gives us a baseline.
+        let io_labels = disks
+            .iter()
+            .map(|disk| {
+                let io_device = disks::io_device_name(&disk.name)
+                    .and_then(|name| io.get_key_value(name))
+                    .map(|(name, counters)| (name, counters.as_ref()));
+
+                match io_device {
+                    Some((name, Some(current))) => {
+                        let rate = match self.io_harvest.get(name) {
+                            Some(Some(previous)) => {
+                                let rate = current.rate_since(previous, elapsed);
+                                new_entry.io_data.insert(name.clone(), rate);
+                                rate
+                            }
+                            _ => disks::IoRate::default(),
+                        };
+
+                        (
+                            dec_bytes_per_second_string(rate.read.round() as u64),
+                            dec_bytes_per_second_string(rate.write.round() as u64),
+                        )
                     }
+                    Some((_, None)) => (
+                        dec_bytes_per_second_string(0),
+                        dec_bytes_per_second_string(0),
+                    ),
+                    None => ("N/A".to_string(), "N/A".to_string()),
+                }
+            })
+            .collect();
 
-                    if let Some(io_labels) = self.io_labels.get_mut(itx) {
-                        *io_labels = ("N/A".to_string(), "N/A".to_string());
+        // Free space changes slowly compared to how often it is harvested, so it is only sampled
+        // every so often to keep a long forecast window from holding too many samples.
+        for disk in &disks {
+            if let Some(free_space) = disk.free_space {
+                let history = self
+                    .free_space_history
+                    .entry(disk.mount_point.clone())
+                    .or_default();
+                let is_due = match history.last() {
+                    Some((last_time, _)) => {
+                        harvested_time.duration_since(*last_time) >= FREE_SPACE_SAMPLE_INTERVAL
                     }
+                    None => true,
+                };
+                if is_due {
+                    history.push((harvested_time, free_space));
                 }
             }
         }
 
+        self.io_labels = io_labels;
         self.disk_harvest = disks;
         self.io_harvest = io;
     }
@@ -427,6 +620,20 @@ impl DataCollection {
         self.arc_harvest = arc;
     }
 
+    #[cfg(feature = "zfs")]
+    fn eat_arc_stats(
+        &mut self, arc_stats: memory::arc::ArcHarvest, harvested_time: Instant,
+        new_entry: &mut TimedData,
+    ) {
+        // The hits and misses are cumulative, so the first harvest only gives us a baseline.
+        if let Some(previous) = &self.arc_stats_harvest {
+            let elapsed = harvested_time.duration_since(self.current_instant);
+            new_entry.arc_stats_data = Some(arc_stats.activity_since(previous, elapsed));
+        }
+
+        self.arc_stats_harvest = Some(arc_stats);
+    }
+
     #[cfg(feature = "gpu")]
     fn eat_gpu(&mut self, gpu: Vec<(String, memory::MemHarvest)>, new_entry: &mut TimedData) {
         // Note this only pre-calculates the data points - the names will be
@@ -438,3 +645,124 @@ impl DataCollection {
         self.gpu_harvest = gpu.to_vec();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn disk(name: &str) -> disks::DiskHarvest {
+        disks::DiskHarvest {
+            name: format!("/dev/{}", name),
+            mount_point: format!("/mnt/{}", name),
+            ..Default::default()
+        }
+    }
+
+    fn io(counters: &[(&str, u64, u64)]) -> disks::IoHarvest {
+        counters
+            .iter()
+            .map(|&(name, read_bytes, write_bytes)| {
+                (
+                    name.to_string(),
+                    Some(disks::IoData {
+                        read_bytes,
+                        write_bytes,
+                        stats: None,
+                    }),
+                )
+            })
+            .collect()
+    }
+
+    /// Eats a harvest of disks and their counters from one second after the previous one.
+    fn eat(
+        data: &mut DataCollection, disks: Vec<disks::DiskHarvest>, io: disks::IoHarvest,
+    ) -> TimedData {
+        let harvested_time = data.current_instant + Duration::from_secs(1);
+        let mut new_entry = TimedData::default();
+        data.eat_disks(disks, io, harvested_time, &mut new_entry);
+        data.current_instant = harvested_time;
+
+        new_entry
+    }
+
+    fn labels(data: &DataCollection) -> Vec<(&str, &str)> {
+        data.io_labels
+            .iter()
+            .map(|(read, write)| (read.as_str(), write.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_io_rates_follow_reordered_disks() {
+        let mut data = DataCollection::default();
+        eat(
+            &mut data,
+            vec![disk("disk0"), disk("disk1")],
+            io(&[("disk0", 1000, 0), ("disk1", 0, 0)]),
+        );
+
+        let entry = eat(
+            &mut data,
+            vec![disk("disk1"), disk("disk0")],
+            io(&[("disk0", 3000, 0), ("disk1", 0, 500)]),
+        );
+        assert_eq!(labels(&data), vec![("0B/s", "500B/s"), ("2KB/s", "0B/s")]);
+        assert_eq!(entry.io_data["disk0"].read, 2000.0);
+        assert_eq!(entry.io_data["disk1"].write, 500.0);
+    }
+
+    #[test]
+    fn test_io_rates_of_new_disk() {
+        let mut data = DataCollection::default();
+        eat(&mut data, vec![disk("disk0")], io(&[("disk0", 0, 0)]));
+
+        // A disk that appears with a lot already read shouldn't show it all as one spike.
+        let entry = eat(
+            &mut data,
+            vec![disk("disk0"), disk("disk1")],
+            io(&[("disk0", 1000, 0), ("disk1", 5_000_000, 0)]),
+        );
+        assert_eq!(labels(&data), vec![("1KB/s", "0B/s"), ("0B/s", "0B/s")]);
+        assert!(!entry.io_data.contains_key("disk1"));
+
+        let entry = eat(
+            &mut data,
+            vec![disk("disk0"), disk("disk1")],
+            io(&[("disk0", 1000, 0), ("disk1", 5_001_000, 0)]),
+        );
+        assert_eq!(labels(&data), vec![("0B/s", "0B/s"), ("1KB/s", "0B/s")]);
+        assert_eq!(entry.io_data["disk1"].read, 1000.0);
+    }
+
+    #[test]
+    fn test_io_rates_of_removed_disk() {
+        let mut data = DataCollection::default();
+        eat(
+            &mut data,
+            vec![disk("disk0"), disk("disk1")],
+            io(&[("disk0", 1000, 0), ("disk1", 1000, 0)]),
+        );
+
+        let entry = eat(&mut data, vec![disk("disk1")], io(&[("disk1", 2000, 0)]));
+        assert_eq!(labels(&data), vec![("1KB/s", "0B/s")]);
+        assert!(!data.io_harvest.contains_key("disk0"));
+        assert!(!entry.io_data.contains_key("disk0"));
+
+        // Plugging a device back in restarts its counters, which shouldn't be compared against the
+        // ones from before it was removed.
+        let entry = eat(
+            &mut data,
+            vec![disk("disk0"), disk("disk1")],
+            io(&[("disk0", 10, 0), ("disk1", 2000, 0)]),
+        );
+        assert_eq!(labels(&data), vec![("0B/s", "0B/s"), ("0B/s", "0B/s")]);
+        assert!(!entry.io_data.contains_key("disk0"));
+
+        // Disks without any counters at all are still shown.
+        eat(&mut data, vec![disk("disk2")], io(&[]));
+        assert_eq!(labels(&data), vec![("N/A", "N/A")]);
+    }
+}
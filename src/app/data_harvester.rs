@@ -22,10 +22,13 @@ pub mod nvidia;
 
 #[cfg(feature = "battery")]
 pub mod batteries;
+pub mod cgroup;
 pub mod cpu;
 pub mod disks;
+pub mod kernel;
 pub mod memory;
 pub mod network;
+pub mod pressure;
 pub mod processes;
 pub mod temperature;
 
@@ -36,15 +39,25 @@ pub struct Data {
     pub load_avg: Option<cpu::LoadAvgHarvest>,
     pub memory: Option<memory::MemHarvest>,
     pub swap: Option<memory::MemHarvest>,
+    pub paging: Option<memory::paging::PagingHarvest>,
+    pub numa: Option<Vec<(usize, memory::MemHarvest)>>,
+    pub compressed: Option<Vec<memory::compression::CompressedHarvest>>,
+    pub huge_pages: Option<memory::huge_pages::HugePagesHarvest>,
     pub temperature_sensors: Option<Vec<temperature::TempHarvest>>,
     pub network: Option<network::NetworkHarvest>,
     pub list_of_processes: Option<Vec<processes::ProcessHarvest>>,
     pub disks: Option<Vec<disks::DiskHarvest>>,
     pub io: Option<disks::IoHarvest>,
+    pub block_devices: Option<Vec<disks::BlockDevice>>,
+    pub pressure: Option<pressure::PressureHarvest>,
+    pub kernel: Option<kernel::KernelHarvest>,
+    pub cgroup: Option<cgroup::CgroupHarvest>,
     #[cfg(feature = "battery")]
     pub list_of_batteries: Option<Vec<batteries::BatteryHarvest>>,
     #[cfg(feature = "zfs")]
     pub arc: Option<memory::MemHarvest>,
+    #[cfg(feature = "zfs")]
+    pub arc_stats: Option<memory::arc::ArcHarvest>,
     #[cfg(feature = "gpu")]
     pub gpu: Option<Vec<(String, memory::MemHarvest)>>,
 }
@@ -57,15 +70,25 @@ impl Default for Data {
             load_avg: None,
             memory: None,
             swap: None,
+            paging: None,
+            numa: None,
+            compressed: None,
+            huge_pages: None,
             temperature_sensors: None,
             list_of_processes: None,
             disks: None,
             io: None,
+            block_devices: None,
             network: None,
+            pressure: None,
+            kernel: None,
+            cgroup: None,
             #[cfg(feature = "battery")]
             list_of_batteries: None,
             #[cfg(feature = "zfs")]
             arc: None,
+            #[cfg(feature = "zfs")]
+            arc_stats: None,
             #[cfg(feature = "gpu")]
             gpu: None,
         }
@@ -78,10 +101,18 @@ impl Data {
         self.temperature_sensors = None;
         self.list_of_processes = None;
         self.disks = None;
+        self.block_devices = None;
         self.memory = None;
         self.swap = None;
+        self.paging = None;
+        self.numa = None;
+        self.compressed = None;
+        self.huge_pages = None;
         self.cpu = None;
         self.load_avg = None;
+        self.pressure = None;
+        self.kernel = None;
+        self.cgroup = None;
 
         if let Some(network) = &mut self.network {
             network.first_run_cleanup();
@@ -89,6 +120,7 @@ impl Data {
         #[cfg(feature = "zfs")]
         {
             self.arc = None;
+            self.arc_stats = None;
         }
         #[cfg(feature = "gpu")]
         {
@@ -102,8 +134,8 @@ pub struct DataCollector {
     pub data: Data,
     #[cfg(not(target_os = "linux"))]
     sys: System,
-    previous_cpu_times: Vec<(cpu::PastCpuWork, cpu::PastCpuTotal)>,
-    previous_average_cpu_time: Option<(cpu::PastCpuWork, cpu::PastCpuTotal)>,
+    previous_cpu_times: Vec<cpu::PastCpuTimes>,
+    previous_average_cpu_time: Option<cpu::PastCpuTimes>,
     #[cfg(target_os = "linux")]
     pid_mapping: FxHashMap<crate::Pid, processes::PrevProcDetails>,
     #[cfg(target_os = "linux")]
@@ -117,6 +149,14 @@ pub struct DataCollector {
     total_rx: u64,
     total_tx: u64,
     show_average_cpu: bool,
+    cpu_grouping: Option<cpu::CpuGrouping>,
+    cpu_topology: Vec<cpu::topology::CpuTopology>,
+    use_container_limits: bool,
+    show_all_mounts: bool,
+    #[cfg(target_os = "linux")]
+    cgroup: Option<cgroup::Cgroup>,
+    #[cfg(target_os = "linux")]
+    previous_cgroup: Option<cgroup::CgroupHarvest>,
     widgets_to_harvest: UsedWidgets,
     #[cfg(feature = "battery")]
     battery_manager: Option<Manager>,
@@ -149,6 +189,14 @@ impl DataCollector {
             total_rx: 0,
             total_tx: 0,
             show_average_cpu: false,
+            cpu_grouping: None,
+            cpu_topology: vec![],
+            use_container_limits: false,
+            show_all_mounts: false,
+            #[cfg(target_os = "linux")]
+            cgroup: None,
+            #[cfg(target_os = "linux")]
+            previous_cgroup: None,
             widgets_to_harvest: UsedWidgets::default(),
             #[cfg(feature = "battery")]
             battery_manager: None,
@@ -161,6 +209,17 @@ impl DataCollector {
     }
 
     pub fn init(&mut self) {
+        if self.cpu_grouping.is_some() && self.widgets_to_harvest.use_cpu {
+            self.cpu_topology = cpu::topology::read_cpu_topology();
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            if self.use_container_limits {
+                self.cgroup = cgroup::Cgroup::find();
+            }
+        }
+
         #[cfg(target_os = "linux")]
         {
             futures::executor::block_on(self.initialize_memory_size());
@@ -240,6 +299,18 @@ impl DataCollector {
         self.show_average_cpu = show_average_cpu;
     }
 
+    pub fn set_cpu_grouping(&mut self, cpu_grouping: Option<cpu::CpuGrouping>) {
+        self.cpu_grouping = cpu_grouping;
+    }
+
+    pub fn set_use_container_limits(&mut self, use_container_limits: bool) {
+        self.use_container_limits = use_container_limits;
+    }
+
+    pub fn set_show_all_mounts(&mut self, show_all_mounts: bool) {
+        self.show_all_mounts = show_all_mounts;
+    }
+
     pub async fn update_data(&mut self) {
         #[cfg(not(target_os = "linux"))]
         {
@@ -265,6 +336,10 @@ impl DataCollector {
 
         let current_instant = std::time::Instant::now();
 
+        // Container limits
+        #[cfg(target_os = "linux")]
+        let cgroup_harvest = self.cgroup.as_ref().map(cgroup::Cgroup::read);
+
         // CPU
         if self.widgets_to_harvest.use_cpu {
             #[cfg(not(target_os = "freebsd"))]
@@ -293,6 +368,28 @@ impl DataCollector {
                 }
             }
 
+            // Frequency
+            if let Some(cpu_data) = &mut self.data.cpu {
+                #[cfg(target_os = "linux")]
+                cpu::frequency::set_cpu_frequencies(cpu_data);
+                #[cfg(not(target_os = "linux"))]
+                cpu::frequency::set_cpu_frequencies(cpu_data, &self.sys);
+            }
+
+            // Container limits, before grouping so that groups only cover the cores in use.
+            #[cfg(target_os = "linux")]
+            if let Some(cgroup_harvest) = &cgroup_harvest {
+                self.limit_cpu_data(
+                    cgroup_harvest,
+                    current_instant.duration_since(self.last_collection_time),
+                );
+            }
+
+            // Grouping
+            if let (Some(cpu_data), Some(cpu_grouping)) = (&mut self.data.cpu, self.cpu_grouping) {
+                cpu::topology::group_cpu_data(cpu_data, &self.cpu_topology, cpu_grouping);
+            }
+
             #[cfg(target_family = "unix")]
             {
                 // Load Average
@@ -317,6 +414,12 @@ impl DataCollector {
             if let Ok(process_list) = {
                 #[cfg(target_os = "linux")]
                 {
+                    // Memory usage is relative to the container's limit, if there is one.
+                    let mem_total_kb = match &cgroup_harvest {
+                        Some(cgroup_harvest) => cgroup_harvest.memory_total(self.mem_total_kb),
+                        None => self.mem_total_kb,
+                    };
+
                     processes::get_process_data(
                         &mut self.prev_idle,
                         &mut self.prev_non_idle,
@@ -325,7 +428,7 @@ impl DataCollector {
                         current_instant
                             .duration_since(self.last_collection_time)
                             .as_secs(),
-                        self.mem_total_kb,
+                        mem_total_kb,
                         &mut self.user_table,
                     )
                 }
@@ -377,6 +480,39 @@ impl DataCollector {
             }
         }
 
+        // Pressure
+        #[cfg(target_os = "linux")]
+        {
+            if self.widgets_to_harvest.use_pressure {
+                self.data.pressure = pressure::get_pressure_data();
+            }
+        }
+
+        // Paging and NUMA nodes
+        #[cfg(target_os = "linux")]
+        {
+            if self.widgets_to_harvest.use_mem {
+                self.data.paging = memory::paging::get_paging_data();
+                self.data.numa = memory::numa::get_numa_data();
+            }
+        }
+
+        // ARC details
+        #[cfg(all(feature = "zfs", any(target_os = "linux", target_os = "freebsd")))]
+        {
+            if self.widgets_to_harvest.use_mem {
+                self.data.arc_stats = memory::arc::get_arc_stats();
+            }
+        }
+
+        // Kernel activity
+        #[cfg(target_os = "linux")]
+        {
+            if self.widgets_to_harvest.use_kernel {
+                self.data.kernel = kernel::get_kernel_data();
+            }
+        }
+
         let network_data_fut = {
             #[cfg(any(target_os = "windows", target_os = "freebsd"))]
             {
@@ -421,8 +557,10 @@ impl DataCollector {
         };
         let disk_data_fut = disks::get_disk_usage(
             self.widgets_to_harvest.use_disk,
+            self.show_all_mounts,
             &self.filters.disk_filter,
             &self.filters.mount_filter,
+            &self.filters.fs_filter,
         );
         let disk_io_usage_fut = disks::get_io_usage(self.widgets_to_harvest.use_disk);
 
@@ -445,6 +583,25 @@ impl DataCollector {
             self.data.memory = memory;
         }
 
+        // Compressed memory and huge pages, which come from the RAM breakdown before it is
+        // limited to the cgroup.
+        #[cfg(target_os = "linux")]
+        {
+            if self.widgets_to_harvest.use_mem {
+                let breakdown = self
+                    .data
+                    .memory
+                    .as_ref()
+                    .and_then(|memory| memory.breakdown);
+                self.data.compressed =
+                    Some(memory::compression::get_compressed_data(breakdown.as_ref()));
+                self.data.huge_pages = breakdown.and_then(|breakdown| breakdown.huge_pages);
+            }
+        }
+
+        #[cfg(target_os = "linux")]
+        self.set_cgroup_data(cgroup_harvest);
+
         if let Ok(swap) = mem_res.swap {
             self.data.swap = swap;
         }
@@ -467,10 +624,45 @@ impl DataCollector {
             self.data.io = io;
         }
 
+        #[cfg(target_os = "linux")]
+        if self.widgets_to_harvest.use_disk {
+            self.data.block_devices = Some(disks::get_block_devices());
+        }
+
         // Update time
         self.data.last_collection_time = current_instant;
         self.last_collection_time = current_instant;
     }
+
+    /// Limits the CPU data to the cores the cgroup may run on, with the average replaced by its
+    /// usage of its CPU limit since the previous reading.
+    #[cfg(target_os = "linux")]
+    fn limit_cpu_data(
+        &mut self, cgroup_harvest: &cgroup::CgroupHarvest, elapsed: std::time::Duration,
+    ) {
+        if let Some(cpu_data) = &mut self.data.cpu {
+            let num_cores = cpu_data
+                .iter()
+                .filter(|cpu| matches!(cpu.data_type, cpu::CpuDataType::Cpu(_)))
+                .count();
+            let usage = self
+                .previous_cgroup
+                .as_ref()
+                .and_then(|previous| cgroup_harvest.cpu_usage_since(previous, elapsed, num_cores));
+            cgroup::limit_cpu_data(cpu_data, cgroup_harvest, usage);
+        }
+    }
+
+    /// Limits the memory data to the cgroup and stores its reading.  The reading is also kept for
+    /// the next update, as [`Data`] is replaced once it is sent off.
+    #[cfg(target_os = "linux")]
+    fn set_cgroup_data(&mut self, cgroup_harvest: Option<cgroup::CgroupHarvest>) {
+        if let (Some(memory), Some(cgroup_harvest)) = (&mut self.data.memory, &cgroup_harvest) {
+            cgroup::limit_mem_data(memory, cgroup_harvest);
+        }
+        self.previous_cgroup = cgroup_harvest.clone();
+        self.data.cgroup = cgroup_harvest;
+    }
 }
 
 #[cfg(target_os = "freebsd")]
@@ -486,3 +678,58 @@ where
         .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::Other, "key not found"))
         .and_then(|val| serde_json::from_value(val).map_err(|err| err.into()))
 }
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_cgroup_cpu_usage_across_updates() {
+        use std::time::Duration;
+
+        use super::*;
+        use crate::app::data_farmer::DataCollection;
+
+        let mut collector = DataCollector::new(DataFilters {
+            disk_filter: None,
+            mount_filter: None,
+            fs_filter: None,
+            temp_filter: None,
+            net_filter: None,
+        });
+        let mut data_collection = DataCollection::default();
+
+        // Collect and send off the data twice, as the collection thread does.
+        let mut averages = vec![];
+        for cpu_usage in [1_000_000, 1_500_000] {
+            let cgroup_harvest = cgroup::CgroupHarvest {
+                cpu_quota: Some(1.0),
+                cpu_usage: Some(cpu_usage),
+                ..Default::default()
+            };
+            collector.data.cpu = Some(vec![
+                cpu::CpuData {
+                    data_type: cpu::CpuDataType::Avg,
+                    cpu_usage: 10.0,
+                    state_usage: None,
+                    frequency: None,
+                },
+                cpu::CpuData {
+                    data_type: cpu::CpuDataType::Cpu(0),
+                    cpu_usage: 10.0,
+                    state_usage: None,
+                    frequency: None,
+                },
+            ]);
+
+            collector.limit_cpu_data(&cgroup_harvest, Duration::from_secs(1));
+            collector.set_cgroup_data(Some(cgroup_harvest));
+            averages.push(collector.data.cpu.as_ref().unwrap()[0].cpu_usage);
+
+            data_collection.eat_data(Box::new(std::mem::take(&mut collector.data)));
+        }
+
+        // The first update has nothing to compare against, so it falls back to the cores.
+        assert_eq!(averages, vec![10.0, 50.0]);
+        assert!(data_collection.cgroup_harvest.is_some());
+    }
+}
@@ -0,0 +1,477 @@
+//! Data collection for the memory and CPU limits of the cgroup that bottom runs in, such as those
+//! of a container.
+//!
+//! This is only available on Linux, through either cgroup v2 or the `memory`, `cpu`, `cpuacct`, and
+//! `cpuset` controllers of cgroup v1.
+
+use std::time::Duration;
+
+use super::{
+    cpu::{CpuDataType, CpuHarvest, CpuStates},
+    memory::MemHarvest,
+};
+
+/// The limits and usage of a cgroup.  Anything that couldn't be read, or that isn't limited, is
+/// `None`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CgroupHarvest {
+    /// The memory limit in KiB.
+    pub memory_limit: Option<u64>,
+    /// The memory in use in KiB, not counting inactive page cache, which the kernel reclaims before
+    /// the limit is hit.
+    pub memory_used: Option<u64>,
+    /// How many CPUs worth of time the cgroup may use, from its CPU quota.
+    pub cpu_quota: Option<f64>,
+    /// The CPU time used by the cgroup since it was created, in microseconds.
+    pub cpu_usage: Option<u64>,
+    /// The cores that the cgroup may run on.
+    pub cpuset: Option<Vec<usize>>,
+}
+
+impl CgroupHarvest {
+    /// Returns the memory available to the cgroup in KiB, which is never more than the host has.
+    pub fn memory_total(&self, host_total_kib: u64) -> u64 {
+        match self.memory_limit {
+            Some(memory_limit) => memory_limit.min(host_total_kib),
+            None => host_total_kib,
+        }
+    }
+
+    /// Returns how many CPUs worth of time the cgroup can use at once, given the number of cores
+    /// on the host.
+    pub fn cpu_limit(&self, num_cores: usize) -> f64 {
+        let num_cores = match &self.cpuset {
+            Some(cpuset) if !cpuset.is_empty() => cpuset.len().min(num_cores),
+            _ => num_cores,
+        } as f64;
+
+        match self.cpu_quota {
+            Some(cpu_quota) => cpu_quota.min(num_cores),
+            None => num_cores,
+        }
+    }
+
+    /// Returns the percentage of its CPU limit that the cgroup used since a previous reading,
+    /// given the time that passed and the number of cores on the host.
+    pub fn cpu_usage_since(
+        &self, previous: &CgroupHarvest, elapsed: Duration, num_cores: usize,
+    ) -> Option<f64> {
+        let used = self.cpu_usage?.saturating_sub(previous.cpu_usage?) as f64;
+        let available = elapsed.as_micros() as f64 * self.cpu_limit(num_cores);
+
+        if available > 0.0 {
+            Some((used * 100.0 / available).clamp(0.0, 100.0))
+        } else {
+            None
+        }
+    }
+}
+
+/// Measures RAM against the cgroup's limit and usage rather than the host's.  The breakdown of RAM
+/// only covers the host, so it is dropped.
+pub fn limit_mem_data(memory: &mut MemHarvest, cgroup: &CgroupHarvest) {
+    let mem_total_in_kib = cgroup.memory_total(memory.mem_total_in_kib);
+    let mem_used_in_kib = cgroup
+        .memory_used
+        .unwrap_or(memory.mem_used_in_kib)
+        .min(mem_total_in_kib);
+
+    *memory = MemHarvest {
+        mem_total_in_kib,
+        mem_used_in_kib,
+        use_percent: if mem_total_in_kib == 0 {
+            None
+        } else {
+            Some(mem_used_in_kib as f64 / mem_total_in_kib as f64 * 100.0)
+        },
+        breakdown: None,
+    };
+}
+
+/// Removes any cores that the cgroup can't run on, and replaces the average with the cgroup's
+/// usage of its CPU limit.  If that isn't known, the average of the remaining cores is used.
+pub fn limit_cpu_data(cpu_harvest: &mut CpuHarvest, cgroup: &CgroupHarvest, usage: Option<f64>) {
+    if let Some(cpuset) = cgroup.cpuset.as_ref().filter(|cpuset| !cpuset.is_empty()) {
+        cpu_harvest.retain(|cpu| match cpu.data_type {
+            CpuDataType::Cpu(index) => cpuset.contains(&index),
+            _ => true,
+        });
+    }
+
+    let cores = cpu_harvest
+        .iter()
+        .filter(|cpu| matches!(cpu.data_type, CpuDataType::Cpu(_)))
+        .collect::<Vec<_>>();
+    let cores_usage = if cores.is_empty() {
+        0.0
+    } else {
+        cores.iter().map(|cpu| cpu.cpu_usage).sum::<f64>() / cores.len() as f64
+    };
+    let cores_state_usage =
+        CpuStates::mean(cores.iter().filter_map(|cpu| cpu.state_usage.as_ref()));
+
+    if let Some(average) = cpu_harvest
+        .iter_mut()
+        .find(|cpu| cpu.data_type == CpuDataType::Avg)
+    {
+        average.cpu_usage = usage.unwrap_or(cores_usage);
+        average.state_usage = cores_state_usage;
+    }
+}
+
+/// Where the files for each controller of a cgroup are.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cgroup {
+    V1 {
+        memory: Option<std::path::PathBuf>,
+        cpu: Option<std::path::PathBuf>,
+        cpuacct: Option<std::path::PathBuf>,
+        cpuset: Option<std::path::PathBuf>,
+    },
+    V2(std::path::PathBuf),
+}
+
+#[cfg(target_os = "linux")]
+impl Cgroup {
+    /// Finds the cgroup that bottom is running in.
+    pub fn find() -> Option<Cgroup> {
+        let proc_cgroup = std::fs::read_to_string("/proc/self/cgroup").ok()?;
+        Cgroup::find_in(&proc_cgroup, std::path::Path::new("/sys/fs/cgroup"))
+    }
+
+    /// Finds a cgroup from the contents of `/proc/self/cgroup`, given where cgroups are mounted.
+    /// Each line is of the form "id:controllers:path".  Inside a container, the path is often not
+    /// visible, as the container's own cgroup is mounted at the root instead.
+    fn find_in(proc_cgroup: &str, root: &std::path::Path) -> Option<Cgroup> {
+        let cgroup_dir = |mount: &str, path: &str| {
+            let mount_dir = root.join(mount);
+            let dir = mount_dir.join(path.trim_start_matches('/'));
+            if dir.is_dir() {
+                Some(dir)
+            } else if mount_dir.is_dir() {
+                Some(mount_dir)
+            } else {
+                None
+            }
+        };
+
+        let entries = proc_cgroup
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(3, ':');
+                Some((fields.nth(1)?, fields.next()?))
+            })
+            .collect::<Vec<_>>();
+
+        if root.join("cgroup.controllers").is_file() {
+            let (_, path) = entries
+                .iter()
+                .find(|(controllers, _)| controllers.is_empty())?;
+            return cgroup_dir("", path).map(Cgroup::V2);
+        }
+
+        let controller_dir = |controller: &str| {
+            entries
+                .iter()
+                .find(|(controllers, _)| controllers.split(',').any(|name| name == controller))
+                .and_then(|(controllers, path)| cgroup_dir(controllers, path))
+        };
+        let memory = controller_dir("memory");
+        let cpu = controller_dir("cpu");
+        let cpuacct = controller_dir("cpuacct");
+        let cpuset = controller_dir("cpuset");
+
+        if memory.is_none() && cpu.is_none() && cpuacct.is_none() && cpuset.is_none() {
+            None
+        } else {
+            Some(Cgroup::V1 {
+                memory,
+                cpu,
+                cpuacct,
+                cpuset,
+            })
+        }
+    }
+
+    /// Reads the cgroup's current limits and usage.
+    pub fn read(&self) -> CgroupHarvest {
+        fn read_file(dir: Option<&std::path::Path>, file: &str) -> Option<String> {
+            std::fs::read_to_string(dir?.join(file))
+                .ok()
+                .map(|contents| contents.trim().to_string())
+        }
+
+        fn read_number(dir: Option<&std::path::Path>, file: &str) -> Option<u64> {
+            read_file(dir, file)?.parse().ok()
+        }
+
+        /// Returns a named value from a file with a "name value" pair per line.
+        fn read_stat(dir: Option<&std::path::Path>, file: &str, name: &str) -> Option<u64> {
+            read_file(dir, file)?.lines().find_map(|line| {
+                let (line_name, value) = line.split_once(' ')?;
+                if line_name == name {
+                    value.trim().parse().ok()
+                } else {
+                    None
+                }
+            })
+        }
+
+        fn read_cpuset(dir: Option<&std::path::Path>, files: &[&str]) -> Option<Vec<usize>> {
+            files
+                .iter()
+                .filter_map(|file| read_file(dir, file))
+                .map(|cpu_list| super::cpu::topology::parse_cpu_list(&cpu_list))
+                .find(|cpuset| !cpuset.is_empty())
+        }
+
+        fn memory_used(usage: Option<u64>, inactive_file: Option<u64>) -> Option<u64> {
+            usage.map(|usage| usage.saturating_sub(inactive_file.unwrap_or(0)) / 1024)
+        }
+
+        match self {
+            Cgroup::V1 {
+                memory,
+                cpu,
+                cpuacct,
+                cpuset,
+            } => {
+                let memory = memory.as_deref();
+                let cpu = cpu.as_deref();
+
+                // There's no way to say that there is no memory limit in v1, so the largest
+                // multiple of the page size is used instead.
+                let memory_limit = read_number(memory, "memory.limit_in_bytes")
+                    .filter(|limit| *limit < 1 << 62)
+                    .map(|limit| limit / 1024);
+
+                // A quota of -1 means that there is no limit.
+                let cpu_quota = read_file(cpu, "cpu.cfs_quota_us")
+                    .and_then(|quota| quota.parse::<u64>().ok())
+                    .zip(read_number(cpu, "cpu.cfs_period_us"))
+                    .filter(|(_, period)| *period > 0)
+                    .map(|(quota, period)| quota as f64 / period as f64);
+
+                CgroupHarvest {
+                    memory_limit,
+                    memory_used: memory_used(
+                        read_number(memory, "memory.usage_in_bytes"),
+                        read_stat(memory, "memory.stat", "total_inactive_file"),
+                    ),
+                    cpu_quota,
+                    cpu_usage: read_number(cpuacct.as_deref(), "cpuacct.usage")
+                        .map(|nanoseconds| nanoseconds / 1000),
+                    cpuset: read_cpuset(
+                        cpuset.as_deref(),
+                        &["cpuset.effective_cpus", "cpuset.cpus"],
+                    ),
+                }
+            }
+            Cgroup::V2(dir) => {
+                let dir = Some(dir.as_path());
+
+                // Both of these are "max" if there is no limit.
+                let memory_limit = read_number(dir, "memory.max").map(|limit| limit / 1024);
+                let cpu_quota = read_file(dir, "cpu.max").and_then(|cpu_max| {
+                    let (quota, period) = cpu_max.split_once(' ')?;
+                    let (quota, period) = (quota.parse::<u64>().ok()?, period.parse::<u64>().ok()?);
+                    if period > 0 {
+                        Some(quota as f64 / period as f64)
+                    } else {
+                        None
+                    }
+                });
+
+                CgroupHarvest {
+                    memory_limit,
+                    memory_used: memory_used(
+                        read_number(dir, "memory.current"),
+                        read_stat(dir, "memory.stat", "inactive_file"),
+                    ),
+                    cpu_quota,
+                    cpu_usage: read_stat(dir, "cpu.stat", "usage_usec"),
+                    cpuset: read_cpuset(dir, &["cpuset.cpus.effective"]),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::data_harvester::cpu::CpuData;
+
+    fn cpu(data_type: CpuDataType, cpu_usage: f64) -> CpuData {
+        CpuData {
+            data_type,
+            cpu_usage,
+            state_usage: None,
+            frequency: None,
+        }
+    }
+
+    #[test]
+    fn test_cpu_usage_since() {
+        let previous = CgroupHarvest {
+            cpu_quota: Some(1.5),
+            cpu_usage: Some(1_000_000),
+            cpuset: Some(vec![0, 1]),
+            ..Default::default()
+        };
+        let current = CgroupHarvest {
+            cpu_usage: Some(1_750_000),
+            ..previous.clone()
+        };
+
+        assert_eq!(current.cpu_limit(8), 1.5);
+        assert_eq!(
+            current.cpu_usage_since(&previous, Duration::from_secs(1), 8),
+            Some(50.0)
+        );
+        assert_eq!(
+            CgroupHarvest {
+                cpu_quota: None,
+                ..current.clone()
+            }
+            .cpu_limit(8),
+            2.0
+        );
+        assert_eq!(
+            current.cpu_usage_since(&CgroupHarvest::default(), Duration::from_secs(1), 8),
+            None
+        );
+    }
+
+    #[test]
+    fn test_limit_data() {
+        let cgroup = CgroupHarvest {
+            memory_limit: Some(1024),
+            memory_used: Some(256),
+            cpuset: Some(vec![1, 2]),
+            ..Default::default()
+        };
+
+        let mut memory = MemHarvest {
+            mem_total_in_kib: 4096,
+            mem_used_in_kib: 2048,
+            use_percent: Some(50.0),
+            breakdown: None,
+        };
+        limit_mem_data(&mut memory, &cgroup);
+        assert_eq!(
+            (
+                memory.mem_total_in_kib,
+                memory.mem_used_in_kib,
+                memory.use_percent
+            ),
+            (1024, 256, Some(25.0))
+        );
+
+        let mut cpu_harvest = vec![
+            cpu(CpuDataType::Avg, 10.0),
+            cpu(CpuDataType::Cpu(0), 0.0),
+            cpu(CpuDataType::Cpu(1), 20.0),
+            cpu(CpuDataType::Cpu(2), 40.0),
+        ];
+        limit_cpu_data(&mut cpu_harvest, &cgroup, None);
+        assert_eq!(
+            cpu_harvest
+                .iter()
+                .map(|cpu| (cpu.data_type, cpu.cpu_usage))
+                .collect::<Vec<_>>(),
+            vec![
+                (CpuDataType::Avg, 30.0),
+                (CpuDataType::Cpu(1), 20.0),
+                (CpuDataType::Cpu(2), 40.0),
+            ]
+        );
+
+        limit_cpu_data(&mut cpu_harvest, &cgroup, Some(75.0));
+        assert_eq!(cpu_harvest[0].cpu_usage, 75.0);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_read_cgroup() {
+        use crate::utils::test_utils::TempDir;
+
+        let dir = TempDir::new("cgroup_v1");
+
+        // cgroup v1, where the container's cgroups are mounted at the root of each controller.
+        for (controller, file, contents) in [
+            ("memory", "memory.limit_in_bytes", "536870912\n"),
+            ("memory", "memory.usage_in_bytes", "209715200\n"),
+            (
+                "memory",
+                "memory.stat",
+                "cache 100\ntotal_inactive_file 104857600\n",
+            ),
+            ("cpu,cpuacct", "cpu.cfs_quota_us", "50000\n"),
+            ("cpu,cpuacct", "cpu.cfs_period_us", "100000\n"),
+            ("cpu,cpuacct", "cpuacct.usage", "3000000\n"),
+            ("cpuset", "cpuset.effective_cpus", "0-1\n"),
+        ] {
+            dir.write(format!("{}/{}", controller, file), contents);
+        }
+        let proc_cgroup = "12:cpuset:/docker/abc\n\
+                           11:cpu,cpuacct:/docker/abc\n\
+                           10:memory:/docker/abc\n\
+                           1:name=systemd:/docker/abc\n\
+                           0::/docker/abc\n";
+
+        let cgroup = Cgroup::find_in(proc_cgroup, dir.path()).unwrap();
+        assert_eq!(
+            cgroup,
+            Cgroup::V1 {
+                memory: Some(dir.path().join("memory")),
+                cpu: Some(dir.path().join("cpu,cpuacct")),
+                cpuacct: Some(dir.path().join("cpu,cpuacct")),
+                cpuset: Some(dir.path().join("cpuset")),
+            }
+        );
+        assert_eq!(
+            cgroup.read(),
+            CgroupHarvest {
+                memory_limit: Some(524288),
+                memory_used: Some(102400),
+                cpu_quota: Some(0.5),
+                cpu_usage: Some(3000),
+                cpuset: Some(vec![0, 1]),
+            }
+        );
+
+        // cgroup v2, with no memory limit.
+        let dir = TempDir::new("cgroup_v2");
+        dir.write("cgroup.controllers", "cpuset cpu memory\n");
+        for (file, contents) in [
+            ("memory.max", "max\n"),
+            ("memory.current", "4194304\n"),
+            ("memory.stat", "anon 100\ninactive_file 1048576\n"),
+            ("cpu.max", "200000 100000\n"),
+            ("cpu.stat", "usage_usec 123456\nuser_usec 100000\n"),
+            ("cpuset.cpus.effective", "0-3\n"),
+        ] {
+            dir.write(format!("system.slice/btm.scope/{}", file), contents);
+        }
+
+        let cgroup = Cgroup::find_in("0::/system.slice/btm.scope\n", dir.path()).unwrap();
+        assert_eq!(
+            cgroup,
+            Cgroup::V2(dir.path().join("system.slice/btm.scope"))
+        );
+        assert_eq!(
+            cgroup.read(),
+            CgroupHarvest {
+                memory_limit: None,
+                memory_used: Some(3072),
+                cpu_quota: Some(2.0),
+                cpu_usage: Some(123456),
+                cpuset: Some(vec![0, 1, 2, 3]),
+            }
+        );
+
+        assert_eq!(Cgroup::find_in("", &dir.path().join("missing")), None);
+    }
+}
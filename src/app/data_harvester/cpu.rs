@@ -1,9 +1,16 @@
-//! Data collection for CPU usage and load average.
+//! Data collection for CPU usage, frequency, and load average.
 //!
 //! For CPU usage, Linux, macOS, and Windows are handled by Heim, FreeBSD by sysinfo.
 //!
 //! For load average, macOS and Linux are supported through Heim, FreeBSD by sysinfo.
 
+pub mod frequency;
+pub mod topology;
+
+use std::str::FromStr;
+
+use crate::utils::error::{self, BottomError};
+
 cfg_if::cfg_if! {
     if #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))] {
         pub mod heim;
@@ -16,19 +23,250 @@ cfg_if::cfg_if! {
 
 pub type LoadAvgHarvest = [f32; 3];
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CpuDataType {
     Avg,
+    /// The combined usage of every core within a group.
+    Group(CpuGroup),
     Cpu(usize),
 }
 
+/// How cores can be grouped together in the CPU widget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuGrouping {
+    Socket,
+    NumaNode,
+    CoreType,
+}
+
+impl FromStr for CpuGrouping {
+    type Err = BottomError;
+
+    fn from_str(s: &str) -> error::Result<Self> {
+        let lower_case = s.to_lowercase();
+        match lower_case.as_str() {
+            "socket" => Ok(CpuGrouping::Socket),
+            "numa" => Ok(CpuGrouping::NumaNode),
+            "core_type" => Ok(CpuGrouping::CoreType),
+            _ => Err(BottomError::ConfigError(format!(
+                "\"{}\" is an invalid CPU grouping, use one of \"socket\", \"numa\", or \"core_type\".",
+                s
+            ))),
+        }
+    }
+}
+
+/// The kind of core on a hybrid CPU.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CoreType {
+    Performance,
+    Efficiency,
+}
+
+/// A group of cores that share a socket, NUMA node, or core type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CpuGroup {
+    Socket(usize),
+    NumaNode(usize),
+    CoreType(CoreType),
+}
+
+impl CpuGroup {
+    /// The name of the group, such as "Socket0" or "P-core".
+    pub fn name(&self) -> String {
+        match self {
+            CpuGroup::Socket(id) => format!("Socket{}", id),
+            CpuGroup::NumaNode(id) => format!("Node{}", id),
+            CpuGroup::CoreType(CoreType::Performance) => "P-core".to_string(),
+            CpuGroup::CoreType(CoreType::Efficiency) => "E-core".to_string(),
+        }
+    }
+
+    /// A shorter name for the group for when space is tight, such as "S0" or "P".
+    pub fn short_name(&self) -> String {
+        match self {
+            CpuGroup::Socket(id) => format!("S{}", id),
+            CpuGroup::NumaNode(id) => format!("N{}", id),
+            CpuGroup::CoreType(CoreType::Performance) => "P".to_string(),
+            CpuGroup::CoreType(CoreType::Efficiency) => "E".to_string(),
+        }
+    }
+}
+
+/// A value for each state a CPU can spend time in.  Depending on where it is used, this is either
+/// the cumulative time spent in each state in seconds, or the percentage of time spent in each state
+/// since the last update.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CpuStates {
+    pub user: f64,
+    pub nice: f64,
+    pub system: f64,
+    pub irq: f64,
+    pub soft_irq: f64,
+    pub io_wait: f64,
+    pub steal: f64,
+}
+
+impl CpuStates {
+    /// Returns the percentage of time spent in each state between two cumulative times, given the
+    /// total time that passed.
+    pub fn usage_between(previous: &CpuStates, current: &CpuStates, total_time: f64) -> CpuStates {
+        let percentage = |previous: f64, current: f64| {
+            if current > previous && total_time > 0.0 {
+                (current - previous) * 100.0 / total_time
+            } else {
+                0.0
+            }
+        };
+
+        CpuStates {
+            user: percentage(previous.user, current.user),
+            nice: percentage(previous.nice, current.nice),
+            system: percentage(previous.system, current.system),
+            irq: percentage(previous.irq, current.irq),
+            soft_irq: percentage(previous.soft_irq, current.soft_irq),
+            io_wait: percentage(previous.io_wait, current.io_wait),
+            steal: percentage(previous.steal, current.steal),
+        }
+    }
+
+    /// Returns the mean of each state across a set of CPUs, or `None` if there are none.
+    pub fn mean<'a>(states: impl IntoIterator<Item = &'a CpuStates>) -> Option<CpuStates> {
+        let mut count = 0;
+        let mut sum = CpuStates::default();
+        for state in states {
+            count += 1;
+            sum.user += state.user;
+            sum.nice += state.nice;
+            sum.system += state.system;
+            sum.irq += state.irq;
+            sum.soft_irq += state.soft_irq;
+            sum.io_wait += state.io_wait;
+            sum.steal += state.steal;
+        }
+
+        if count == 0 {
+            return None;
+        }
+
+        let count = f64::from(count);
+        Some(CpuStates {
+            user: sum.user / count,
+            nice: sum.nice / count,
+            system: sum.system / count,
+            irq: sum.irq / count,
+            soft_irq: sum.soft_irq / count,
+            io_wait: sum.io_wait / count,
+            steal: sum.steal / count,
+        })
+    }
+}
+
+/// The clock frequency of a CPU in MHz, along with the range it can scale within if known.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CpuFrequency {
+    pub current: u64,
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+}
+
 #[derive(Debug, Clone)]
 pub struct CpuData {
     pub data_type: CpuDataType,
     pub cpu_usage: f64,
+
+    /// The percentage of time spent in each state, if the platform reports it.
+    pub state_usage: Option<CpuStates>,
+
+    /// The current frequency, if the platform reports it.
+    pub frequency: Option<CpuFrequency>,
 }
 
 pub type CpuHarvest = Vec<CpuData>;
 
-pub type PastCpuWork = f64;
-pub type PastCpuTotal = f64;
+/// Cumulative CPU times in seconds, which are compared between updates to get the usage.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PastCpuTimes {
+    /// Time spent doing work.
+    pub work: f64,
+
+    /// Time spent in total, including being idle.
+    pub total: f64,
+
+    /// Time spent in each state, if the platform reports it.
+    pub states: Option<CpuStates>,
+}
+
+impl PastCpuTimes {
+    /// Returns the usage percentage and the per-state usage percentages since the given times.
+    pub fn usage_since(&self, previous: &PastCpuTimes) -> (f64, Option<CpuStates>) {
+        let work = if self.work > previous.work {
+            self.work - previous.work
+        } else {
+            0.0
+        };
+        let total = if self.total > previous.total {
+            self.total - previous.total
+        } else {
+            1.0
+        };
+
+        let state_usage = match (&previous.states, &self.states) {
+            (Some(previous_states), Some(current_states)) => Some(CpuStates::usage_between(
+                previous_states,
+                current_states,
+                total,
+            )),
+            _ => None,
+        };
+
+        (work * 100.0 / total, state_usage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_since() {
+        let previous = PastCpuTimes {
+            work: 10.0,
+            total: 20.0,
+            states: Some(CpuStates {
+                user: 5.0,
+                system: 5.0,
+                io_wait: 2.0,
+                ..Default::default()
+            }),
+        };
+        let current = PastCpuTimes {
+            work: 15.0,
+            total: 40.0,
+            states: Some(CpuStates {
+                user: 8.0,
+                system: 7.0,
+                io_wait: 6.0,
+                ..Default::default()
+            }),
+        };
+
+        let (usage, state_usage) = current.usage_since(&previous);
+        assert_eq!(usage, 25.0);
+        assert_eq!(
+            state_usage,
+            Some(CpuStates {
+                user: 15.0,
+                system: 10.0,
+                io_wait: 20.0,
+                ..Default::default()
+            })
+        );
+
+        let no_states = PastCpuTimes {
+            states: None,
+            ..current
+        };
+        assert_eq!(no_states.usage_since(&previous).1, None);
+    }
+}
@@ -0,0 +1,238 @@
+//! Per-core CPU frequencies.
+//!
+//! On Linux, these are read from cpufreq in sysfs, falling back to `/proc/cpuinfo` when cpufreq
+//! isn't available (as is common in VMs).  Other platforms go through sysinfo, which only reports
+//! the current frequency.
+
+use super::{CpuDataType, CpuFrequency, CpuHarvest};
+
+/// Sets the frequency of each CPU in the harvest.  The average entry gets the mean of the cores.
+#[cfg(target_os = "linux")]
+pub fn set_cpu_frequencies(cpu_harvest: &mut CpuHarvest) {
+    let cpuinfo_frequencies = once_cell::unsync::OnceCell::new();
+
+    for cpu in cpu_harvest.iter_mut() {
+        if let CpuDataType::Cpu(index) = cpu.data_type {
+            let cpufreq_dir =
+                std::path::PathBuf::from(format!("/sys/devices/system/cpu/cpu{}/cpufreq", index));
+
+            cpu.frequency = read_cpufreq(&cpufreq_dir).or_else(|| {
+                cpuinfo_frequencies
+                    .get_or_init(|| {
+                        std::fs::read_to_string("/proc/cpuinfo")
+                            .map(|cpuinfo| parse_cpuinfo_frequencies(&cpuinfo))
+                            .unwrap_or_default()
+                    })
+                    .get(index)
+                    .copied()
+                    .flatten()
+            });
+        }
+    }
+
+    set_average_frequency(cpu_harvest);
+}
+
+/// Sets the frequency of each CPU in the harvest.  The average entry gets the mean of the cores.
+#[cfg(not(target_os = "linux"))]
+pub fn set_cpu_frequencies(cpu_harvest: &mut CpuHarvest, sys: &sysinfo::System) {
+    use sysinfo::{CpuExt, SystemExt};
+
+    let cpus = sys.cpus();
+    for cpu in cpu_harvest.iter_mut() {
+        if let CpuDataType::Cpu(index) = cpu.data_type {
+            cpu.frequency = cpus
+                .get(index)
+                .map(|cpu| cpu.frequency())
+                .filter(|&frequency| frequency > 0)
+                .map(|current| CpuFrequency {
+                    current,
+                    min: None,
+                    max: None,
+                });
+        }
+    }
+
+    set_average_frequency(cpu_harvest);
+}
+
+fn set_average_frequency(cpu_harvest: &mut CpuHarvest) {
+    let average = average_frequency(
+        cpu_harvest
+            .iter()
+            .filter(|cpu| matches!(cpu.data_type, CpuDataType::Cpu(_)))
+            .filter_map(|cpu| cpu.frequency),
+    );
+
+    if let Some(average) = average {
+        for cpu in cpu_harvest.iter_mut() {
+            if let CpuDataType::Avg = cpu.data_type {
+                cpu.frequency = Some(average);
+            }
+        }
+    }
+}
+
+/// Returns the mean of the current frequencies, along with the lowest minimum and highest maximum,
+/// or `None` if there are no frequencies.
+pub(super) fn average_frequency(
+    frequencies: impl IntoIterator<Item = CpuFrequency>,
+) -> Option<CpuFrequency> {
+    let frequencies: Vec<CpuFrequency> = frequencies.into_iter().collect();
+
+    if frequencies.is_empty() {
+        return None;
+    }
+
+    Some(CpuFrequency {
+        current: frequencies
+            .iter()
+            .map(|frequency| frequency.current)
+            .sum::<u64>()
+            / frequencies.len() as u64,
+        min: frequencies
+            .iter()
+            .filter_map(|frequency| frequency.min)
+            .min(),
+        max: frequencies
+            .iter()
+            .filter_map(|frequency| frequency.max)
+            .max(),
+    })
+}
+
+/// Reads the frequency from a cpufreq directory, where values are in kHz.
+#[cfg(target_os = "linux")]
+fn read_cpufreq(dir: &std::path::Path) -> Option<CpuFrequency> {
+    let read_mhz = |file: &str| {
+        std::fs::read_to_string(dir.join(file))
+            .ok()
+            .and_then(|contents| contents.trim().parse::<u64>().ok())
+            .map(|khz| khz / 1000)
+    };
+
+    Some(CpuFrequency {
+        current: read_mhz("scaling_cur_freq").or_else(|| read_mhz("cpuinfo_cur_freq"))?,
+        min: read_mhz("cpuinfo_min_freq"),
+        max: read_mhz("cpuinfo_max_freq"),
+    })
+}
+
+/// Parses the "cpu MHz" of each processor from `/proc/cpuinfo`, indexed by processor number.
+#[cfg(target_os = "linux")]
+fn parse_cpuinfo_frequencies(cpuinfo: &str) -> Vec<Option<CpuFrequency>> {
+    let mut frequencies = Vec::new();
+    let mut processor = None;
+
+    for line in cpuinfo.lines() {
+        let (key, value) = match line.split_once(':') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => continue,
+        };
+
+        match key {
+            "processor" => processor = value.parse::<usize>().ok(),
+            "cpu MHz" => {
+                if let (Some(index), Ok(mhz)) = (processor, value.parse::<f64>()) {
+                    if frequencies.len() <= index {
+                        frequencies.resize(index + 1, None);
+                    }
+                    frequencies[index] = Some(CpuFrequency {
+                        current: mhz.round() as u64,
+                        min: None,
+                        max: None,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    frequencies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::data_harvester::cpu::CpuData;
+
+    fn cpu(data_type: CpuDataType, frequency: Option<CpuFrequency>) -> CpuData {
+        CpuData {
+            data_type,
+            cpu_usage: 0.0,
+            state_usage: None,
+            frequency,
+        }
+    }
+
+    #[test]
+    fn test_average_frequency() {
+        let mut harvest = vec![
+            cpu(CpuDataType::Avg, None),
+            cpu(
+                CpuDataType::Cpu(0),
+                Some(CpuFrequency {
+                    current: 1000,
+                    min: Some(800),
+                    max: Some(3000),
+                }),
+            ),
+            cpu(
+                CpuDataType::Cpu(1),
+                Some(CpuFrequency {
+                    current: 3000,
+                    min: Some(400),
+                    max: Some(4000),
+                }),
+            ),
+            cpu(CpuDataType::Cpu(2), None),
+        ];
+
+        set_average_frequency(&mut harvest);
+        assert_eq!(
+            harvest[0].frequency,
+            Some(CpuFrequency {
+                current: 2000,
+                min: Some(400),
+                max: Some(4000),
+            })
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_read_cpufreq() {
+        let dir = crate::utils::test_utils::TempDir::new("cpufreq");
+        assert_eq!(read_cpufreq(dir.path()), None);
+
+        dir.write("scaling_cur_freq", "2400000\n");
+        dir.write("cpuinfo_max_freq", "4200000\n");
+        assert_eq!(
+            read_cpufreq(dir.path()),
+            Some(CpuFrequency {
+                current: 2400,
+                min: None,
+                max: Some(4200),
+            })
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_cpuinfo_frequencies() {
+        let cpuinfo = "processor\t: 0\nmodel name\t: Some CPU\ncpu MHz\t\t: 2100.000\n\n\
+                       processor\t: 2\ncpu MHz\t\t: 3599.6\n";
+        let frequencies = parse_cpuinfo_frequencies(cpuinfo);
+
+        assert_eq!(frequencies.len(), 3);
+        assert_eq!(
+            frequencies[0].map(|frequency| frequency.current),
+            Some(2100)
+        );
+        assert_eq!(frequencies[1], None);
+        assert_eq!(
+            frequencies[2].map(|frequency| frequency.current),
+            Some(3600)
+        );
+    }
+}
@@ -18,32 +18,25 @@ cfg_if::cfg_if! {
     }
 }
 
-use crate::{
-    components::tui_widget::time_chart::Point,
-    data_harvester::cpu::{CpuData, CpuDataType, CpuHarvest, PastCpuTotal, PastCpuWork},
-};
+use crate::data_harvester::cpu::{CpuData, CpuDataType, CpuHarvest, PastCpuTimes};
 
 use futures::StreamExt;
 use std::collections::VecDeque;
 
 pub async fn get_cpu_data_list(
-    show_average_cpu: bool, previous_cpu_times: &mut Vec<(PastCpuWork, PastCpuTotal)>,
-    previous_average_cpu_time: &mut Option<(PastCpuWork, PastCpuTotal)>,
+    show_average_cpu: bool, previous_cpu_times: &mut Vec<PastCpuTimes>,
+    previous_average_cpu_time: &mut Option<PastCpuTimes>,
 ) -> crate::error::Result<CpuHarvest> {
-    fn calculate_cpu_usage_percentage(
-        (previous_working_time, previous_total_time): Point,
-        (current_working_time, current_total_time): Point,
-    ) -> f64 {
-        ((if current_working_time > previous_working_time {
-            current_working_time - previous_working_time
-        } else {
-            0.0
-        }) * 100.0)
-            / (if current_total_time > previous_total_time {
-                current_total_time - previous_total_time
-            } else {
-                1.0
-            })
+    fn cpu_data(
+        data_type: CpuDataType, previous: &PastCpuTimes, current: &PastCpuTimes,
+    ) -> CpuData {
+        let (cpu_usage, state_usage) = current.usage_since(previous);
+        CpuData {
+            data_type,
+            cpu_usage,
+            state_usage,
+            frequency: None,
+        }
     }
 
     // Get all CPU times...
@@ -57,7 +50,7 @@ pub async fn get_cpu_data_list(
         let second_cpu_times = heim::cpu::times().await?;
         futures::pin_mut!(second_cpu_times);
 
-        let mut new_cpu_times: Vec<(PastCpuWork, PastCpuTotal)> = Vec::new();
+        let mut new_cpu_times: Vec<PastCpuTimes> = Vec::new();
         let mut cpu_deque: VecDeque<CpuData> = VecDeque::new();
         let mut collected_zip = cpu_times.zip(second_cpu_times).enumerate(); // Gotta move it here, can't on while line.
 
@@ -65,18 +58,18 @@ pub async fn get_cpu_data_list(
             if let (Ok(past), Ok(present)) = (past, present) {
                 let present_times = convert_cpu_times(&present);
                 new_cpu_times.push(present_times);
-                cpu_deque.push_back(CpuData {
-                    data_type: CpuDataType::Cpu(itx),
-                    cpu_usage: calculate_cpu_usage_percentage(
-                        convert_cpu_times(&past),
-                        present_times,
-                    ),
-                });
+                cpu_deque.push_back(cpu_data(
+                    CpuDataType::Cpu(itx),
+                    &convert_cpu_times(&past),
+                    &present_times,
+                ));
             } else {
-                new_cpu_times.push((0.0, 0.0));
+                new_cpu_times.push(PastCpuTimes::default());
                 cpu_deque.push_back(CpuData {
                     data_type: CpuDataType::Cpu(itx),
                     cpu_usage: 0.0,
+                    state_usage: None,
+                    frequency: None,
                 });
             }
         }
@@ -84,72 +77,70 @@ pub async fn get_cpu_data_list(
         *previous_cpu_times = new_cpu_times;
         cpu_deque
     } else {
-        let (new_cpu_times, cpu_deque): (Vec<(PastCpuWork, PastCpuTotal)>, VecDeque<CpuData>) =
-            cpu_times
-                .collect::<Vec<_>>()
-                .await
-                .iter()
-                .zip(&*previous_cpu_times)
-                .enumerate()
-                .map(|(itx, (current_cpu, (past_cpu_work, past_cpu_total)))| {
-                    if let Ok(cpu_time) = current_cpu {
-                        let present_times = convert_cpu_times(cpu_time);
-
-                        (
-                            present_times,
-                            CpuData {
-                                data_type: CpuDataType::Cpu(itx),
-                                cpu_usage: calculate_cpu_usage_percentage(
-                                    (*past_cpu_work, *past_cpu_total),
-                                    present_times,
-                                ),
-                            },
-                        )
-                    } else {
-                        (
-                            (*past_cpu_work, *past_cpu_total),
-                            CpuData {
-                                data_type: CpuDataType::Cpu(itx),
-                                cpu_usage: 0.0,
-                            },
-                        )
-                    }
-                })
-                .unzip();
+        let (new_cpu_times, cpu_deque): (Vec<PastCpuTimes>, VecDeque<CpuData>) = cpu_times
+            .collect::<Vec<_>>()
+            .await
+            .iter()
+            .zip(&*previous_cpu_times)
+            .enumerate()
+            .map(|(itx, (current_cpu, past_cpu_times))| {
+                if let Ok(cpu_time) = current_cpu {
+                    let present_times = convert_cpu_times(cpu_time);
+
+                    (
+                        present_times,
+                        cpu_data(CpuDataType::Cpu(itx), past_cpu_times, &present_times),
+                    )
+                } else {
+                    (
+                        *past_cpu_times,
+                        CpuData {
+                            data_type: CpuDataType::Cpu(itx),
+                            cpu_usage: 0.0,
+                            state_usage: None,
+                            frequency: None,
+                        },
+                    )
+                }
+            })
+            .unzip();
 
         *previous_cpu_times = new_cpu_times;
         cpu_deque
     };
 
+    #[cfg(target_os = "linux")]
+    set_cpu_numbers(&mut cpu_deque);
+
     // Get average CPU if needed... and slap it at the top
     if show_average_cpu {
         let cpu_time = heim::cpu::time().await?;
 
-        let (cpu_usage, new_average_cpu_time) = if let Some((past_cpu_work, past_cpu_total)) =
-            previous_average_cpu_time
-        {
-            let present_times = convert_cpu_times(&cpu_time);
-            (
-                calculate_cpu_usage_percentage((*past_cpu_work, *past_cpu_total), present_times),
-                present_times,
-            )
-        } else {
-            // Again, we need to do a quick timeout...
-            futures_timer::Delay::new(std::time::Duration::from_millis(100)).await;
-            let second_cpu_time = heim::cpu::time().await?;
-
-            let present_times = convert_cpu_times(&second_cpu_time);
-            (
-                calculate_cpu_usage_percentage(convert_cpu_times(&cpu_time), present_times),
-                present_times,
-            )
-        };
+        let (average_cpu_data, new_average_cpu_time) =
+            if let Some(past_cpu_times) = previous_average_cpu_time {
+                let present_times = convert_cpu_times(&cpu_time);
+                (
+                    cpu_data(CpuDataType::Avg, past_cpu_times, &present_times),
+                    present_times,
+                )
+            } else {
+                // Again, we need to do a quick timeout...
+                futures_timer::Delay::new(std::time::Duration::from_millis(100)).await;
+                let second_cpu_time = heim::cpu::time().await?;
+
+                let present_times = convert_cpu_times(&second_cpu_time);
+                (
+                    cpu_data(
+                        CpuDataType::Avg,
+                        &convert_cpu_times(&cpu_time),
+                        &present_times,
+                    ),
+                    present_times,
+                )
+            };
 
         *previous_average_cpu_time = Some(new_average_cpu_time);
-        cpu_deque.push_front(CpuData {
-            data_type: CpuDataType::Avg,
-            cpu_usage,
-        })
+        cpu_deque.push_front(average_cpu_data)
     }
 
     // Ok(Vec::from(cpu_deque.drain(0..3).collect::<Vec<_>>())) // For artificially limiting the CPU results
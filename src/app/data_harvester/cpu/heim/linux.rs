@@ -1,18 +1,73 @@
 //! Linux-specific functions regarding CPU usage.
 
-use crate::components::tui_widget::time_chart::Point;
+use std::collections::VecDeque;
+
+use crate::data_harvester::cpu::{CpuData, CpuDataType, CpuStates, PastCpuTimes};
 use heim::cpu::os::linux::CpuTimeExt;
+use heim::units::time::second;
+
+pub fn convert_cpu_times(cpu_time: &heim::cpu::CpuTime) -> PastCpuTimes {
+    let states = CpuStates {
+        user: cpu_time.user().get::<second>(),
+        nice: cpu_time.nice().get::<second>(),
+        system: cpu_time.system().get::<second>(),
+        irq: cpu_time.irq().get::<second>(),
+        soft_irq: cpu_time.soft_irq().get::<second>(),
+        io_wait: cpu_time.io_wait().get::<second>(),
+        steal: cpu_time.steal().get::<second>(),
+    };
+    let working_time =
+        states.user + states.nice + states.system + states.irq + states.soft_irq + states.steal;
+
+    PastCpuTimes {
+        work: working_time,
+        total: working_time + cpu_time.idle().get::<second>() + states.io_wait,
+        states: Some(states),
+    }
+}
+
+/// Replaces the position of each core in the harvest with its CPU number.  Offline CPUs are left
+/// out of `/proc/stat` (which is where heim reads the CPU times from), so once one is offline, the
+/// position of every later core no longer matches its number.
+pub fn set_cpu_numbers(cpu_deque: &mut VecDeque<CpuData>) {
+    if let Ok(stat) = std::fs::read_to_string("/proc/stat") {
+        let numbers = parse_cpu_numbers(&stat);
+
+        // If a CPU went on or offline in between reads, then just leave things be until next time.
+        if numbers.len() == cpu_deque.len() {
+            for (cpu, number) in cpu_deque.iter_mut().zip(numbers) {
+                cpu.data_type = CpuDataType::Cpu(number);
+            }
+        }
+    }
+}
+
+/// Parses the number of each `cpuN` line in `/proc/stat`, in order.
+fn parse_cpu_numbers(stat: &str) -> Vec<usize> {
+    stat.lines()
+        .filter_map(|line| {
+            line.split_whitespace()
+                .next()?
+                .strip_prefix("cpu")?
+                .parse::<usize>()
+                .ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cpu_numbers() {
+        let stat = "cpu  100 0 50 1000 0 0 0 0 0 0\n\
+                    cpu0 50 0 25 500 0 0 0 0 0 0\n\
+                    cpu1 50 0 25 500 0 0 0 0 0 0\n\
+                    cpu3 0 0 0 0 0 0 0 0 0 0\n\
+                    intr 1234 0 0\n\
+                    ctxt 5678\n";
 
-pub fn convert_cpu_times(cpu_time: &heim::cpu::CpuTime) -> Point {
-    let working_time: f64 = (cpu_time.user()
-        + cpu_time.nice()
-        + cpu_time.system()
-        + cpu_time.irq()
-        + cpu_time.soft_irq()
-        + cpu_time.steal())
-    .get::<heim::units::time::second>();
-    (
-        working_time,
-        working_time + (cpu_time.idle() + cpu_time.io_wait()).get::<heim::units::time::second>(),
-    )
+        assert_eq!(parse_cpu_numbers(stat), vec![0, 1, 3]);
+    }
 }
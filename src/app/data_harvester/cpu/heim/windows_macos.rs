@@ -1,12 +1,20 @@
 //! Windows and macOS-specific functions regarding CPU usage.
 
-use crate::components::tui_widget::time_chart::Point;
+use crate::data_harvester::cpu::{CpuStates, PastCpuTimes};
+use heim::units::time::second;
 
-pub fn convert_cpu_times(cpu_time: &heim::cpu::CpuTime) -> Point {
-    let working_time: f64 =
-        (cpu_time.user() + cpu_time.system()).get::<heim::units::time::second>();
-    (
-        working_time,
-        working_time + cpu_time.idle().get::<heim::units::time::second>(),
-    )
+pub fn convert_cpu_times(cpu_time: &heim::cpu::CpuTime) -> PastCpuTimes {
+    // Only user and system time are reported here, so the other states are always zero.
+    let states = CpuStates {
+        user: cpu_time.user().get::<second>(),
+        system: cpu_time.system().get::<second>(),
+        ..Default::default()
+    };
+    let working_time = states.user + states.system;
+
+    PastCpuTimes {
+        work: working_time,
+        total: working_time + cpu_time.idle().get::<second>(),
+        states: Some(states),
+    }
 }
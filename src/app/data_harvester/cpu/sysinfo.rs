@@ -5,13 +5,12 @@ use std::collections::VecDeque;
 
 use sysinfo::{CpuExt, LoadAvg, System, SystemExt};
 
-use super::{CpuData, CpuDataType, CpuHarvest, PastCpuTotal, PastCpuWork};
+use super::{CpuData, CpuDataType, CpuHarvest, PastCpuTimes};
 use crate::app::data_harvester::cpu::LoadAvgHarvest;
 
 pub async fn get_cpu_data_list(
-    sys: &sysinfo::System, show_average_cpu: bool,
-    _previous_cpu_times: &mut [(PastCpuWork, PastCpuTotal)],
-    _previous_average_cpu_time: &mut Option<(PastCpuWork, PastCpuTotal)>,
+    sys: &sysinfo::System, show_average_cpu: bool, _previous_cpu_times: &mut [PastCpuTimes],
+    _previous_average_cpu_time: &mut Option<PastCpuTimes>,
 ) -> crate::error::Result<CpuHarvest> {
     let mut cpu_deque: VecDeque<_> = sys
         .cpus()
@@ -20,6 +19,8 @@ pub async fn get_cpu_data_list(
         .map(|(i, cpu)| CpuData {
             data_type: CpuDataType::Cpu(i),
             cpu_usage: cpu.cpu_usage() as f64,
+            state_usage: None,
+            frequency: None,
         })
         .collect();
 
@@ -29,6 +30,8 @@ pub async fn get_cpu_data_list(
         cpu_deque.push_front(CpuData {
             data_type: CpuDataType::Avg,
             cpu_usage: cpu.cpu_usage() as f64,
+            state_usage: None,
+            frequency: None,
         })
     }
 
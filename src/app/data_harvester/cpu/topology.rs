@@ -0,0 +1,316 @@
+//! CPU topology, used to group cores by socket, NUMA node, or core type.
+//!
+//! This is only read on Linux, from `/sys/devices/system/cpu` and `/sys/devices/system/node`.
+//! Hybrid core types come from the `cpu_core` and `cpu_atom` PMU devices on Intel, falling back to
+//! differences in `cpu_capacity` on ARM.
+
+use std::collections::BTreeMap;
+
+use super::{
+    frequency::average_frequency, CoreType, CpuData, CpuDataType, CpuGroup, CpuGrouping,
+    CpuHarvest, CpuStates,
+};
+
+/// Where a core sits in the system.  Anything that couldn't be determined is `None`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CpuTopology {
+    pub socket: Option<usize>,
+    pub numa_node: Option<usize>,
+    pub core_type: Option<CoreType>,
+}
+
+impl CpuTopology {
+    /// Returns the group this core belongs to for a given grouping.
+    pub fn group(&self, grouping: CpuGrouping) -> Option<CpuGroup> {
+        match grouping {
+            CpuGrouping::Socket => self.socket.map(CpuGroup::Socket),
+            CpuGrouping::NumaNode => self.numa_node.map(CpuGroup::NumaNode),
+            CpuGrouping::CoreType => self.core_type.map(CpuGroup::CoreType),
+        }
+    }
+}
+
+/// Returns the topology of each core, indexed by core number.
+#[cfg(target_os = "linux")]
+pub fn read_cpu_topology() -> Vec<CpuTopology> {
+    read_cpu_topology_from(std::path::Path::new("/sys/devices"))
+}
+
+/// Returns the topology of each core, indexed by core number.  This isn't supported outside of
+/// Linux, so nothing is returned.
+#[cfg(not(target_os = "linux"))]
+pub fn read_cpu_topology() -> Vec<CpuTopology> {
+    vec![]
+}
+
+/// Reads the topology of each core from a sysfs devices directory, usually `/sys/devices`.
+#[cfg(target_os = "linux")]
+fn read_cpu_topology_from(sys_devices: &std::path::Path) -> Vec<CpuTopology> {
+    use std::fs;
+
+    let read_trimmed = |path: std::path::PathBuf| {
+        fs::read_to_string(path)
+            .ok()
+            .map(|contents| contents.trim().to_string())
+    };
+
+    let cpu_dir = sys_devices.join("system/cpu");
+    let mut cores = match fs::read_dir(&cpu_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                entry
+                    .file_name()
+                    .to_str()
+                    .and_then(|name| name.strip_prefix("cpu"))
+                    .and_then(|index| index.parse::<usize>().ok())
+            })
+            .collect::<Vec<_>>(),
+        Err(_) => return vec![],
+    };
+    cores.sort_unstable();
+
+    let num_cores = match cores.last() {
+        Some(last) => last + 1,
+        None => return vec![],
+    };
+    let mut topology = vec![CpuTopology::default(); num_cores];
+
+    // Sockets.  A package ID of -1 means that it's unknown.
+    for &core in &cores {
+        topology[core].socket =
+            read_trimmed(cpu_dir.join(format!("cpu{}/topology/physical_package_id", core)))
+                .and_then(|id| id.parse::<usize>().ok());
+    }
+
+    // NUMA nodes.
+    if let Ok(entries) = fs::read_dir(sys_devices.join("system/node")) {
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let node = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.strip_prefix("node"))
+                .and_then(|node| node.parse::<usize>().ok());
+
+            if let (Some(node), Some(cpu_list)) = (node, read_trimmed(entry.path().join("cpulist")))
+            {
+                for core in parse_cpu_list(&cpu_list) {
+                    if let Some(core_topology) = topology.get_mut(core) {
+                        core_topology.numa_node = Some(node);
+                    }
+                }
+            }
+        }
+    }
+
+    // Hybrid core types.
+    let performance_cores = read_trimmed(sys_devices.join("cpu_core/cpus"));
+    let efficiency_cores = read_trimmed(sys_devices.join("cpu_atom/cpus"));
+    if performance_cores.is_some() || efficiency_cores.is_some() {
+        for (cpu_list, core_type) in [
+            (performance_cores, CoreType::Performance),
+            (efficiency_cores, CoreType::Efficiency),
+        ] {
+            for core in cpu_list.as_deref().map(parse_cpu_list).unwrap_or_default() {
+                if let Some(core_topology) = topology.get_mut(core) {
+                    core_topology.core_type = Some(core_type);
+                }
+            }
+        }
+    } else {
+        let capacities = cores
+            .iter()
+            .filter_map(|&core| {
+                read_trimmed(cpu_dir.join(format!("cpu{}/cpu_capacity", core)))
+                    .and_then(|capacity| capacity.parse::<u64>().ok())
+                    .map(|capacity| (core, capacity))
+            })
+            .collect::<Vec<_>>();
+
+        let max_capacity = capacities.iter().map(|(_, capacity)| *capacity).max();
+        let min_capacity = capacities.iter().map(|(_, capacity)| *capacity).min();
+        if let (Some(max_capacity), Some(min_capacity)) = (max_capacity, min_capacity) {
+            if max_capacity != min_capacity {
+                for (core, capacity) in capacities {
+                    topology[core].core_type = Some(if capacity == max_capacity {
+                        CoreType::Performance
+                    } else {
+                        CoreType::Efficiency
+                    });
+                }
+            }
+        }
+    }
+
+    topology
+}
+
+/// Parses a kernel CPU list such as "0-3,8,10-11".  Anything that can't be parsed is skipped.
+#[cfg(target_os = "linux")]
+pub fn parse_cpu_list(cpu_list: &str) -> Vec<usize> {
+    cpu_list
+        .split(',')
+        .map(str::trim)
+        .filter(|range| !range.is_empty())
+        .filter_map(|range| {
+            let (start, end) = range.split_once('-').unwrap_or((range, range));
+            Some(start.parse::<usize>().ok()?..=end.parse::<usize>().ok()?)
+        })
+        .flatten()
+        .collect()
+}
+
+/// Reorders the harvest so that each group's cores follow an entry with their combined usage.  The
+/// average stays first, followed by any cores without a group, so that every core after a group
+/// entry belongs to it.  Nothing is changed if no core has a group.
+pub fn group_cpu_data(
+    cpu_harvest: &mut CpuHarvest, topology: &[CpuTopology], grouping: CpuGrouping,
+) {
+    let group_of = |cpu: &CpuData| match cpu.data_type {
+        CpuDataType::Cpu(index) => topology
+            .get(index)
+            .and_then(|core_topology| core_topology.group(grouping)),
+        _ => None,
+    };
+
+    if !cpu_harvest.iter().any(|cpu| group_of(cpu).is_some()) {
+        return;
+    }
+
+    let mut average = vec![];
+    let mut groups: BTreeMap<CpuGroup, Vec<CpuData>> = BTreeMap::new();
+    let mut ungrouped = vec![];
+    for cpu in cpu_harvest.drain(..) {
+        match (cpu.data_type, group_of(&cpu)) {
+            (CpuDataType::Avg, _) => average.push(cpu),
+            (_, Some(group)) => groups.entry(group).or_default().push(cpu),
+            (_, None) => ungrouped.push(cpu),
+        }
+    }
+
+    cpu_harvest.extend(average);
+    cpu_harvest.extend(ungrouped);
+    for (group, cores) in groups {
+        cpu_harvest.push(CpuData {
+            data_type: CpuDataType::Group(group),
+            cpu_usage: cores.iter().map(|cpu| cpu.cpu_usage).sum::<f64>() / cores.len() as f64,
+            state_usage: CpuStates::mean(cores.iter().filter_map(|cpu| cpu.state_usage.as_ref())),
+            frequency: average_frequency(cores.iter().filter_map(|cpu| cpu.frequency)),
+        });
+        cpu_harvest.extend(cores);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cpu(data_type: CpuDataType, cpu_usage: f64) -> CpuData {
+        CpuData {
+            data_type,
+            cpu_usage,
+            state_usage: None,
+            frequency: None,
+        }
+    }
+
+    #[test]
+    fn test_group_cpu_data() {
+        let topology = [
+            CpuTopology {
+                socket: Some(1),
+                ..Default::default()
+            },
+            CpuTopology {
+                socket: Some(0),
+                ..Default::default()
+            },
+            CpuTopology {
+                socket: Some(1),
+                ..Default::default()
+            },
+        ];
+        let mut harvest = vec![
+            cpu(CpuDataType::Avg, 50.0),
+            cpu(CpuDataType::Cpu(0), 20.0),
+            cpu(CpuDataType::Cpu(1), 50.0),
+            cpu(CpuDataType::Cpu(2), 40.0),
+            cpu(CpuDataType::Cpu(3), 90.0),
+        ];
+
+        group_cpu_data(&mut harvest, &topology, CpuGrouping::Socket);
+        assert_eq!(
+            harvest
+                .iter()
+                .map(|cpu| (cpu.data_type, cpu.cpu_usage))
+                .collect::<Vec<_>>(),
+            vec![
+                (CpuDataType::Avg, 50.0),
+                (CpuDataType::Cpu(3), 90.0),
+                (CpuDataType::Group(CpuGroup::Socket(0)), 50.0),
+                (CpuDataType::Cpu(1), 50.0),
+                (CpuDataType::Group(CpuGroup::Socket(1)), 30.0),
+                (CpuDataType::Cpu(0), 20.0),
+                (CpuDataType::Cpu(2), 40.0),
+            ]
+        );
+
+        // None of the cores have a known core type, so nothing should change.
+        let mut harvest = vec![
+            cpu(CpuDataType::Cpu(0), 20.0),
+            cpu(CpuDataType::Cpu(1), 50.0),
+        ];
+        group_cpu_data(&mut harvest, &topology, CpuGrouping::CoreType);
+        assert_eq!(harvest.len(), 2);
+        assert_eq!(harvest[0].data_type, CpuDataType::Cpu(0));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_cpu_list() {
+        assert_eq!(parse_cpu_list("0-3,8,10-11\n"), vec![0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(parse_cpu_list("5"), vec![5]);
+        assert_eq!(parse_cpu_list(""), Vec::<usize>::new());
+        assert_eq!(parse_cpu_list("a,2"), vec![2]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_read_cpu_topology() {
+        let dir = crate::utils::test_utils::TempDir::new("topology");
+        for (core, package) in [(0, "0"), (1, "0"), (2, "1"), (3, "-1")] {
+            dir.write(
+                format!("system/cpu/cpu{}/topology/physical_package_id", core),
+                package,
+            );
+        }
+        dir.create_dir("system/cpu/cpufreq");
+        dir.write("system/node/node0/cpulist", "0-1\n");
+        dir.write("system/node/node1/cpulist", "2-3\n");
+        dir.write("cpu_core/cpus", "0,2\n");
+        dir.write("cpu_atom/cpus", "1,3\n");
+
+        let topology = read_cpu_topology_from(dir.path());
+        assert_eq!(topology.len(), 4);
+        assert_eq!(
+            topology[2],
+            CpuTopology {
+                socket: Some(1),
+                numa_node: Some(1),
+                core_type: Some(CoreType::Performance),
+            }
+        );
+        assert_eq!(
+            topology[3],
+            CpuTopology {
+                socket: None,
+                numa_node: Some(1),
+                core_type: Some(CoreType::Efficiency),
+            }
+        );
+        assert_eq!(
+            topology[1].group(CpuGrouping::NumaNode),
+            Some(CpuGroup::NumaNode(0))
+        );
+    }
+}
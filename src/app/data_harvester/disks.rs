@@ -3,6 +3,13 @@
 //! For Linux, macOS, and Windows, this is handled by heim. For FreeBSD there is a custom
 //! implementation.
 
+use std::time::Duration;
+#[cfg(unix)]
+use std::{collections::HashSet, sync::Mutex};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
 cfg_if::cfg_if! {
     if #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))] {
         pub mod heim;
@@ -20,12 +27,433 @@ pub struct DiskHarvest {
     pub free_space: Option<u64>,
     pub used_space: Option<u64>,
     pub total_space: Option<u64>,
+    /// The type of the filesystem, such as "ext4".
+    pub fs_type: Option<String>,
+    pub total_inodes: Option<u64>,
+    pub free_inodes: Option<u64>,
+    pub read_only: Option<bool>,
+}
+
+/// How long to wait for a network or FUSE filesystem to answer before giving up on it.
+pub const REMOTE_FS_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Filesystem types that are served over the network, and so may stop answering if the server
+/// goes away. FUSE filesystems are matched separately, as their types all start with "fuse".
+const REMOTE_FS_TYPES: [&str; 12] = [
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "ncpfs",
+    "afs",
+    "9p",
+    "ceph",
+    "glusterfs",
+    "lustre",
+    "davfs",
+];
+
+/// Mount points with a `statvfs` call still waiting on an answer, so that an unresponsive
+/// filesystem doesn't leave behind another blocked thread every update.
+#[cfg(unix)]
+static PENDING_FS_STATS: Lazy<Mutex<HashSet<String>>> = Lazy::new(Default::default);
+
+/// Returns whether a filesystem type is served over the network or by a FUSE process, either of
+/// which might never answer a `statvfs` call.
+pub fn is_remote_fs(fs_type: &str) -> bool {
+    fs_type.starts_with("fuse") || fs_type == "sshfs" || REMOTE_FS_TYPES.contains(&fs_type)
+}
+
+/// The space, inode counts, and flags of a mounted filesystem.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FsStats {
+    pub total_space: u64,
+    /// The space available to unprivileged users.
+    pub free_space: u64,
+    pub used_space: u64,
+    /// The total number of inodes, or `None` if the filesystem doesn't have a fixed number of
+    /// them (such as btrfs).
+    pub total_inodes: Option<u64>,
+    pub free_inodes: Option<u64>,
+    pub read_only: bool,
+}
+
+/// Returns the inode counts and flags of the filesystem mounted at a path, using `statvfs`.
+#[cfg(unix)]
+pub fn get_fs_stats(mount_point: &str) -> Option<FsStats> {
+    let path = std::ffi::CString::new(mount_point).ok()?;
+    let mut stats = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+
+    // SAFETY: `path` is a valid C string, and `stats` is only read if `statvfs` filled it in.
+    let result = unsafe { libc::statvfs(path.as_ptr(), stats.as_mut_ptr()) };
+    if result != 0 {
+        return None;
+    }
+    let stats = unsafe { stats.assume_init() };
+
+    // Filesystems that allocate inodes as needed (such as btrfs) report zero of them.
+    #[allow(clippy::unnecessary_cast)]
+    let (total_inodes, free_inodes) = if stats.f_files > 0 {
+        (Some(stats.f_files as u64), Some(stats.f_ffree as u64))
+    } else {
+        (None, None)
+    };
+
+    #[allow(clippy::unnecessary_cast)]
+    let block_size = stats.f_frsize as u64;
+    #[allow(clippy::unnecessary_cast)]
+    let (blocks, free_blocks, available_blocks) = (
+        stats.f_blocks as u64,
+        stats.f_bfree as u64,
+        stats.f_bavail as u64,
+    );
+
+    Some(FsStats {
+        total_space: blocks * block_size,
+        free_space: available_blocks * block_size,
+        used_space: blocks.saturating_sub(free_blocks) * block_size,
+        total_inodes,
+        free_inodes,
+        read_only: stats.f_flag & libc::ST_RDONLY != 0,
+    })
+}
+
+/// Like [`get_fs_stats`], but gives up and returns `None` if the filesystem doesn't answer within
+/// `timeout`. The call is made on another thread, which is left behind if it never returns.
+#[cfg(unix)]
+pub fn get_fs_stats_with_timeout(mount_point: &str, timeout: Duration) -> Option<FsStats> {
+    // If the last call hasn't returned yet, this one won't either.
+    if !PENDING_FS_STATS
+        .lock()
+        .ok()?
+        .insert(mount_point.to_string())
+    {
+        return None;
+    }
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let mount_point = mount_point.to_string();
+    std::thread::spawn(move || {
+        let fs_stats = get_fs_stats(&mount_point);
+        if let Ok(mut pending) = PENDING_FS_STATS.lock() {
+            pending.remove(&mount_point);
+        }
+        let _ = sender.send(fs_stats);
+    });
+
+    receiver.recv_timeout(timeout).ok().flatten()
 }
 
 #[derive(Clone, Debug)]
 pub struct IoData {
     pub read_bytes: u64,
     pub write_bytes: u64,
+    /// Operation counts and timings, which are only available on Linux.
+    pub stats: Option<DiskStats>,
+}
+
+impl IoData {
+    /// Returns the read and write rates since a previous reading, given the time that passed.
+    pub fn rate_since(&self, previous: &IoData, elapsed: Duration) -> IoRate {
+        let seconds = elapsed.as_secs_f64();
+        let rate = |current: u64, previous: u64| {
+            if seconds > 0.0 {
+                current.saturating_sub(previous) as f64 / seconds
+            } else {
+                0.0
+            }
+        };
+
+        IoRate {
+            read: rate(self.read_bytes, previous.read_bytes),
+            write: rate(self.write_bytes, previous.write_bytes),
+            activity: match (&self.stats, &previous.stats) {
+                (Some(stats), Some(previous_stats)) => {
+                    Some(stats.activity_since(previous_stats, elapsed))
+                }
+                _ => None,
+            },
+        }
+    }
+}
+
+/// The read and write rates of a device between two updates, in bytes per second.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct IoRate {
+    pub read: f64,
+    pub write: f64,
+    pub activity: Option<DiskActivity>,
+}
+
+/// A device's counters from `/proc/diskstats`.  Times are in milliseconds, and everything but
+/// `in_flight` is cumulative since boot.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DiskStats {
+    pub reads: u64,
+    pub reads_merged: u64,
+    pub read_time: u64,
+    pub writes: u64,
+    pub writes_merged: u64,
+    pub write_time: u64,
+    /// The number of requests that are currently queued or being serviced.
+    pub in_flight: u64,
+    /// The time that the device had at least one request in flight.
+    pub io_time: u64,
+    /// The time spent on every request, so overlapping requests are counted more than once.
+    pub weighted_io_time: u64,
+}
+
+impl DiskStats {
+    /// Returns the device's activity since a previous reading, given the time that passed.
+    pub fn activity_since(&self, previous: &DiskStats, elapsed: Duration) -> DiskActivity {
+        let seconds = elapsed.as_secs_f64();
+        let milliseconds = seconds * 1000.0;
+        let delta = |current: u64, previous: u64| current.saturating_sub(previous) as f64;
+        let per_second = |count: f64| if seconds > 0.0 { count / seconds } else { 0.0 };
+        let per_operation = |time: f64, operations: f64| {
+            if operations > 0.0 {
+                Some(time / operations)
+            } else {
+                None
+            }
+        };
+
+        let reads = delta(self.reads, previous.reads);
+        let writes = delta(self.writes, previous.writes);
+        let read_time = delta(self.read_time, previous.read_time);
+        let write_time = delta(self.write_time, previous.write_time);
+        let (queue_depth, utilisation) = if milliseconds > 0.0 {
+            (
+                delta(self.weighted_io_time, previous.weighted_io_time) / milliseconds,
+                (delta(self.io_time, previous.io_time) / milliseconds * 100.0).min(100.0),
+            )
+        } else {
+            (0.0, 0.0)
+        };
+
+        DiskActivity {
+            reads: per_second(reads),
+            writes: per_second(writes),
+            reads_merged: per_second(delta(self.reads_merged, previous.reads_merged)),
+            writes_merged: per_second(delta(self.writes_merged, previous.writes_merged)),
+            read_await: per_operation(read_time, reads),
+            write_await: per_operation(write_time, writes),
+            io_await: per_operation(read_time + write_time, reads + writes),
+            in_flight: self.in_flight,
+            queue_depth,
+            utilisation,
+        }
+    }
+}
+
+/// A device's activity between two updates, worked out in the same way as `iostat -x`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DiskActivity {
+    /// Completed reads per second.
+    pub reads: f64,
+    /// Completed writes per second.
+    pub writes: f64,
+    /// Reads per second that were merged with another one before being sent to the device.
+    pub reads_merged: f64,
+    /// Writes per second that were merged with another one before being sent to the device.
+    pub writes_merged: f64,
+    /// The average time that each read took in milliseconds, including time spent queued.
+    pub read_await: Option<f64>,
+    /// The average time that each write took in milliseconds, including time spent queued.
+    pub write_await: Option<f64>,
+    /// The average time that each read or write took in milliseconds.
+    pub io_await: Option<f64>,
+    /// The number of requests in flight at the time of the reading.
+    pub in_flight: u64,
+    /// The average number of requests that were queued or being serviced.
+    pub queue_depth: f64,
+    /// The percentage of time that the device was busy.
+    pub utilisation: f64,
+}
+
+impl DiskActivity {
+    /// Returns the completed reads and writes per second.
+    pub fn iops(&self) -> f64 {
+        self.reads + self.writes
+    }
 }
 
 pub type IoHarvest = std::collections::HashMap<String, Option<IoData>>;
+
+/// Returns the name that a disk's IO counters are stored under in an [`IoHarvest`], given the
+/// name of the disk.
+pub fn io_device_name(disk_name: &str) -> Option<&str> {
+    let trim = disk_name.split('/').last()?;
+
+    if cfg!(target_os = "macos") {
+        // Must trim one level further for macOS!
+        static DISK_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"disk\d+").unwrap());
+        DISK_REGEX.find(trim).map(|disk_trim| disk_trim.as_str())
+    } else {
+        Some(trim)
+    }
+}
+
+/// What kind of block device something is, going by how the kernel names and describes it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockDeviceKind {
+    Disk,
+    Partition,
+    /// A device-mapper device set up by cryptsetup, such as a LUKS volume.
+    Crypt,
+    /// A device-mapper device set up by LVM, such as a logical volume.
+    Lvm,
+    /// Any other device-mapper device.
+    Mapper,
+    Raid,
+    Loop,
+}
+
+/// The state of an md RAID array, from `/proc/mdstat`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RaidStatus {
+    /// The RAID level, such as "raid1".  Inactive arrays don't have one.
+    pub level: Option<String>,
+    pub active: bool,
+    /// The number of devices that the array should have.
+    pub total_devices: Option<u32>,
+    /// The number of devices that the array has working.
+    pub working_devices: Option<u32>,
+    /// The action that the array is busy with, such as "resync" or "recovery".
+    pub action: Option<String>,
+    /// How far through the action the array is, as a percentage.  This is `None` if the action is
+    /// still waiting to start.
+    pub progress: Option<f64>,
+}
+
+impl RaidStatus {
+    /// Returns whether the array is missing any of its devices.
+    pub fn is_degraded(&self) -> bool {
+        matches!(
+            (self.total_devices, self.working_devices),
+            (Some(total), Some(working)) if working < total
+        )
+    }
+}
+
+/// A block device, and the devices that it is built on top of.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockDevice {
+    /// The kernel's name for the device, such as "sda1" or "dm-0".
+    pub name: String,
+    /// The name that device-mapper gave the device, such as "vg-root".
+    pub label: Option<String>,
+    pub kind: BlockDeviceKind,
+    /// The size of the device in bytes.
+    pub size: Option<u64>,
+    /// The devices that this one is built on: the disk of a partition, or the devices underneath a
+    /// device-mapper device or RAID array.
+    pub parents: Vec<String>,
+    pub raid: Option<RaidStatus>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_remote_fs() {
+        assert!(is_remote_fs("nfs4"));
+        assert!(is_remote_fs("cifs"));
+        assert!(is_remote_fs("fuse.sshfs"));
+        assert!(!is_remote_fs("ext4"));
+        assert!(!is_remote_fs("tmpfs"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_get_fs_stats_with_timeout() {
+        let fs_stats = get_fs_stats_with_timeout("/", Duration::from_secs(10)).unwrap();
+        assert!(fs_stats.used_space <= fs_stats.total_space);
+        assert!(!PENDING_FS_STATS.lock().unwrap().contains("/"));
+    }
+
+    #[test]
+    fn test_rate_since() {
+        let previous = IoData {
+            read_bytes: 1000,
+            write_bytes: 5000,
+            stats: None,
+        };
+        let current = IoData {
+            read_bytes: 3000,
+            write_bytes: 4000,
+            stats: None,
+        };
+
+        assert_eq!(
+            current.rate_since(&previous, Duration::from_secs(2)),
+            IoRate {
+                read: 1000.0,
+                write: 0.0,
+                activity: None,
+            }
+        );
+        assert_eq!(
+            current.rate_since(&previous, Duration::ZERO),
+            IoRate::default()
+        );
+    }
+
+    #[test]
+    fn test_activity_since() {
+        let previous = DiskStats {
+            reads: 100,
+            reads_merged: 10,
+            read_time: 400,
+            writes: 50,
+            writes_merged: 0,
+            write_time: 1000,
+            in_flight: 0,
+            io_time: 1000,
+            weighted_io_time: 1400,
+        };
+        let current = DiskStats {
+            reads: 300,
+            reads_merged: 30,
+            read_time: 800,
+            writes: 250,
+            writes_merged: 100,
+            write_time: 4200,
+            in_flight: 3,
+            io_time: 2500,
+            weighted_io_time: 5400,
+        };
+
+        assert_eq!(
+            current.activity_since(&previous, Duration::from_secs(2)),
+            DiskActivity {
+                reads: 100.0,
+                writes: 100.0,
+                reads_merged: 10.0,
+                writes_merged: 50.0,
+                read_await: Some(2.0),
+                write_await: Some(16.0),
+                io_await: Some(9.0),
+                in_flight: 3,
+                queue_depth: 2.0,
+                utilisation: 75.0,
+            }
+        );
+
+        let idle = previous.activity_since(&previous, Duration::from_secs(1));
+        assert_eq!(
+            (idle.iops(), idle.io_await, idle.utilisation),
+            (0.0, None, 0.0)
+        );
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn test_io_device_name() {
+        assert_eq!(io_device_name("/dev/sda1"), Some("sda1"));
+        assert_eq!(io_device_name("/dev/mapper/root"), Some("root"));
+        assert_eq!(io_device_name("tmpfs"), Some("tmpfs"));
+    }
+}
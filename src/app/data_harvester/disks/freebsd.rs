@@ -28,7 +28,7 @@ pub async fn get_io_usage(actually_get: bool) -> crate::utils::error::Result<Opt
         return Ok(None);
     }
 
-    let io_harvest = get_disk_info().map(|storage_system_information| {
+    let io_harvest = get_disk_info(false).map(|storage_system_information| {
         storage_system_information
             .filesystem
             .into_iter()
@@ -39,13 +39,19 @@ pub async fn get_io_usage(actually_get: bool) -> crate::utils::error::Result<Opt
 }
 
 pub async fn get_disk_usage(
-    actually_get: bool, disk_filter: &Option<Filter>, mount_filter: &Option<Filter>,
+    actually_get: bool,
+    all_mounts: bool,
+    disk_filter: &Option<Filter>,
+    mount_filter: &Option<Filter>,
+    // The filesystem type isn't collected on FreeBSD, so there is nothing for this to filter on.
+    _fs_filter: &Option<Filter>,
 ) -> crate::utils::error::Result<Option<Vec<DiskHarvest>>> {
     if !actually_get {
         return Ok(None);
     }
 
-    let mut vec_disks: Vec<DiskHarvest> = get_disk_info().map(|storage_system_information| {
+    let disk_info = get_disk_info(all_mounts);
+    let mut vec_disks: Vec<DiskHarvest> = disk_info.map(|storage_system_information| {
         storage_system_information
             .filesystem
             .into_iter()
@@ -65,12 +71,23 @@ pub async fn get_disk_usage(
                 if matches_allow_list(filter_check_map.as_slice())
                     || !matches_ignore_list(filter_check_map.as_slice())
                 {
+                    // The types of the filesystems aren't known here, so any of them could be a
+                    // network filesystem that never answers.
+                    let fs_stats = if all_mounts {
+                        super::get_fs_stats_with_timeout(&disk.mounted_on, super::REMOTE_FS_TIMEOUT)
+                    } else {
+                        super::get_fs_stats(&disk.mounted_on)
+                    };
                     Some(DiskHarvest {
                         free_space: Some(disk.available_blocks * 1024),
                         used_space: Some(disk.used_blocks * 1024),
                         total_space: Some(disk.total_blocks * 1024),
                         mount_point: disk.mounted_on,
                         name: disk.name,
+                        fs_type: None,
+                        total_inodes: fs_stats.and_then(|fs_stats| fs_stats.total_inodes),
+                        free_inodes: fs_stats.and_then(|fs_stats| fs_stats.free_inodes),
+                        read_only: fs_stats.map(|fs_stats| fs_stats.read_only),
                     })
                 } else {
                     None
@@ -97,9 +114,23 @@ fn matches_ignore_list(filter_check_map: &[(&Option<Filter>, &String)]) -> bool
     })
 }
 
-fn get_disk_info() -> io::Result<StorageSystemInformation> {
-    let output = std::process::Command::new("df")
-        .args(&["--libxo", "json", "-k", "-t", "ufs,msdosfs,zfs"])
-        .output()?;
-    deserialize_xo("storage-system-information", &output.stdout)
+fn get_disk_info(all_mounts: bool) -> io::Result<StorageSystemInformation> {
+    if all_mounts {
+        // `-n` uses the statistics that the kernel already has, rather than asking each filesystem
+        // (which can hang on an unresponsive network filesystem).
+        let output = std::process::Command::new("df")
+            .args(&["--libxo", "json", "-k", "-n"])
+            .output()?;
+        let mut info: StorageSystemInformation =
+            deserialize_xo("storage-system-information", &output.stdout)?;
+
+        // Filesystems without any space, such as devfs, aren't worth listing.
+        info.filesystem.retain(|disk| disk.total_blocks > 0);
+        Ok(info)
+    } else {
+        let output = std::process::Command::new("df")
+            .args(&["--libxo", "json", "-k", "-t", "ufs,msdosfs,zfs"])
+            .output()?;
+        deserialize_xo("storage-system-information", &output.stdout)
+    }
 }
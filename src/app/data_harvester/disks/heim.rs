@@ -36,16 +36,28 @@ pub async fn get_io_usage(actually_get: bool) -> crate::utils::error::Result<Opt
                 Some(IoData {
                     read_bytes: io.read_bytes().get::<heim::units::information::byte>(),
                     write_bytes: io.write_bytes().get::<heim::units::information::byte>(),
+                    stats: None,
                 }),
             );
         }
     }
 
+    #[cfg(target_os = "linux")]
+    {
+        let disk_stats = get_disk_stats();
+        io_hash.iter_mut().for_each(|(name, io)| {
+            if let Some(io) = io {
+                io.stats = disk_stats.get(name).copied();
+            }
+        });
+    }
+
     Ok(Some(io_hash))
 }
 
 pub async fn get_disk_usage(
-    actually_get: bool, disk_filter: &Option<Filter>, mount_filter: &Option<Filter>,
+    actually_get: bool, all_mounts: bool, disk_filter: &Option<Filter>,
+    mount_filter: &Option<Filter>, fs_filter: &Option<Filter>,
 ) -> crate::utils::error::Result<Option<Vec<DiskHarvest>>> {
     if !actually_get {
         return Ok(None);
@@ -53,8 +65,15 @@ pub async fn get_disk_usage(
 
     use futures::StreamExt;
 
+    #[cfg(target_os = "linux")]
+    let mount_info = get_mount_info();
+
     let mut vec_disks: Vec<DiskHarvest> = Vec::new();
-    let partitions_stream = heim::disk::partitions_physical().await?;
+    let partitions_stream = if all_mounts {
+        heim::disk::partitions().await?.left_stream()
+    } else {
+        heim::disk::partitions_physical().await?.right_stream()
+    };
     futures::pin_mut!(partitions_stream);
 
     while let Some(part) = partitions_stream.next().await {
@@ -67,6 +86,16 @@ pub async fn get_disk_usage(
                 .unwrap_or("Name Unavailable"))
             .to_string();
 
+            // On Linux, mountinfo also tells us whether a filesystem is read-only, and has the full
+            // type of FUSE filesystems (such as "fuse.sshfs").
+            #[cfg(target_os = "linux")]
+            let (fs_type, read_only) = match mount_info.get(&mount_point) {
+                Some(info) => (info.fs_type.clone(), Some(info.read_only)),
+                None => (partition.file_system().as_str().to_string(), None),
+            };
+            #[cfg(not(target_os = "linux"))]
+            let (fs_type, read_only) = (partition.file_system().as_str().to_string(), None);
+
             // Precedence ordering in the case where name and mount filters disagree, "allow" takes precedence over "deny".
             //
             // For implementation, we do this as follows:
@@ -74,7 +103,11 @@ pub async fn get_disk_usage(
             // 2. Is the entry denied through any filter? That is, does it match an entry in a filter where `is_list_ignored` is `true`? If so, we always deny this entry.
             // 3. Anything else is allowed.
 
-            let filter_check_map = [(disk_filter, &name), (mount_filter, &mount_point)];
+            let filter_check_map = [
+                (disk_filter, &name),
+                (mount_filter, &mount_point),
+                (fs_filter, &fs_type),
+            ];
 
             // This represents case 1.  That is, if there is a match in an allowing list - if there is, then
             // immediately allow it!
@@ -111,26 +144,66 @@ pub async fn get_disk_usage(
             };
 
             if to_keep {
+                // Network and FUSE filesystems may never answer if their server has gone away, so
+                // they are only asked (once) with a timeout, rather than through heim.
+                let is_remote = cfg!(unix) && super::is_remote_fs(&fs_type);
+
+                #[cfg(unix)]
+                let fs_stats = if is_remote {
+                    super::get_fs_stats_with_timeout(&mount_point, super::REMOTE_FS_TIMEOUT)
+                } else {
+                    super::get_fs_stats(&mount_point)
+                };
+                #[cfg(not(unix))]
+                let fs_stats: Option<super::FsStats> = None;
+
                 // The usage line can fail in some cases (for example, if you use Void Linux + LUKS,
                 // see https://github.com/ClementTsang/bottom/issues/419 for details).  As such, check
                 // it like this instead.
-                if let Ok(usage) = heim::disk::usage(partition.mount_point()).await {
-                    vec_disks.push(DiskHarvest {
-                        free_space: Some(usage.free().get::<heim::units::information::byte>()),
-                        used_space: Some(usage.used().get::<heim::units::information::byte>()),
-                        total_space: Some(usage.total().get::<heim::units::information::byte>()),
-                        mount_point,
-                        name,
-                    });
+                let usage = if is_remote {
+                    fs_stats.map(|fs_stats| {
+                        (
+                            fs_stats.free_space,
+                            fs_stats.used_space,
+                            fs_stats.total_space,
+                        )
+                    })
+                } else if let Ok(usage) = heim::disk::usage(partition.mount_point()).await {
+                    Some((
+                        usage.free().get::<heim::units::information::byte>(),
+                        usage.used().get::<heim::units::information::byte>(),
+                        usage.total().get::<heim::units::information::byte>(),
+                    ))
                 } else {
-                    vec_disks.push(DiskHarvest {
-                        free_space: None,
-                        used_space: None,
-                        total_space: None,
-                        mount_point,
-                        name,
-                    });
+                    None
+                };
+
+                // Pseudo filesystems such as proc and sysfs have no space at all, and aren't worth
+                // listing.
+                if all_mounts && matches!(usage, Some((_, _, 0))) {
+                    continue;
                 }
+
+                let (total_inodes, free_inodes, read_only) = match fs_stats {
+                    Some(fs_stats) => (
+                        fs_stats.total_inodes,
+                        fs_stats.free_inodes,
+                        read_only.or(Some(fs_stats.read_only)),
+                    ),
+                    None => (None, None, read_only),
+                };
+
+                vec_disks.push(DiskHarvest {
+                    free_space: usage.map(|(free, _, _)| free),
+                    used_space: usage.map(|(_, used, _)| used),
+                    total_space: usage.map(|(_, _, total)| total),
+                    mount_point,
+                    name,
+                    fs_type: Some(fs_type),
+                    total_inodes,
+                    free_inodes,
+                    read_only,
+                });
             }
         }
     }
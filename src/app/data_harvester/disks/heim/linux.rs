@@ -1,7 +1,11 @@
 //! Linux-specific things for Heim disk data collection.
 
+use std::{collections::HashMap, fs, path::Path};
+
 use heim::disk::Partition;
 
+use crate::data_harvester::disks::{BlockDevice, BlockDeviceKind, DiskStats, RaidStatus};
+
 pub fn get_device_name(partition: &Partition) -> String {
     if let Some(device) = partition.device() {
         // See if this disk is actually mounted elsewhere on Linux...
@@ -32,3 +36,393 @@ pub fn get_device_name(partition: &Partition) -> String {
         "Name Unavailable".to_string()
     }
 }
+
+/// Reads the operation counts and timings of each device from `/proc/diskstats`, which heim
+/// doesn't expose.
+pub fn get_disk_stats() -> HashMap<String, DiskStats> {
+    std::fs::read_to_string("/proc/diskstats")
+        .map(|diskstats| parse_diskstats(&diskstats))
+        .unwrap_or_default()
+}
+
+/// Parses `/proc/diskstats`, which has the major and minor numbers and name of a device followed by
+/// its counters on each line.  Lines with fewer than the 11 counters that every kernel since 2.6
+/// reports are skipped.
+fn parse_diskstats(diskstats: &str) -> HashMap<String, DiskStats> {
+    diskstats
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace().skip(2);
+            let name = fields.next()?;
+            let counters = fields
+                .take(11)
+                .map(|field| field.parse::<u64>().ok())
+                .collect::<Option<Vec<_>>>()?;
+            let counter = |index: usize| counters.get(index).copied();
+
+            Some((
+                name.to_string(),
+                DiskStats {
+                    reads: counter(0)?,
+                    reads_merged: counter(1)?,
+                    read_time: counter(3)?,
+                    writes: counter(4)?,
+                    writes_merged: counter(5)?,
+                    write_time: counter(7)?,
+                    in_flight: counter(8)?,
+                    io_time: counter(9)?,
+                    weighted_io_time: counter(10)?,
+                },
+            ))
+        })
+        .collect()
+}
+
+/// The type and access mode of a mounted filesystem, from `/proc/self/mountinfo`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountInfo {
+    pub fs_type: String,
+    pub read_only: bool,
+}
+
+/// Reads the type and access mode of each mounted filesystem, keyed by mount point.
+pub fn get_mount_info() -> HashMap<String, MountInfo> {
+    std::fs::read_to_string("/proc/self/mountinfo")
+        .map(|mountinfo| parse_mountinfo(&mountinfo))
+        .unwrap_or_default()
+}
+
+/// Parses `/proc/self/mountinfo`.  Each line has the mount point in the fifth field and the mount
+/// options in the sixth, followed by a variable number of optional fields, a "-", the filesystem
+/// type, the source, and the superblock options.  A filesystem is read-only if either set of
+/// options says so.  If something is mounted over another mount, the later line wins.
+fn parse_mountinfo(mountinfo: &str) -> HashMap<String, MountInfo> {
+    mountinfo
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let mount_point = fields.nth(4)?;
+            let mount_options = fields.next()?;
+            fields.find(|field| *field == "-")?;
+            let fs_type = fields.next()?;
+            let super_options = fields.nth(1).unwrap_or_default();
+
+            let is_read_only = |options: &str| options.split(',').any(|option| option == "ro");
+
+            Some((
+                unescape_mount_point(mount_point),
+                MountInfo {
+                    fs_type: fs_type.to_string(),
+                    read_only: is_read_only(mount_options) || is_read_only(super_options),
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Undoes the octal escapes that the kernel uses for spaces, tabs, newlines, and backslashes in
+/// mount points.
+fn unescape_mount_point(mount_point: &str) -> String {
+    let mut result = String::with_capacity(mount_point.len());
+    let mut rest = mount_point;
+
+    while let Some(index) = rest.find('\\') {
+        result.push_str(&rest[..index]);
+        let escape = rest.get(index + 1..index + 4);
+        match escape.and_then(|escape| u8::from_str_radix(escape, 8).ok()) {
+            Some(byte) => {
+                result.push(byte as char);
+                rest = &rest[index + 4..];
+            }
+            None => {
+                result.push('\\');
+                rest = &rest[index + 1..];
+            }
+        }
+    }
+    result.push_str(rest);
+
+    result
+}
+
+/// Reads every block device in `/sys/class/block`, along with the devices that each is built on
+/// top of, and the state of any RAID arrays.
+pub fn get_block_devices() -> Vec<BlockDevice> {
+    let mut raid = std::fs::read_to_string("/proc/mdstat")
+        .map(|mdstat| parse_mdstat(&mdstat))
+        .unwrap_or_default();
+
+    let entries = match fs::read_dir("/sys/class/block") {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+
+    entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let name = entry.file_name().into_string().ok()?;
+            let path = entry.path();
+            let read = |file: &str| fs::read_to_string(path.join(file)).ok();
+
+            let kind = if path.join("partition").exists() {
+                BlockDeviceKind::Partition
+            } else if path.join("dm").exists() {
+                read("dm/uuid")
+                    .map(|uuid| dm_kind(&uuid))
+                    .unwrap_or(BlockDeviceKind::Mapper)
+            } else if path.join("md").exists() {
+                BlockDeviceKind::Raid
+            } else if name.starts_with("loop") {
+                BlockDeviceKind::Loop
+            } else {
+                BlockDeviceKind::Disk
+            };
+
+            // A partition sits in the directory of its disk, while device-mapper devices and RAID
+            // arrays list the devices underneath them in "slaves" (which is the reverse of those
+            // devices' "holders").
+            let parents = if kind == BlockDeviceKind::Partition {
+                fs::canonicalize(&path)
+                    .ok()
+                    .and_then(|path| {
+                        let disk = path.parent()?.file_name()?;
+                        disk.to_str().map(|disk| vec![disk.to_string()])
+                    })
+                    .unwrap_or_default()
+            } else {
+                device_names(&path.join("slaves"))
+            };
+
+            Some(BlockDevice {
+                label: read("dm/name").map(|label| label.trim().to_string()),
+                size: read("size")
+                    .and_then(|size| size.trim().parse::<u64>().ok())
+                    .map(|sectors| sectors * 512),
+                parents,
+                raid: raid.remove(&name),
+                kind,
+                name,
+            })
+        })
+        .collect()
+}
+
+/// Returns the names of the devices linked to in a "slaves" or "holders" directory.
+fn device_names(path: &Path) -> Vec<String> {
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the kind of a device-mapper device from its UUID, which starts with the name of the
+/// tool that set it up.
+fn dm_kind(uuid: &str) -> BlockDeviceKind {
+    if uuid.starts_with("CRYPT-") {
+        BlockDeviceKind::Crypt
+    } else if uuid.starts_with("LVM-") {
+        BlockDeviceKind::Lvm
+    } else {
+        BlockDeviceKind::Mapper
+    }
+}
+
+/// Parses `/proc/mdstat`.  Each array starts with a line like "md0 : active raid1 sdb1[1] sda1[0]",
+/// followed by indented lines with its size and device counts (such as "[2/1]"), and then the
+/// progress of any resync, recovery, check, or reshape.
+fn parse_mdstat(mdstat: &str) -> HashMap<String, RaidStatus> {
+    const ACTIONS: [&str; 5] = ["resync", "recovery", "check", "repair", "reshape"];
+
+    let mut arrays = HashMap::new();
+    let mut current: Option<(String, RaidStatus)> = None;
+
+    for line in mdstat.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            if let Some((name, status)) = current.take() {
+                arrays.insert(name, status);
+            }
+
+            if let Some((name, description)) = line.split_once(" : ") {
+                if name.starts_with("md") {
+                    let mut words = description
+                        .split_whitespace()
+                        .filter(|word| !word.starts_with('('));
+                    let active = words.next() == Some("active");
+                    let level = if active {
+                        words.next().map(str::to_string)
+                    } else {
+                        None
+                    };
+
+                    current = Some((
+                        name.to_string(),
+                        RaidStatus {
+                            level,
+                            active,
+                            ..Default::default()
+                        },
+                    ));
+                }
+            }
+        } else if let Some((_, status)) = &mut current {
+            // Actions look like "recovery =  8.5% (...)" after a progress bar, or like
+            // "resync=PENDING" if they haven't started yet.
+            let action = ACTIONS.iter().find_map(|action| {
+                let rest = line.split_once(action)?.1.trim_start().strip_prefix('=')?;
+                Some((action, rest))
+            });
+            if let Some((action, rest)) = action {
+                status.action = Some(action.to_string());
+                status.progress = rest
+                    .split_whitespace()
+                    .next()
+                    .and_then(|progress| progress.strip_suffix('%'))
+                    .and_then(|progress| progress.parse().ok());
+                continue;
+            }
+
+            // The device counts look like "[2/1]", and are followed by the state of each device
+            // (such as "[U_]").
+            let counts = line.split_whitespace().find_map(|word| {
+                let (total, working) =
+                    word.strip_prefix('[')?.strip_suffix(']')?.split_once('/')?;
+                Some((total.parse().ok()?, working.parse().ok()?))
+            });
+            if let Some((total, working)) = counts {
+                status.total_devices = Some(total);
+                status.working_devices = Some(working);
+            }
+        }
+    }
+
+    if let Some((name, status)) = current {
+        arrays.insert(name, status);
+    }
+
+    arrays
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_diskstats() {
+        let diskstats =
+            "   8       0 sda 1200 30 96000 4000 800 120 64000 9000 2 7000 13000 0 0 0 0\n\
+                         8       1 sda1 1000 30 80000 3500 700 120 56000 8000 0 6000 11500\n\
+                         7       0 loop0 4 0\n";
+        let stats = parse_diskstats(diskstats);
+
+        assert_eq!(stats.len(), 2);
+        assert_eq!(
+            stats.get("sda"),
+            Some(&DiskStats {
+                reads: 1200,
+                reads_merged: 30,
+                read_time: 4000,
+                writes: 800,
+                writes_merged: 120,
+                write_time: 9000,
+                in_flight: 2,
+                io_time: 7000,
+                weighted_io_time: 13000,
+            })
+        );
+        assert_eq!(stats.get("sda1").map(|stats| stats.io_time), Some(6000));
+    }
+
+    #[test]
+    fn test_parse_mountinfo() {
+        let mountinfo = "22 1 8:2 / / rw,relatime shared:1 - ext4 /dev/sda2 rw,errors=remount-ro\n\
+                         23 22 8:1 / /boot ro,relatime shared:2 - vfat /dev/sda1 rw\n\
+                         24 22 7:0 / /snap/core/1 ro,nodev - squashfs /dev/loop0 ro\n\
+                         25 22 0:50 / /mnt/my\\040drive rw master:3 - fuse.sshfs host: rw\n\
+                         26 22 8:3 / /data rw - xfs /dev/sda3 ro\n";
+        let mounts = parse_mountinfo(mountinfo);
+
+        let mount = |fs_type: &str, read_only: bool| MountInfo {
+            fs_type: fs_type.to_string(),
+            read_only,
+        };
+        assert_eq!(mounts.len(), 5);
+        assert_eq!(mounts.get("/"), Some(&mount("ext4", false)));
+        assert_eq!(mounts.get("/boot"), Some(&mount("vfat", true)));
+        assert_eq!(mounts.get("/snap/core/1"), Some(&mount("squashfs", true)));
+        assert_eq!(
+            mounts.get("/mnt/my drive"),
+            Some(&mount("fuse.sshfs", false))
+        );
+        assert_eq!(mounts.get("/data"), Some(&mount("xfs", true)));
+    }
+
+    #[test]
+    fn test_unescape_mount_point() {
+        assert_eq!(unescape_mount_point("/mnt/a\\040b"), "/mnt/a b");
+        assert_eq!(unescape_mount_point("/mnt/a\\134b"), "/mnt/a\\b");
+        assert_eq!(unescape_mount_point("/mnt/a\\b"), "/mnt/a\\b");
+    }
+
+    #[test]
+    fn test_parse_mdstat() {
+        let mdstat = "Personalities : [raid1] [raid6] [raid5] [raid4]\n\
+                      md0 : active raid1 sdb1[1] sda1[0]\n      \
+                      1048512 blocks super 1.2 [2/2] [UU]\n\
+                      \n\
+                      md1 : active raid5 sdd1[3] sdc1[1] sdb2[0]\n      \
+                      2095104 blocks super 1.2 level 5, 512k chunk, algorithm 2 [3/2] [UU_]\n      \
+                      [=>...................]  recovery =  8.5% (89216/1047552) finish=0.7min\n\
+                      \n\
+                      md2 : active (auto-read-only) raid1 sde1[1] sdf1[0]\n      \
+                      524224 blocks super 1.2 [2/2] [UU]\n      \
+                      \tresync=PENDING\n\
+                      \n\
+                      md127 : inactive sdg[0](S)\n      \
+                      976630488 blocks super 1.2\n\
+                      \n\
+                      unused devices: <none>\n";
+        let arrays = parse_mdstat(mdstat);
+
+        assert_eq!(arrays.len(), 4);
+        assert_eq!(
+            arrays.get("md0"),
+            Some(&RaidStatus {
+                level: Some("raid1".to_string()),
+                active: true,
+                total_devices: Some(2),
+                working_devices: Some(2),
+                action: None,
+                progress: None,
+            })
+        );
+        assert!(!arrays["md0"].is_degraded());
+
+        let md1 = &arrays["md1"];
+        assert!(md1.is_degraded());
+        assert_eq!(md1.action.as_deref(), Some("recovery"));
+        assert_eq!(md1.progress, Some(8.5));
+
+        let md2 = &arrays["md2"];
+        assert_eq!(md2.level.as_deref(), Some("raid1"));
+        assert_eq!(md2.action.as_deref(), Some("resync"));
+        assert_eq!(md2.progress, None);
+
+        let md127 = &arrays["md127"];
+        assert!(!md127.active);
+        assert_eq!(md127.level, None);
+        assert_eq!(md127.total_devices, None);
+    }
+
+    #[test]
+    fn test_dm_kind() {
+        assert_eq!(
+            dm_kind("CRYPT-LUKS2-0123456789abcdef-luks-root"),
+            BlockDeviceKind::Crypt
+        );
+        assert_eq!(dm_kind("LVM-abcdefABCDEF"), BlockDeviceKind::Lvm);
+        assert_eq!(dm_kind("mpath-3600a0b80"), BlockDeviceKind::Mapper);
+    }
+}
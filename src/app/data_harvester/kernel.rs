@@ -0,0 +1,205 @@
+//! Data collection for kernel activity, such as context switches, interrupts, and forks.
+//!
+//! This is only available on Linux, through `/proc/stat`.
+
+use std::time::Duration;
+
+/// Counters from `/proc/stat`.  Everything but the running and blocked task counts is cumulative
+/// since boot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KernelHarvest {
+    pub context_switches: u64,
+    pub interrupts: u64,
+    pub soft_interrupts: u64,
+    pub forks: u64,
+    pub procs_running: u64,
+    pub procs_blocked: u64,
+}
+
+impl KernelHarvest {
+    /// Returns the activity since a previous reading, given the time that passed.
+    pub fn activity_since(&self, previous: &KernelHarvest, elapsed: Duration) -> KernelActivity {
+        let seconds = elapsed.as_secs_f64();
+        let rate = |current: u64, previous: u64| {
+            if seconds > 0.0 {
+                current.saturating_sub(previous) as f64 / seconds
+            } else {
+                0.0
+            }
+        };
+
+        KernelActivity {
+            context_switches: rate(self.context_switches, previous.context_switches),
+            interrupts: rate(self.interrupts, previous.interrupts),
+            soft_interrupts: rate(self.soft_interrupts, previous.soft_interrupts),
+            forks: rate(self.forks, previous.forks),
+            running: self.procs_running as f64,
+            blocked: self.procs_blocked as f64,
+        }
+    }
+}
+
+/// Kernel activity between two updates.  Counters are rates per second, while the running and
+/// blocked task counts are as of the latest update.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct KernelActivity {
+    pub context_switches: f64,
+    pub interrupts: f64,
+    pub soft_interrupts: f64,
+    pub forks: f64,
+    pub running: f64,
+    pub blocked: f64,
+}
+
+/// One of the values in [`KernelActivity`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KernelCounter {
+    ContextSwitches,
+    Interrupts,
+    SoftInterrupts,
+    Forks,
+    Running,
+    Blocked,
+}
+
+impl KernelCounter {
+    pub const ALL: [KernelCounter; 6] = [
+        KernelCounter::ContextSwitches,
+        KernelCounter::Interrupts,
+        KernelCounter::SoftInterrupts,
+        KernelCounter::Forks,
+        KernelCounter::Running,
+        KernelCounter::Blocked,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            KernelCounter::ContextSwitches => "Ctx switches",
+            KernelCounter::Interrupts => "Interrupts",
+            KernelCounter::SoftInterrupts => "Soft IRQs",
+            KernelCounter::Forks => "Forks",
+            KernelCounter::Running => "Running",
+            KernelCounter::Blocked => "Blocked",
+        }
+    }
+
+    /// Whether this is a rate per second rather than a count.
+    pub fn is_rate(&self) -> bool {
+        !matches!(self, KernelCounter::Running | KernelCounter::Blocked)
+    }
+
+    pub fn value(&self, activity: &KernelActivity) -> f64 {
+        match self {
+            KernelCounter::ContextSwitches => activity.context_switches,
+            KernelCounter::Interrupts => activity.interrupts,
+            KernelCounter::SoftInterrupts => activity.soft_interrupts,
+            KernelCounter::Forks => activity.forks,
+            KernelCounter::Running => activity.running,
+            KernelCounter::Blocked => activity.blocked,
+        }
+    }
+}
+
+/// Reads the kernel counters from `/proc/stat`.
+#[cfg(target_os = "linux")]
+pub fn get_kernel_data() -> Option<KernelHarvest> {
+    std::fs::read_to_string("/proc/stat")
+        .ok()
+        .and_then(|stat| parse_proc_stat(&stat))
+}
+
+/// Parses the counters after the per-CPU times in `/proc/stat`.  The "intr" and "softirq" lines
+/// are followed by a count per source, but only the totals that come first are used.
+#[cfg(target_os = "linux")]
+fn parse_proc_stat(stat: &str) -> Option<KernelHarvest> {
+    let mut harvest = KernelHarvest::default();
+    let mut found_any = false;
+
+    for line in stat.lines() {
+        let mut fields = line.split_whitespace();
+        let counter = match fields.next() {
+            Some("ctxt") => &mut harvest.context_switches,
+            Some("intr") => &mut harvest.interrupts,
+            Some("softirq") => &mut harvest.soft_interrupts,
+            Some("processes") => &mut harvest.forks,
+            Some("procs_running") => &mut harvest.procs_running,
+            Some("procs_blocked") => &mut harvest.procs_blocked,
+            _ => continue,
+        };
+
+        if let Some(value) = fields.next().and_then(|value| value.parse().ok()) {
+            *counter = value;
+            found_any = true;
+        }
+    }
+
+    if found_any {
+        Some(harvest)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_activity_since() {
+        let previous = KernelHarvest {
+            context_switches: 1000,
+            interrupts: 500,
+            forks: 10,
+            ..Default::default()
+        };
+        let current = KernelHarvest {
+            context_switches: 3000,
+            interrupts: 1500,
+            forks: 11,
+            procs_running: 3,
+            procs_blocked: 1,
+            ..Default::default()
+        };
+
+        let activity = current.activity_since(&previous, Duration::from_secs(2));
+        assert_eq!(
+            activity,
+            KernelActivity {
+                context_switches: 1000.0,
+                interrupts: 500.0,
+                soft_interrupts: 0.0,
+                forks: 0.5,
+                running: 3.0,
+                blocked: 1.0,
+            }
+        );
+        assert_eq!(KernelCounter::Forks.value(&activity), 0.5);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_proc_stat() {
+        let stat = "cpu  10132153 290696 3084719 46828483 16683 0 25195 0 0 0\n\
+                    cpu0 1393280 32966 572056 13343292 6130 0 17875 0 0 0\n\
+                    intr 199292 41 9 0 0 0 0 3 0 1 0\n\
+                    ctxt 4508235\n\
+                    btime 1700000000\n\
+                    processes 86031\n\
+                    procs_running 2\n\
+                    procs_blocked 1\n\
+                    softirq 2350122 0 364839 2 58102 0 0 1214 912045 0 1013920\n";
+
+        assert_eq!(
+            parse_proc_stat(stat),
+            Some(KernelHarvest {
+                context_switches: 4508235,
+                interrupts: 199292,
+                soft_interrupts: 2350122,
+                forks: 86031,
+                procs_running: 2,
+                procs_blocked: 1,
+            })
+        );
+        assert_eq!(parse_proc_stat("cpu 1 2 3 4\n"), None);
+    }
+}
@@ -2,6 +2,12 @@
 //!
 //! For Linux, macOS, and Windows, this is handled by Heim. On FreeBSD it is handled by sysinfo.
 
+pub mod arc;
+pub mod compression;
+pub mod huge_pages;
+pub mod numa;
+pub mod paging;
+
 cfg_if::cfg_if! {
     if #[cfg(any(target_os = "freebsd", target_os = "linux", target_os = "macos", target_os = "windows"))] {
         pub mod general;
@@ -0,0 +1,248 @@
+//! Data collection for the details of the ZFS ARC, such as its hit ratio and the L2ARC.
+//!
+//! This needs the `zfs` feature.  On Linux it is read from `/proc/spl/kstat/zfs/arcstats`, and on
+//! FreeBSD from the `kstat.zfs.misc.arcstats` sysctls.
+
+use std::time::Duration;
+
+/// Statistics from the ARC.  Hits and misses are cumulative since the ZFS module was loaded, and
+/// sizes are in KiB.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ArcHarvest {
+    pub hits: u64,
+    pub misses: u64,
+    pub size_in_kib: u64,
+    /// The most recently used list, for data that has been read once.
+    pub mru_in_kib: u64,
+    /// The most frequently used list, for data that has been read more than once.
+    pub mfu_in_kib: u64,
+    /// The most that the ARC can grow to.
+    pub max_size_in_kib: u64,
+    pub l2_hits: u64,
+    pub l2_misses: u64,
+    /// The size of the data in the L2ARC, which is zero if there are no cache devices.
+    pub l2_size_in_kib: u64,
+}
+
+impl ArcHarvest {
+    /// Returns the ARC's activity since a previous reading, given the time that passed.
+    pub fn activity_since(&self, previous: &ArcHarvest, elapsed: Duration) -> ArcActivity {
+        let seconds = elapsed.as_secs_f64();
+        let hits = self.hits.saturating_sub(previous.hits);
+        let misses = self.misses.saturating_sub(previous.misses);
+        let l2_hits = self.l2_hits.saturating_sub(previous.l2_hits);
+        let l2_misses = self.l2_misses.saturating_sub(previous.l2_misses);
+
+        let rate = |count: u64| {
+            if seconds > 0.0 {
+                count as f64 / seconds
+            } else {
+                0.0
+            }
+        };
+        let ratio = |hits: u64, misses: u64| {
+            if hits + misses > 0 {
+                Some(hits as f64 / (hits + misses) as f64 * 100.0)
+            } else {
+                None
+            }
+        };
+        let percent_of_max = |size_in_kib: u64| {
+            if self.max_size_in_kib > 0 {
+                size_in_kib as f64 / self.max_size_in_kib as f64 * 100.0
+            } else {
+                0.0
+            }
+        };
+
+        ArcActivity {
+            hits: rate(hits),
+            misses: rate(misses),
+            hit_ratio: ratio(hits, misses),
+            l2_hit_ratio: ratio(l2_hits, l2_misses),
+            mru_percent: percent_of_max(self.mru_in_kib),
+            mfu_percent: percent_of_max(self.mfu_in_kib),
+        }
+    }
+}
+
+/// The ARC's activity between two updates.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ArcActivity {
+    /// Hits per second.
+    pub hits: f64,
+    /// Misses per second.
+    pub misses: f64,
+    /// The percentage of reads that were hits, if there were any reads.
+    pub hit_ratio: Option<f64>,
+    /// The percentage of reads that missed the ARC but hit the L2ARC, if there were any.
+    pub l2_hit_ratio: Option<f64>,
+    /// The size of the MRU list, as a percentage of the maximum ARC size.
+    pub mru_percent: f64,
+    /// The size of the MFU list, as a percentage of the maximum ARC size.
+    pub mfu_percent: f64,
+}
+
+/// Reads the ARC statistics from `/proc/spl/kstat/zfs/arcstats`.
+#[cfg(all(feature = "zfs", target_os = "linux"))]
+pub fn get_arc_stats() -> Option<ArcHarvest> {
+    std::fs::read_to_string("/proc/spl/kstat/zfs/arcstats")
+        .ok()
+        .and_then(|arcstats| parse_arcstats(&arcstats))
+}
+
+/// Reads the ARC statistics from the `kstat.zfs.misc.arcstats` sysctls.
+#[cfg(all(feature = "zfs", target_os = "freebsd"))]
+pub fn get_arc_stats() -> Option<ArcHarvest> {
+    use sysctl::Sysctl;
+
+    let read = |name: &str| -> Option<u64> {
+        let ctl = sysctl::Ctl::new(&format!("kstat.zfs.misc.arcstats.{}", name)).ok()?;
+        match ctl.value().ok()? {
+            sysctl::CtlValue::U64(value) => Some(value),
+            _ => None,
+        }
+    };
+
+    Some(ArcHarvest {
+        hits: read("hits")?,
+        misses: read("misses")?,
+        size_in_kib: read("size")? / 1024,
+        mru_in_kib: read("mru_size").unwrap_or(0) / 1024,
+        mfu_in_kib: read("mfu_size").unwrap_or(0) / 1024,
+        max_size_in_kib: read("c_max").unwrap_or(0) / 1024,
+        l2_hits: read("l2_hits").unwrap_or(0),
+        l2_misses: read("l2_misses").unwrap_or(0),
+        l2_size_in_kib: read("l2_size").unwrap_or(0) / 1024,
+    })
+}
+
+/// Parses `arcstats`, which starts with two header lines followed by a "name type data" triple
+/// per line.  Sizes are in bytes.
+#[cfg(all(feature = "zfs", target_os = "linux"))]
+fn parse_arcstats(arcstats: &str) -> Option<ArcHarvest> {
+    let mut hits = None;
+    let mut misses = None;
+    let mut size = None;
+    let mut mru_size = 0;
+    let mut mfu_size = 0;
+    let mut c_max = 0;
+    let mut l2_hits = 0;
+    let mut l2_misses = 0;
+    let mut l2_size = 0;
+
+    for line in arcstats.lines() {
+        let mut fields = line.split_whitespace();
+        let (name, value) = match (fields.next(), fields.nth(1)) {
+            (Some(name), Some(value)) => (name, value),
+            _ => continue,
+        };
+
+        let value = match value.parse::<u64>() {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+
+        match name {
+            "hits" => hits = Some(value),
+            "misses" => misses = Some(value),
+            "size" => size = Some(value),
+            "mru_size" => mru_size = value,
+            "mfu_size" => mfu_size = value,
+            "c_max" => c_max = value,
+            "l2_hits" => l2_hits = value,
+            "l2_misses" => l2_misses = value,
+            "l2_size" => l2_size = value,
+            _ => {}
+        }
+    }
+
+    Some(ArcHarvest {
+        hits: hits?,
+        misses: misses?,
+        size_in_kib: size? / 1024,
+        mru_in_kib: mru_size / 1024,
+        mfu_in_kib: mfu_size / 1024,
+        max_size_in_kib: c_max / 1024,
+        l2_hits,
+        l2_misses,
+        l2_size_in_kib: l2_size / 1024,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_activity_since() {
+        let previous = ArcHarvest {
+            hits: 1000,
+            misses: 100,
+            size_in_kib: 4096,
+            mru_in_kib: 1024,
+            mfu_in_kib: 2048,
+            max_size_in_kib: 8192,
+            l2_hits: 10,
+            l2_misses: 90,
+            l2_size_in_kib: 0,
+        };
+        let current = ArcHarvest {
+            hits: 1900,
+            misses: 200,
+            l2_hits: 30,
+            l2_misses: 170,
+            ..previous
+        };
+
+        assert_eq!(
+            current.activity_since(&previous, Duration::from_secs(2)),
+            ArcActivity {
+                hits: 450.0,
+                misses: 50.0,
+                hit_ratio: Some(90.0),
+                l2_hit_ratio: Some(20.0),
+                mru_percent: 12.5,
+                mfu_percent: 25.0,
+            }
+        );
+
+        let idle = previous.activity_since(&previous, Duration::ZERO);
+        assert_eq!(
+            (idle.hits, idle.hit_ratio, idle.l2_hit_ratio),
+            (0.0, None, None)
+        );
+    }
+
+    #[cfg(all(feature = "zfs", target_os = "linux"))]
+    #[test]
+    fn test_parse_arcstats() {
+        let arcstats = "13 1 0x01 123 33456 7391093493 2937948262374\n\
+                        name                            type data\n\
+                        hits                            4    5000\n\
+                        misses                          4    1000\n\
+                        c_max                           4    8589934592\n\
+                        size                            4    4294967296\n\
+                        mru_size                        4    1073741824\n\
+                        mfu_size                        4    2147483648\n\
+                        l2_hits                         4    40\n\
+                        l2_misses                       4    960\n\
+                        l2_size                         4    536870912\n";
+
+        assert_eq!(
+            parse_arcstats(arcstats),
+            Some(ArcHarvest {
+                hits: 5000,
+                misses: 1000,
+                size_in_kib: 4194304,
+                mru_in_kib: 1048576,
+                mfu_in_kib: 2097152,
+                max_size_in_kib: 8388608,
+                l2_hits: 40,
+                l2_misses: 960,
+                l2_size_in_kib: 524288,
+            })
+        );
+        assert_eq!(parse_arcstats("name type data\n"), None);
+    }
+}
@@ -0,0 +1,181 @@
+//! Data collection for compressed memory, that is zram devices and the zswap pool.
+//!
+//! This is only available on Linux, through `/sys/block/zram*` and `/proc/meminfo`.  The zswap
+//! pool's sizes are read along with the rest of `/proc/meminfo` when collecting RAM usage.
+
+#[cfg(target_os = "linux")]
+use super::MemBreakdown;
+
+/// Memory that is stored compressed in RAM, in KiB.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompressedHarvest {
+    /// The zram device, such as "zram0", or "zswap".
+    pub name: String,
+    /// The size of the data before it was compressed.
+    pub original_in_kib: u64,
+    /// The RAM used to store the compressed data, including any overhead.
+    pub compressed_in_kib: u64,
+}
+
+impl CompressedHarvest {
+    /// Returns how many times smaller the data is once compressed, if anything is stored.
+    pub fn ratio(&self) -> Option<f64> {
+        if self.compressed_in_kib > 0 {
+            Some(self.original_in_kib as f64 / self.compressed_in_kib as f64)
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns every zram device that is set up, sorted by name, followed by the zswap pool if zswap
+/// is enabled and the RAM breakdown has its sizes.
+#[cfg(target_os = "linux")]
+pub fn get_compressed_data(breakdown: Option<&MemBreakdown>) -> Vec<CompressedHarvest> {
+    let mut compressed = read_zram_data_from(std::path::Path::new("/sys/block"));
+
+    let zswap_enabled = std::fs::read_to_string("/sys/module/zswap/parameters/enabled")
+        .map(|enabled| enabled.trim() == "Y")
+        .unwrap_or(false);
+    if zswap_enabled {
+        if let Some(zswap) = breakdown.and_then(zswap_data) {
+            compressed.push(zswap);
+        }
+    }
+
+    compressed
+}
+
+/// Returns the zswap pool from a RAM breakdown, where "Zswap" is the size of the pool and
+/// "Zswapped" is the size of the data in it before compression.
+#[cfg(target_os = "linux")]
+fn zswap_data(breakdown: &MemBreakdown) -> Option<CompressedHarvest> {
+    Some(CompressedHarvest {
+        name: "zswap".to_string(),
+        original_in_kib: breakdown.zswapped?,
+        compressed_in_kib: breakdown.zswap?,
+    })
+}
+
+/// Reads the zram devices from a sysfs block directory, usually `/sys/block`.  Devices that
+/// haven't been given a size yet aren't in use, so they are skipped.
+#[cfg(target_os = "linux")]
+fn read_zram_data_from(block_dir: &std::path::Path) -> Vec<CompressedHarvest> {
+    let mut devices = match std::fs::read_dir(block_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                if !name.starts_with("zram") {
+                    return None;
+                }
+
+                let disk_size = std::fs::read_to_string(entry.path().join("disksize")).ok()?;
+                if disk_size.trim().parse::<u64>().ok()? == 0 {
+                    return None;
+                }
+                let mm_stat = std::fs::read_to_string(entry.path().join("mm_stat")).ok()?;
+
+                parse_mm_stat(name, &mm_stat)
+            })
+            .collect::<Vec<_>>(),
+        Err(_) => return vec![],
+    };
+    devices.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+    devices
+}
+
+/// Parses a zram device's `mm_stat`, a line of sizes in bytes that starts with the original data
+/// size, the compressed data size, and the total memory used including overhead.
+#[cfg(target_os = "linux")]
+fn parse_mm_stat(name: String, mm_stat: &str) -> Option<CompressedHarvest> {
+    let mut fields = mm_stat
+        .split_whitespace()
+        .map(|field| field.parse::<u64>().ok());
+    let original = fields.next()??;
+    let _compressed = fields.next()??;
+    let mem_used_total = fields.next()??;
+
+    Some(CompressedHarvest {
+        name,
+        original_in_kib: original / 1024,
+        compressed_in_kib: mem_used_total / 1024,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ratio() {
+        let compressed = CompressedHarvest {
+            name: "zram0".to_string(),
+            original_in_kib: 4096,
+            compressed_in_kib: 1024,
+        };
+        assert_eq!(compressed.ratio(), Some(4.0));
+        assert_eq!(CompressedHarvest::default().ratio(), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_read_compressed_data() {
+        let dir = crate::utils::test_utils::TempDir::new("zram");
+        for (device, disk_size, mm_stat) in [
+            (
+                "zram1",
+                "1073741824\n",
+                "   4194304   1048576   2097152        0   2097152        0        0        0\n",
+            ),
+            (
+                "zram0",
+                "8589934592\n",
+                "1073741824 268435456 276824064        0 276824064     1234        0        0\n",
+            ),
+            ("zram2", "0\n", "0 0 0 0 0 0 0 0\n"),
+            ("sda", "0\n", ""),
+        ] {
+            dir.write(format!("{}/disksize", device), disk_size);
+            dir.write(format!("{}/mm_stat", device), mm_stat);
+        }
+
+        assert_eq!(
+            read_zram_data_from(dir.path()),
+            vec![
+                CompressedHarvest {
+                    name: "zram0".to_string(),
+                    original_in_kib: 1048576,
+                    compressed_in_kib: 270336,
+                },
+                CompressedHarvest {
+                    name: "zram1".to_string(),
+                    original_in_kib: 4096,
+                    compressed_in_kib: 2048,
+                },
+            ]
+        );
+        assert!(read_zram_data_from(&dir.path().join("missing")).is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_zswap_data() {
+        let breakdown = MemBreakdown {
+            zswap: Some(51200),
+            zswapped: Some(204800),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            zswap_data(&breakdown),
+            Some(CompressedHarvest {
+                name: "zswap".to_string(),
+                original_in_kib: 204800,
+                compressed_in_kib: 51200,
+            })
+        );
+        assert_eq!(zswap_data(&MemBreakdown::default()), None);
+    }
+}
@@ -13,6 +13,34 @@ pub struct MemHarvest {
     pub mem_total_in_kib: u64,
     pub mem_used_in_kib: u64,
     pub use_percent: Option<f64>,
+    /// A breakdown of where the memory went.  This is only available for RAM on Linux.
+    pub breakdown: Option<MemBreakdown>,
+}
+
+/// A breakdown of RAM usage, in KiB.  The used, buffer, cache, reclaimable slab, and free memory
+/// add up to the total, with shared memory counted as used rather than as part of the page cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemBreakdown {
+    pub total: u64,
+    pub used: u64,
+    pub buffers: u64,
+    /// The page cache, not including shared memory.
+    pub cache: u64,
+    pub shared: u64,
+    pub slab_reclaimable: u64,
+    pub free: u64,
+    /// An estimate of how much memory can be given to new programs without swapping.
+    pub available: u64,
+    /// Memory waiting to be written back to disk.
+    pub dirty: u64,
+    /// Memory being written back to disk right now.
+    pub writeback: u64,
+    /// The RAM used by the zswap pool.  Kernels older than 5.19 don't report this.
+    pub zswap: Option<u64>,
+    /// The size of the data in the zswap pool before it was compressed.
+    pub zswapped: Option<u64>,
+    /// The pool of persistent huge pages, if the kernel supports them.
+    pub huge_pages: Option<super::huge_pages::HugePagesHarvest>,
 }
 
 #[derive(Debug)]
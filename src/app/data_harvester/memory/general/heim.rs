@@ -2,6 +2,9 @@
 
 use crate::data_harvester::memory::{MemCollect, MemHarvest};
 
+#[cfg(target_os = "linux")]
+use crate::data_harvester::memory::{huge_pages::HugePagesHarvest, MemBreakdown};
+
 pub async fn get_mem_data(actually_get: bool, _get_gpu: bool) -> MemCollect {
     if !actually_get {
         MemCollect {
@@ -29,73 +32,14 @@ pub async fn get_mem_data(actually_get: bool, _get_gpu: bool) -> MemCollect {
 }
 
 pub async fn get_ram_data() -> crate::utils::error::Result<Option<MemHarvest>> {
-    let (mem_total_in_kib, mem_used_in_kib) = {
+    let (mem_total_in_kib, mem_used_in_kib, breakdown) = {
         #[cfg(target_os = "linux")]
         {
             use smol::fs::read_to_string;
             let meminfo = read_to_string("/proc/meminfo").await?;
+            let breakdown = parse_meminfo(&meminfo);
 
-            // All values are in KiB by default.
-            let mut mem_total = 0;
-            let mut cached = 0;
-            let mut s_reclaimable = 0;
-            let mut shmem = 0;
-            let mut buffers = 0;
-            let mut mem_free = 0;
-
-            let mut keys_read: u8 = 0;
-            const TOTAL_KEYS_NEEDED: u8 = 6;
-
-            for line in meminfo.lines() {
-                if let Some((label, value)) = line.split_once(':') {
-                    let to_write = match label {
-                        "MemTotal" => &mut mem_total,
-                        "MemFree" => &mut mem_free,
-                        "Buffers" => &mut buffers,
-                        "Cached" => &mut cached,
-                        "Shmem" => &mut shmem,
-                        "SReclaimable" => &mut s_reclaimable,
-                        _ => {
-                            continue;
-                        }
-                    };
-
-                    if let Some((number, _unit)) = value.trim_start().split_once(' ') {
-                        // Parse the value, remember it's in KiB!
-                        if let Ok(number) = number.parse::<u64>() {
-                            *to_write = number;
-
-                            // We only need a few keys, so we can bail early.
-                            keys_read += 1;
-                            if keys_read == TOTAL_KEYS_NEEDED {
-                                break;
-                            }
-                        }
-                    }
-                }
-            }
-
-            // Let's preface this by saying that memory usage calculations are... not straightforward.
-            // There are conflicting implementations everywhere.
-            //
-            // Now that we've added this preface (mainly for future reference), the current implementation below for usage
-            // is based on htop's calculation formula. See
-            // https://github.com/htop-dev/htop/blob/976c6123f41492aaf613b9d172eef1842fb7b0a3/linux/LinuxProcessList.c#L1584
-            // for implementation details as of writing.
-            //
-            // Another implementation, commonly used in other things, is to skip the shmem part of the calculation,
-            // which matches gopsutil and stuff like free.
-
-            let total = mem_total;
-            let cached_mem = cached + s_reclaimable - shmem;
-            let used_diff = mem_free + cached_mem + buffers;
-            let used = if total >= used_diff {
-                total - used_diff
-            } else {
-                total - mem_free
-            };
-
-            (total, used)
+            (breakdown.total, breakdown.used, Some(breakdown))
         }
         #[cfg(target_os = "macos")]
         {
@@ -106,6 +50,7 @@ pub async fn get_ram_data() -> crate::utils::error::Result<Option<MemHarvest>> {
             (
                 memory.total().get::<kibibyte>(),
                 memory.active().get::<kibibyte>() + memory.wire().get::<kibibyte>(),
+                None,
             )
         }
         #[cfg(target_os = "windows")]
@@ -117,13 +62,14 @@ pub async fn get_ram_data() -> crate::utils::error::Result<Option<MemHarvest>> {
             (
                 mem_total_in_kib,
                 mem_total_in_kib - memory.available().get::<kibibyte>(),
+                None,
             )
         }
         #[cfg(target_os = "freebsd")]
         {
             let mut s = System::new();
             s.refresh_memory();
-            (s.total_memory(), s.used_memory())
+            (s.total_memory(), s.used_memory(), None)
         }
     };
 
@@ -135,9 +81,116 @@ pub async fn get_ram_data() -> crate::utils::error::Result<Option<MemHarvest>> {
         } else {
             Some(mem_used_in_kib as f64 / mem_total_in_kib as f64 * 100.0)
         },
+        breakdown,
     }))
 }
 
+/// Parses `/proc/meminfo` into a breakdown of RAM usage, along with the zswap pool and huge pages.
+#[cfg(target_os = "linux")]
+fn parse_meminfo(meminfo: &str) -> MemBreakdown {
+    // All values are in KiB by default, except for the huge page counts.
+    let mut mem_total = 0;
+    let mut cached = 0;
+    let mut s_reclaimable = 0;
+    let mut shmem = 0;
+    let mut buffers = 0;
+    let mut mem_free = 0;
+    let mut dirty = 0;
+    let mut writeback = 0;
+    let mut mem_available = None;
+    let mut zswap = None;
+    let mut zswapped = None;
+    let mut huge_pages_total = None;
+    let mut huge_pages_free = 0;
+    let mut huge_pages_reserved = 0;
+    let mut huge_page_size = 0;
+
+    let mut keys_read: u8 = 0;
+    const TOTAL_KEYS_NEEDED: u8 = 15;
+
+    for line in meminfo.lines() {
+        if let Some((label, value)) = line.split_once(':') {
+            let to_write = match label {
+                "MemTotal" => &mut mem_total,
+                "MemFree" => &mut mem_free,
+                "Buffers" => &mut buffers,
+                "Cached" => &mut cached,
+                "Shmem" => &mut shmem,
+                "SReclaimable" => &mut s_reclaimable,
+                "Dirty" => &mut dirty,
+                "Writeback" => &mut writeback,
+                "MemAvailable" => mem_available.get_or_insert(0),
+                "Zswap" => zswap.get_or_insert(0),
+                "Zswapped" => zswapped.get_or_insert(0),
+                "HugePages_Total" => huge_pages_total.get_or_insert(0),
+                "HugePages_Free" => &mut huge_pages_free,
+                "HugePages_Rsvd" => &mut huge_pages_reserved,
+                "Hugepagesize" => &mut huge_page_size,
+                _ => {
+                    continue;
+                }
+            };
+
+            // The huge page counts have no unit, so don't rely on there being one.
+            if let Some(number) = value.split_whitespace().next() {
+                // Parse the value, remember it's in KiB!
+                if let Ok(number) = number.parse::<u64>() {
+                    *to_write = number;
+
+                    // We only need a few keys, so we can bail early.
+                    keys_read += 1;
+                    if keys_read == TOTAL_KEYS_NEEDED {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    // Let's preface this by saying that memory usage calculations are... not straightforward.
+    // There are conflicting implementations everywhere.
+    //
+    // Now that we've added this preface (mainly for future reference), the current implementation below for usage
+    // is based on htop's calculation formula. See
+    // https://github.com/htop-dev/htop/blob/976c6123f41492aaf613b9d172eef1842fb7b0a3/linux/LinuxProcessList.c#L1584
+    // for implementation details as of writing.
+    //
+    // Another implementation, commonly used in other things, is to skip the shmem part of the calculation,
+    // which matches gopsutil and stuff like free.
+
+    let total = mem_total;
+    let cache = cached.saturating_sub(shmem);
+    let cached_mem = cache + s_reclaimable;
+    let used_diff = mem_free + cached_mem + buffers;
+    let used = if total >= used_diff {
+        total - used_diff
+    } else {
+        total.saturating_sub(mem_free)
+    };
+
+    MemBreakdown {
+        total,
+        used,
+        buffers,
+        cache,
+        shared: shmem,
+        slab_reclaimable: s_reclaimable,
+        free: mem_free,
+        // Kernels older than 3.14 don't report this, so estimate it from what could be reclaimed.
+        available: mem_available.unwrap_or(used_diff),
+        dirty,
+        writeback,
+        zswap,
+        zswapped,
+        huge_pages: huge_pages_total.map(|total| HugePagesHarvest {
+            total,
+            free: huge_pages_free,
+            reserved: huge_pages_reserved,
+            page_size_in_kib: huge_page_size,
+        }),
+    }
+}
+
 pub async fn get_swap_data() -> crate::utils::error::Result<Option<MemHarvest>> {
     #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
     let memory = heim::memory::swap().await?;
@@ -177,6 +230,7 @@ pub async fn get_swap_data() -> crate::utils::error::Result<Option<MemHarvest>>
         } else {
             Some(mem_used_in_kib as f64 / mem_total_in_kib as f64 * 100.0)
         },
+        breakdown: None,
     }))
 }
 
@@ -253,6 +307,7 @@ pub async fn get_arc_data() -> crate::utils::error::Result<Option<MemHarvest>> {
         } else {
             Some(mem_used_in_kib as f64 / mem_total_in_kib as f64 * 100.0)
         },
+        breakdown: None,
     }))
 }
 
@@ -278,6 +333,7 @@ pub async fn get_gpu_data() -> crate::utils::error::Result<Option<Vec<(String, M
                                 } else {
                                     Some(mem_used_in_kib as f64 / mem_total_in_kib as f64 * 100.0)
                                 },
+                                breakdown: None,
                             },
                         ));
                     }
@@ -291,3 +347,71 @@ pub async fn get_gpu_data() -> crate::utils::error::Result<Option<Vec<(String, M
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_meminfo() {
+        use super::*;
+
+        let meminfo = "MemTotal:       16000000 kB\n\
+                       MemFree:         2000000 kB\n\
+                       MemAvailable:    9000000 kB\n\
+                       Buffers:          500000 kB\n\
+                       Cached:          6000000 kB\n\
+                       SwapCached:            0 kB\n\
+                       Dirty:               300 kB\n\
+                       Writeback:            20 kB\n\
+                       Shmem:           1000000 kB\n\
+                       SReclaimable:     700000 kB\n\
+                       Zswap:             51200 kB\n\
+                       Zswapped:         204800 kB\n\
+                       AnonHugePages:      2048 kB\n\
+                       HugePages_Total:     512\n\
+                       HugePages_Free:      384\n\
+                       HugePages_Rsvd:       64\n\
+                       HugePages_Surp:        0\n\
+                       Hugepagesize:       2048 kB\n";
+
+        let breakdown = parse_meminfo(meminfo);
+        assert_eq!(
+            breakdown,
+            MemBreakdown {
+                total: 16000000,
+                used: 7800000,
+                buffers: 500000,
+                cache: 5000000,
+                shared: 1000000,
+                slab_reclaimable: 700000,
+                free: 2000000,
+                available: 9000000,
+                dirty: 300,
+                writeback: 20,
+                zswap: Some(51200),
+                zswapped: Some(204800),
+                huge_pages: Some(HugePagesHarvest {
+                    total: 512,
+                    free: 384,
+                    reserved: 64,
+                    page_size_in_kib: 2048,
+                }),
+            }
+        );
+        assert_eq!(
+            breakdown.used
+                + breakdown.buffers
+                + breakdown.cache
+                + breakdown.slab_reclaimable
+                + breakdown.free,
+            breakdown.total
+        );
+
+        // Older kernels don't have "MemAvailable".
+        let breakdown = parse_meminfo("MemTotal: 1000 kB\nMemFree: 100 kB\nCached: 200 kB\n");
+        assert_eq!(breakdown.used, 700);
+        assert_eq!(breakdown.available, 300);
+        assert_eq!(breakdown.zswap, None);
+        assert_eq!(breakdown.huge_pages, None);
+    }
+}
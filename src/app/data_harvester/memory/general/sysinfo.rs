@@ -40,6 +40,7 @@ pub async fn get_ram_data(sys: &System) -> crate::utils::error::Result<Option<Me
         } else {
             Some(mem_used_in_kib as f64 / mem_total_in_kib as f64 * 100.0)
         },
+        breakdown: None,
     }))
 }
 
@@ -54,6 +55,7 @@ pub async fn get_swap_data(sys: &System) -> crate::utils::error::Result<Option<M
         } else {
             Some(mem_used_in_kib as f64 / mem_total_in_kib as f64 * 100.0)
         },
+        breakdown: None,
     }))
 }
 
@@ -87,6 +89,7 @@ pub async fn get_arc_data() -> crate::utils::error::Result<Option<MemHarvest>> {
         } else {
             Some(mem_used_in_kib as f64 / mem_total_in_kib as f64 * 100.0)
         },
+        breakdown: None,
     }))
 }
 
@@ -112,6 +115,7 @@ pub async fn get_gpu_data() -> crate::utils::error::Result<Option<Vec<(String, M
                                 } else {
                                     Some(mem_used_in_kib as f64 / mem_total_in_kib as f64 * 100.0)
                                 },
+                                breakdown: None,
                             },
                         ));
                     }
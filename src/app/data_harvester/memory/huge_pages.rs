@@ -0,0 +1,43 @@
+//! Data collection for huge pages.
+//!
+//! This is only available on Linux, through `/proc/meminfo`.  The pool is read along with the rest
+//! of `/proc/meminfo` when collecting RAM usage, and carried on its breakdown.
+
+/// The pool of persistent huge pages, counted in pages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HugePagesHarvest {
+    pub total: u64,
+    pub free: u64,
+    /// Pages that have been promised to a mapping but not yet faulted in.  These are still free.
+    pub reserved: u64,
+    /// The size of each page in KiB.
+    pub page_size_in_kib: u64,
+}
+
+impl HugePagesHarvest {
+    /// Returns the percentage of pages in use, if there are any pages.
+    pub fn use_percent(&self) -> Option<f64> {
+        if self.total > 0 {
+            Some(self.total.saturating_sub(self.free) as f64 / self.total as f64 * 100.0)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_use_percent() {
+        let huge_pages = HugePagesHarvest {
+            total: 512,
+            free: 384,
+            reserved: 64,
+            page_size_in_kib: 2048,
+        };
+        assert_eq!(huge_pages.use_percent(), Some(25.0));
+        assert_eq!(HugePagesHarvest::default().use_percent(), None);
+    }
+}
@@ -0,0 +1,131 @@
+//! Data collection for the memory usage of each NUMA node.
+//!
+//! This is only available on Linux, through `/sys/devices/system/node/node*/meminfo`.
+
+#[cfg(target_os = "linux")]
+use super::MemHarvest;
+
+/// Returns the memory usage of each NUMA node, sorted by node number.  Nothing is returned for
+/// systems with only one node, since it would just repeat the RAM usage.
+#[cfg(target_os = "linux")]
+pub fn get_numa_data() -> Option<Vec<(usize, MemHarvest)>> {
+    let nodes = read_numa_data_from(std::path::Path::new("/sys/devices/system/node"));
+
+    if nodes.len() > 1 {
+        Some(nodes)
+    } else {
+        None
+    }
+}
+
+/// Reads the memory usage of each node from a sysfs node directory, usually
+/// `/sys/devices/system/node`.
+#[cfg(target_os = "linux")]
+fn read_numa_data_from(node_dir: &std::path::Path) -> Vec<(usize, MemHarvest)> {
+    let mut nodes = match std::fs::read_dir(node_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let node = entry
+                    .file_name()
+                    .to_str()
+                    .and_then(|name| name.strip_prefix("node"))
+                    .and_then(|node| node.parse::<usize>().ok())?;
+                let meminfo = std::fs::read_to_string(entry.path().join("meminfo")).ok()?;
+
+                Some((node, parse_node_meminfo(&meminfo)?))
+            })
+            .collect::<Vec<_>>(),
+        Err(_) => return vec![],
+    };
+    nodes.sort_unstable_by_key(|(node, _)| *node);
+
+    nodes
+}
+
+/// Parses a node's `meminfo`, where every line is prefixed with the node, like
+/// "Node 0 MemTotal:  16318420 kB".  Usage is worked out the same way as for RAM, except that the
+/// page cache is reported as "FilePages".
+#[cfg(target_os = "linux")]
+fn parse_node_meminfo(meminfo: &str) -> Option<MemHarvest> {
+    let mut mem_total = None;
+    let mut mem_free = 0;
+    let mut file_pages = 0;
+    let mut shmem = 0;
+    let mut s_reclaimable = 0;
+
+    for line in meminfo.lines() {
+        if let Some((label, value)) = line.split_once(':') {
+            let value = match value.split_whitespace().next().map(str::parse::<u64>) {
+                Some(Ok(value)) => value,
+                _ => continue,
+            };
+
+            match label.split_whitespace().last() {
+                Some("MemTotal") => mem_total = Some(value),
+                Some("MemFree") => mem_free = value,
+                Some("FilePages") => file_pages = value,
+                Some("Shmem") => shmem = value,
+                Some("SReclaimable") => s_reclaimable = value,
+                _ => {}
+            }
+        }
+    }
+
+    let mem_total_in_kib = mem_total?;
+    let cached_mem = file_pages.saturating_sub(shmem) + s_reclaimable;
+    let mem_used_in_kib = mem_total_in_kib.saturating_sub(mem_free + cached_mem);
+
+    Some(MemHarvest {
+        mem_total_in_kib,
+        mem_used_in_kib,
+        use_percent: if mem_total_in_kib == 0 {
+            None
+        } else {
+            Some(mem_used_in_kib as f64 / mem_total_in_kib as f64 * 100.0)
+        },
+        breakdown: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_read_numa_data() {
+        use super::*;
+        use crate::utils::test_utils::TempDir;
+
+        let dir = TempDir::new("numa");
+        for (node, total, free) in [(1, 2000, 1000), (0, 4000, 500)] {
+            dir.write(
+                format!("node{}/meminfo", node),
+                format!(
+                    "Node {node} MemTotal:       {total} kB\n\
+                     Node {node} MemFree:        {free} kB\n\
+                     Node {node} MemUsed:        0 kB\n\
+                     Node {node} FilePages:      600 kB\n\
+                     Node {node} Shmem:          100 kB\n\
+                     Node {node} SReclaimable:   100 kB\n\
+                     Node {node} HugePages_Total:     0\n",
+                    node = node,
+                    total = total,
+                    free = free
+                ),
+            );
+        }
+        dir.create_dir("power");
+
+        let nodes = read_numa_data_from(dir.path());
+        assert_eq!(
+            nodes
+                .iter()
+                .map(|(node, mem)| (*node, mem.mem_total_in_kib, mem.mem_used_in_kib))
+                .collect::<Vec<_>>(),
+            vec![(0, 4000, 2900), (1, 2000, 400)]
+        );
+        assert_eq!(nodes[1].1.use_percent, Some(20.0));
+
+        assert!(parse_node_meminfo("Node 0 MemFree: 1 kB\n").is_none());
+    }
+}
@@ -0,0 +1,156 @@
+//! Data collection for swap and paging activity.
+//!
+//! This is only available on Linux, through `/proc/vmstat`.
+
+use std::time::Duration;
+
+/// Counters from `/proc/vmstat`, all cumulative since boot.  Swapping is counted in pages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PagingHarvest {
+    pub swap_in: u64,
+    pub swap_out: u64,
+    pub faults: u64,
+    pub major_faults: u64,
+    /// The number of processes killed for running out of memory.  Kernels older than 4.13 don't
+    /// report this.
+    pub oom_kills: Option<u64>,
+}
+
+impl PagingHarvest {
+    /// Returns the rates per second since a previous reading, given the time that passed.
+    pub fn activity_since(&self, previous: &PagingHarvest, elapsed: Duration) -> PagingActivity {
+        let seconds = elapsed.as_secs_f64();
+        let rate = |current: u64, previous: u64| {
+            if seconds > 0.0 {
+                current.saturating_sub(previous) as f64 / seconds
+            } else {
+                0.0
+            }
+        };
+
+        PagingActivity {
+            swap_in: rate(self.swap_in, previous.swap_in),
+            swap_out: rate(self.swap_out, previous.swap_out),
+            faults: rate(self.faults, previous.faults),
+            major_faults: rate(self.major_faults, previous.major_faults),
+        }
+    }
+}
+
+/// Paging activity between two updates, as rates per second.  Swapping is in pages per second.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PagingActivity {
+    pub swap_in: f64,
+    pub swap_out: f64,
+    /// All page faults, including major ones.
+    pub faults: f64,
+    /// Page faults that had to wait on disk, such as to read a page back in from swap.
+    pub major_faults: f64,
+}
+
+/// Reads the paging counters from `/proc/vmstat`.
+#[cfg(target_os = "linux")]
+pub fn get_paging_data() -> Option<PagingHarvest> {
+    std::fs::read_to_string("/proc/vmstat")
+        .ok()
+        .and_then(|vmstat| parse_vmstat(&vmstat))
+}
+
+/// Parses the counters we need from `/proc/vmstat`, which has one "name value" pair per line.
+#[cfg(target_os = "linux")]
+fn parse_vmstat(vmstat: &str) -> Option<PagingHarvest> {
+    let mut harvest = PagingHarvest::default();
+    let mut found_any = false;
+
+    for line in vmstat.lines() {
+        let (name, value) = match line.split_once(' ') {
+            Some((name, value)) => (name, value.trim()),
+            None => continue,
+        };
+
+        let value = match value.parse::<u64>() {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+
+        match name {
+            "pswpin" => harvest.swap_in = value,
+            "pswpout" => harvest.swap_out = value,
+            "pgfault" => harvest.faults = value,
+            "pgmajfault" => harvest.major_faults = value,
+            "oom_kill" => harvest.oom_kills = Some(value),
+            _ => continue,
+        }
+        found_any = true;
+    }
+
+    if found_any {
+        Some(harvest)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_activity_since() {
+        let previous = PagingHarvest {
+            swap_in: 100,
+            swap_out: 50,
+            faults: 1000,
+            major_faults: 10,
+            oom_kills: Some(0),
+        };
+        let current = PagingHarvest {
+            swap_in: 300,
+            swap_out: 50,
+            faults: 5000,
+            major_faults: 30,
+            oom_kills: Some(1),
+        };
+
+        assert_eq!(
+            current.activity_since(&previous, Duration::from_secs(2)),
+            PagingActivity {
+                swap_in: 100.0,
+                swap_out: 0.0,
+                faults: 2000.0,
+                major_faults: 10.0,
+            }
+        );
+        assert_eq!(
+            current.activity_since(&previous, Duration::ZERO),
+            PagingActivity::default()
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_vmstat() {
+        let vmstat = "nr_free_pages 1234\n\
+                      pswpin 56\n\
+                      pswpout 78\n\
+                      pgfault 987654\n\
+                      pgmajfault 321\n\
+                      oom_kill 2\n";
+
+        assert_eq!(
+            parse_vmstat(vmstat),
+            Some(PagingHarvest {
+                swap_in: 56,
+                swap_out: 78,
+                faults: 987654,
+                major_faults: 321,
+                oom_kills: Some(2),
+            })
+        );
+        assert_eq!(
+            parse_vmstat("pswpin 1\npswpout 2\n").map(|harvest| harvest.oom_kills),
+            Some(None)
+        );
+        assert_eq!(parse_vmstat("nr_free_pages 1234\n"), None);
+    }
+}
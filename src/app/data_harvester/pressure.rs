@@ -0,0 +1,214 @@
+//! Data collection for pressure stall information (PSI).
+//!
+//! This is only available on Linux 4.20 and newer, through `/proc/pressure/{cpu,memory,io}`.
+
+use std::time::Duration;
+
+/// A resource that the kernel reports pressure for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PressureResource {
+    Cpu,
+    Memory,
+    Io,
+}
+
+impl PressureResource {
+    pub const ALL: [PressureResource; 3] = [
+        PressureResource::Cpu,
+        PressureResource::Memory,
+        PressureResource::Io,
+    ];
+
+    /// A short name for the resource, such as "CPU".
+    pub fn name(&self) -> &'static str {
+        match self {
+            PressureResource::Cpu => "CPU",
+            PressureResource::Memory => "MEM",
+            PressureResource::Io => "IO",
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn file_name(&self) -> &'static str {
+        match self {
+            PressureResource::Cpu => "cpu",
+            PressureResource::Memory => "memory",
+            PressureResource::Io => "io",
+        }
+    }
+}
+
+/// One line of a pressure file.  The averages are the percentage of time stalled over the last 10,
+/// 60, and 300 seconds, and the total is the cumulative time stalled in microseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PressureStats {
+    pub avg10: f64,
+    pub avg60: f64,
+    pub avg300: f64,
+    pub total: u64,
+}
+
+impl PressureStats {
+    /// Returns the percentage of time stalled since a previous reading, given the time that passed.
+    /// Without a previous reading, this falls back to the 10 second average.
+    pub fn stall_since(&self, previous: Option<&PressureStats>, elapsed: Duration) -> f64 {
+        match previous {
+            Some(previous) if elapsed.as_micros() > 0 => {
+                let stalled = self.total.saturating_sub(previous.total) as f64;
+                (stalled * 100.0 / elapsed.as_micros() as f64).clamp(0.0, 100.0)
+            }
+            _ => self.avg10,
+        }
+    }
+}
+
+/// The pressure on a single resource.  "Some" is the time at least one task was stalled, and
+/// "full" is the time every non-idle task was stalled at once.  Older kernels don't report "full"
+/// for the CPU.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ResourcePressure {
+    pub some: PressureStats,
+    pub full: Option<PressureStats>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PressureHarvest {
+    pub cpu: Option<ResourcePressure>,
+    pub memory: Option<ResourcePressure>,
+    pub io: Option<ResourcePressure>,
+}
+
+impl PressureHarvest {
+    pub fn get(&self, resource: PressureResource) -> Option<&ResourcePressure> {
+        match resource {
+            PressureResource::Cpu => self.cpu.as_ref(),
+            PressureResource::Memory => self.memory.as_ref(),
+            PressureResource::Io => self.io.as_ref(),
+        }
+    }
+}
+
+/// The percentage of time stalled since the last update, for one resource.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PressureStall {
+    pub some: f64,
+    pub full: Option<f64>,
+}
+
+/// Reads the pressure of each resource.  Returns `None` if PSI isn't available at all, such as on
+/// older kernels or ones booted with `psi=0`.
+#[cfg(target_os = "linux")]
+pub fn get_pressure_data() -> Option<PressureHarvest> {
+    let read = |resource: PressureResource| {
+        std::fs::read_to_string(format!("/proc/pressure/{}", resource.file_name()))
+            .ok()
+            .and_then(|contents| parse_pressure(&contents))
+    };
+
+    let harvest = PressureHarvest {
+        cpu: read(PressureResource::Cpu),
+        memory: read(PressureResource::Memory),
+        io: read(PressureResource::Io),
+    };
+
+    if harvest.cpu.is_none() && harvest.memory.is_none() && harvest.io.is_none() {
+        None
+    } else {
+        Some(harvest)
+    }
+}
+
+/// Parses a pressure file, which looks like:
+///
+/// ```text
+/// some avg10=0.12 avg60=0.05 avg300=0.01 total=123456
+/// full avg10=0.00 avg60=0.00 avg300=0.00 total=6789
+/// ```
+#[cfg(target_os = "linux")]
+fn parse_pressure(contents: &str) -> Option<ResourcePressure> {
+    fn parse_stats<'a>(fields: impl Iterator<Item = &'a str>) -> Option<PressureStats> {
+        let mut stats = PressureStats::default();
+        for field in fields {
+            let (key, value) = field.split_once('=')?;
+            match key {
+                "avg10" => stats.avg10 = value.parse().ok()?,
+                "avg60" => stats.avg60 = value.parse().ok()?,
+                "avg300" => stats.avg300 = value.parse().ok()?,
+                "total" => stats.total = value.parse().ok()?,
+                _ => {}
+            }
+        }
+
+        Some(stats)
+    }
+
+    let mut some = None;
+    let mut full = None;
+    for line in contents.lines() {
+        let mut fields = line.split_whitespace();
+        match fields.next() {
+            Some("some") => some = parse_stats(fields),
+            Some("full") => full = parse_stats(fields),
+            _ => {}
+        }
+    }
+
+    Some(ResourcePressure { some: some?, full })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stall_since() {
+        let previous = PressureStats {
+            avg10: 1.5,
+            total: 1_000_000,
+            ..Default::default()
+        };
+        let current = PressureStats {
+            avg10: 2.5,
+            total: 1_250_000,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            current.stall_since(Some(&previous), Duration::from_secs(1)),
+            25.0
+        );
+        assert_eq!(current.stall_since(None, Duration::from_secs(1)), 2.5);
+        assert_eq!(
+            previous.stall_since(Some(&current), Duration::from_secs(1)),
+            0.0
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_pressure() {
+        let pressure = parse_pressure(
+            "some avg10=1.53 avg60=0.87 avg300=0.20 total=2930427\n\
+             full avg10=0.00 avg60=0.01 avg300=0.00 total=13422\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            pressure.some,
+            PressureStats {
+                avg10: 1.53,
+                avg60: 0.87,
+                avg300: 0.20,
+                total: 2930427,
+            }
+        );
+        assert_eq!(pressure.full.map(|full| full.total), Some(13422));
+
+        let cpu_pressure =
+            parse_pressure("some avg10=0.00 avg60=0.00 avg300=0.00 total=0\n").unwrap();
+        assert_eq!(cpu_pressure.full, None);
+
+        assert_eq!(parse_pressure(""), None);
+        assert_eq!(parse_pressure("some avg10=abc\n"), None);
+    }
+}
@@ -28,6 +28,8 @@ cfg_if::cfg_if! {
     }
 }
 
+use std::time::Duration;
+
 use crate::Pid;
 
 #[derive(Debug, Clone, Default)]
@@ -68,6 +70,18 @@ pub struct ProcessHarvest {
     /// The current state of the process (e.g. zombie, asleep)
     pub process_state: (String, char),
 
+    /// How long the process has been running for.
+    pub time: Duration,
+
+    /// The total CPU time (user and system) the process has used.  This is only collected on Linux.
+    pub cpu_time: Option<Duration>,
+
+    /// The number of threads in the process.  This is only collected on Linux.
+    pub num_threads: Option<u64>,
+
+    /// The nice value of the process.  This is only collected on Linux.
+    pub nice: Option<i64>,
+
     /// This is the *effective* user ID of the process. This is only used on Unix platforms.
     #[cfg(target_family = "unix")]
     pub uid: Option<libc::uid_t>,
@@ -89,5 +103,13 @@ impl ProcessHarvest {
         self.write_bytes_per_sec += rhs.write_bytes_per_sec;
         self.total_read_bytes += rhs.total_read_bytes;
         self.total_write_bytes += rhs.total_write_bytes;
+        self.cpu_time = match (self.cpu_time, rhs.cpu_time) {
+            (Some(lhs), Some(rhs)) => Some(lhs + rhs),
+            (lhs, rhs) => lhs.or(rhs),
+        };
+        self.num_threads = match (self.num_threads, rhs.num_threads) {
+            (Some(lhs), Some(rhs)) => Some(lhs + rhs),
+            (lhs, rhs) => lhs.or(rhs),
+        };
     }
 }
@@ -1,6 +1,7 @@
 //! Process data collection for Linux.
 
 use std::collections::hash_map::Entry;
+use std::time::Duration;
 
 use crate::components::tui_widget::time_chart::Point;
 use crate::utils::error::{self, BottomError};
@@ -37,6 +38,37 @@ impl PrevProcDetails {
     }
 }
 
+/// System-wide values used to convert a process' clock tick counts into durations.
+#[derive(Debug, Clone, Copy)]
+struct TickInfo {
+    /// The system uptime, in seconds.
+    uptime: f64,
+
+    /// The number of clock ticks per second.
+    ticks_per_second: f64,
+}
+
+impl TickInfo {
+    fn new() -> Self {
+        Self {
+            uptime: procfs::Uptime::new().map(|u| u.uptime).unwrap_or(0.0),
+            ticks_per_second: procfs::ticks_per_second()
+                .map(|t| t as f64)
+                .unwrap_or(100.0),
+        }
+    }
+
+    fn ticks_to_duration(&self, ticks: u64) -> Duration {
+        Duration::from_secs_f64(ticks as f64 / self.ticks_per_second)
+    }
+
+    /// Returns how long a process that started `start_ticks` after boot has been running for.
+    fn running_time(&self, start_ticks: u64) -> Duration {
+        let start = start_ticks as f64 / self.ticks_per_second;
+        Duration::from_secs_f64((self.uptime - start).max(0.0))
+    }
+}
+
 fn calculate_idle_values(line: String) -> Point {
     /// Converts a `Option<&str>` value to an f64. If it fails to parse or is `None`, then it will return `0_f64`.
     fn str_to_f64(val: Option<&str>) -> f64 {
@@ -115,10 +147,11 @@ fn get_linux_cpu_usage(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn read_proc(
     prev_proc: &PrevProcDetails, stat: &Stat, cpu_usage: f64, cpu_fraction: f64,
     use_current_cpu_total: bool, time_difference_in_secs: u64, mem_total_kb: u64,
-    user_table: &mut UserTable,
+    tick_info: &TickInfo, user_table: &mut UserTable,
 ) -> error::Result<(ProcessHarvest, u64)> {
     use std::convert::TryFrom;
 
@@ -202,6 +235,9 @@ fn read_proc(
         };
 
     let uid = process.owner;
+    let time = tick_info.running_time(stat.starttime);
+    let cpu_time = tick_info.ticks_to_duration(stat.utime + stat.stime);
+    let num_threads = u64::try_from(stat.num_threads).unwrap_or(0);
 
     Ok((
         ProcessHarvest {
@@ -217,6 +253,10 @@ fn read_proc(
             total_read_bytes,
             total_write_bytes,
             process_state,
+            time,
+            cpu_time: Some(cpu_time),
+            num_threads: Some(num_threads),
+            nice: Some(stat.nice),
             uid: Some(uid),
             user: user_table
                 .get_uid_to_username_mapping(uid)
@@ -236,6 +276,7 @@ pub fn get_process_data(
 
     if let Ok((cpu_usage, cpu_fraction)) = cpu_usage_calculation(prev_idle, prev_non_idle) {
         let mut pids_to_clear: FxHashSet<Pid> = pid_mapping.keys().cloned().collect();
+        let tick_info = TickInfo::new();
 
         let process_vector: Vec<ProcessHarvest> = std::fs::read_dir("/proc")?
             .filter_map(|dir| {
@@ -273,6 +314,7 @@ pub fn get_process_data(
                                 use_current_cpu_total,
                                 time_difference_in_secs,
                                 mem_total_kb,
+                                &tick_info,
                                 user_table,
                             ) {
                                 prev_proc_details.cpu_time = new_process_times;
@@ -2,6 +2,7 @@
 
 use std::collections::HashMap;
 use std::io;
+use std::time::Duration;
 
 use super::ProcessHarvest;
 use sysinfo::{CpuExt, PidExt, ProcessExt, ProcessStatus, System, SystemExt};
@@ -99,6 +100,7 @@ where
             total_read_bytes: disk_usage.total_read_bytes,
             total_write_bytes: disk_usage.total_written_bytes,
             process_state,
+            time: Duration::from_secs(process_val.run_time()),
             uid,
             user: uid
                 .and_then(|uid| {
@@ -108,6 +110,7 @@ where
                         .ok()
                 })
                 .unwrap_or_else(|| "N/A".into()),
+            ..Default::default()
         });
     }
 
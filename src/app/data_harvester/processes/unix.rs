@@ -1,9 +1,14 @@
 //! Unix-specific parts of process collection.
 
+use std::sync::Mutex;
+
 use fxhash::FxHashMap;
 
 use crate::utils::error;
 
+/// The passwd functions share static storage, so only one thread may use them at a time.
+static PASSWD_LOCK: Mutex<()> = Mutex::new(());
+
 #[derive(Debug, Default)]
 pub struct UserTable {
     pub uid_user_mapping: FxHashMap<libc::uid_t, String>,
@@ -14,6 +19,8 @@ impl UserTable {
         if let Some(user) = self.uid_user_mapping.get(&uid) {
             Ok(user.clone())
         } else {
+            let _guard = PASSWD_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+
             // SAFETY: getpwuid returns a null pointer if no passwd entry is found for the uid
             let passwd = unsafe { libc::getpwuid(uid) };
 
@@ -29,4 +36,33 @@ impl UserTable {
             Ok(username)
         }
     }
+
+    /// Returns the name of every user in the passwd database, along with any already in the
+    /// table.  Any new users found are added to the table.
+    pub fn usernames(&mut self) -> Vec<&str> {
+        {
+            let _guard = PASSWD_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+
+            // SAFETY: getpwent returns a null pointer once there are no more entries, and the
+            // entries are only read before the next call.
+            unsafe {
+                libc::setpwent();
+                loop {
+                    let passwd = libc::getpwent();
+                    if passwd.is_null() {
+                        break;
+                    }
+
+                    if let Ok(username) = std::ffi::CStr::from_ptr((*passwd).pw_name).to_str() {
+                        self.uid_user_mapping
+                            .entry((*passwd).pw_uid)
+                            .or_insert_with(|| username.to_string());
+                    }
+                }
+                libc::endpwent();
+            }
+        }
+
+        self.uid_user_mapping.values().map(String::as_str).collect()
+    }
 }
@@ -1,5 +1,7 @@
 //! Process data collection for Windows.  Uses sysinfo.
 
+use std::time::Duration;
+
 use super::ProcessHarvest;
 use sysinfo::{CpuExt, PidExt, ProcessExt, System, SystemExt};
 
@@ -73,6 +75,8 @@ pub fn get_process_data(
             total_read_bytes: disk_usage.total_read_bytes,
             total_write_bytes: disk_usage.total_written_bytes,
             process_state,
+            time: Duration::from_secs(process_val.run_time()),
+            ..Default::default()
         });
     }
 
@@ -21,6 +21,14 @@ impl FrozenState {
         matches!(self, FrozenState::Frozen(_))
     }
 
+    /// Returns the frozen copy of the data if frozen, otherwise the given live data.
+    pub fn data<'a>(&'a self, live_data: &'a DataCollection) -> &'a DataCollection {
+        match self {
+            FrozenState::NotFrozen => live_data,
+            FrozenState::Frozen(data) => data,
+        }
+    }
+
     /// Freezes the [`FrozenState`].
     pub fn freeze(&mut self, data: Box<DataCollection>) {
         *self = FrozenState::Frozen(data);
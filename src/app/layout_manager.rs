@@ -886,13 +886,17 @@ pub enum BottomWidgetType {
     Empty,
     Cpu,
     CpuLegend,
+    CpuHeatmap,
     Mem,
+    Pressure,
+    Kernel,
     Net,
     Proc,
     ProcSearch,
     ProcSort,
     Temp,
     Disk,
+    DiskIo,
     BasicCpu,
     BasicMem,
     BasicNet,
@@ -908,18 +912,25 @@ impl BottomWidgetType {
 
     pub fn is_widget_graph(&self) -> bool {
         use BottomWidgetType::*;
-        matches!(self, Cpu | Net | Mem)
+        matches!(
+            self,
+            Cpu | CpuHeatmap | Net | Mem | Pressure | Kernel | DiskIo
+        )
     }
 
     pub fn get_pretty_name(&self) -> &str {
         use BottomWidgetType::*;
         match self {
             Cpu => "CPU",
+            CpuHeatmap => "CPU Heatmap",
             Mem => "Memory",
+            Pressure => "Pressure",
+            Kernel => "Kernel Activity",
             Net => "Network",
             Proc => "Processes",
             Temp => "Temperature",
             Disk => "Disks",
+            DiskIo => "Disk I/O",
             Battery => "Battery",
             _ => "",
         }
@@ -939,11 +950,15 @@ impl std::str::FromStr for BottomWidgetType {
         let lower_case = s.to_lowercase();
         match lower_case.as_str() {
             "cpu" => Ok(BottomWidgetType::Cpu),
+            "cpu_heatmap" | "heatmap" => Ok(BottomWidgetType::CpuHeatmap),
             "mem" | "memory" => Ok(BottomWidgetType::Mem),
+            "pressure" | "psi" => Ok(BottomWidgetType::Pressure),
+            "kernel" | "kernel_activity" => Ok(BottomWidgetType::Kernel),
             "net" | "network" => Ok(BottomWidgetType::Net),
             "proc" | "process" | "processes" => Ok(BottomWidgetType::Proc),
             "temp" | "temperature" => Ok(BottomWidgetType::Temp),
             "disk" => Ok(BottomWidgetType::Disk),
+            "disk_io" | "io" => Ok(BottomWidgetType::DiskIo),
             "empty" => Ok(BottomWidgetType::Empty),
             "battery" | "batt" if cfg!(feature = "battery") => Ok(BottomWidgetType::Battery),
             _ => {
@@ -955,8 +970,14 @@ Supported widget names:
 +--------------------------+
 |            cpu           |
 +--------------------------+
+|   cpu_heatmap, heatmap   |
++--------------------------+
 |        mem, memory       |
 +--------------------------+
+|      pressure, psi       |
++--------------------------+
+| kernel, kernel_activity  |
++--------------------------+
 |       net, network       |
 +--------------------------+
 | proc, process, processes |
@@ -965,6 +986,8 @@ Supported widget names:
 +--------------------------+
 |           disk           |
 +--------------------------+
+|       disk_io, io        |
++--------------------------+
 |       batt, battery      |
 +--------------------------+
                 ",
@@ -978,8 +1001,14 @@ Supported widget names:
 +--------------------------+
 |            cpu           |
 +--------------------------+
+|   cpu_heatmap, heatmap   |
++--------------------------+
 |        mem, memory       |
 +--------------------------+
+|      pressure, psi       |
++--------------------------+
+| kernel, kernel_activity  |
++--------------------------+
 |       net, network       |
 +--------------------------+
 | proc, process, processes |
@@ -987,6 +1016,8 @@ Supported widget names:
 |     temp, temperature    |
 +--------------------------+
 |           disk           |
++--------------------------+
+|       disk_io, io        |
 +--------------------------+
                 ",
                         s
@@ -1007,4 +1038,6 @@ pub struct UsedWidgets {
     pub use_disk: bool,
     pub use_temp: bool,
     pub use_battery: bool,
+    pub use_pressure: bool,
+    pub use_kernel: bool,
 }
@@ -2,15 +2,145 @@ use crate::utils::error::{
     BottomError::{self, QueryError},
     Result,
 };
-use std::fmt::Debug;
-use std::{borrow::Cow, collections::VecDeque};
+use std::fmt::{Debug, Display};
+use std::{borrow::Cow, collections::VecDeque, ops::Range};
 
 use super::data_harvester::processes::ProcessHarvest;
 
 const DELIMITER_LIST: [char; 6] = ['=', '>', '<', '(', ')', '\"'];
+
+/// A shorthand for `NOT`, which is only treated as its own token at the start of a term so that
+/// names like `foo!bar` are left alone.
+const NOT_CHAR: char = '!';
 const COMPARISON_LIST: [&str; 3] = [">", "=", "<"];
 const OR_LIST: [&str; 2] = ["or", "||"];
 const AND_LIST: [&str; 2] = ["and", "&&"];
+const NOT_LIST: [&str; 2] = ["not", "!"];
+
+/// The names of all prefixes, used for tab completion.  Aliases are left out to keep this short.
+pub const PREFIX_NAMES: [&str; 15] = [
+    "cpu", "mem", "memb", "read", "write", "tread", "twrite", "pid", "ppid", "age", "cputime",
+    "threads", "nice", "state", "user",
+];
+
+type ParseResult<T> = std::result::Result<T, QueryParseError>;
+
+/// An error from parsing a query, along with where in the query it happened, if known.
+#[derive(Debug)]
+pub struct QueryParseError {
+    pub error: BottomError,
+
+    /// The byte range of the token in the query that caused the error.
+    pub range: Option<Range<usize>>,
+}
+
+impl Display for QueryParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.error, f)
+    }
+}
+
+impl From<BottomError> for QueryParseError {
+    fn from(error: BottomError) -> Self {
+        QueryParseError { error, range: None }
+    }
+}
+
+impl From<QueryParseError> for BottomError {
+    fn from(err: QueryParseError) -> Self {
+        err.error
+    }
+}
+
+/// Splits a query into tokens by whitespace, with delimiters being their own tokens, as is a `!`
+/// that starts a term.  Each token is paired with its byte range in the query.
+fn tokenize(search_query: &str) -> Vec<(String, Range<usize>)> {
+    let mut tokens = vec![];
+    let mut token_start = None;
+
+    for (index, c) in search_query.char_indices() {
+        if c == NOT_CHAR && token_start.is_none() {
+            tokens.push((c.to_string(), index..index + c.len_utf8()));
+        } else if c.is_whitespace() || DELIMITER_LIST.contains(&c) {
+            if let Some(start) = token_start.take() {
+                tokens.push((search_query[start..index].to_string(), start..index));
+            }
+            if !c.is_whitespace() {
+                tokens.push((c.to_string(), index..index + c.len_utf8()));
+            }
+        } else if token_start.is_none() {
+            token_start = Some(index);
+        }
+    }
+
+    if let Some(start) = token_start {
+        tokens.push((search_query[start..].to_string(), start..search_query.len()));
+    }
+
+    tokens
+}
+
+/// The tokens of a query being parsed, which keeps track of where each token came from so that
+/// errors can point at the offending part of the query.
+struct QueryTokens {
+    tokens: VecDeque<(String, Range<usize>)>,
+
+    /// The range of the last token taken off the front.
+    last_range: Range<usize>,
+}
+
+impl QueryTokens {
+    fn new(search_query: &str) -> Self {
+        QueryTokens {
+            tokens: tokenize(search_query).into(),
+            last_range: 0..search_query.len(),
+        }
+    }
+
+    fn front(&self) -> Option<&String> {
+        self.tokens.front().map(|(token, _)| token)
+    }
+
+    fn pop_front(&mut self) -> Option<String> {
+        self.tokens.pop_front().map(|(token, range)| {
+            self.last_range = range;
+            token
+        })
+    }
+
+    /// Puts a token back on the front, as if it were the last token taken.
+    fn push_front(&mut self, token: String) {
+        self.tokens.push_front((token, self.last_range.clone()));
+    }
+
+    fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    fn last_range(&self) -> Range<usize> {
+        self.last_range.clone()
+    }
+
+    fn error_at(range: Range<usize>, message: impl Into<Cow<'static, str>>) -> QueryParseError {
+        QueryParseError {
+            error: QueryError(message.into()),
+            range: Some(range),
+        }
+    }
+
+    /// An error pointing at the last token taken.
+    fn error_at_last(&self, message: impl Into<Cow<'static, str>>) -> QueryParseError {
+        Self::error_at(self.last_range(), message)
+    }
+
+    /// An error pointing at the next token, or the last token taken if there is none.
+    fn error_at_front(&self, message: impl Into<Cow<'static, str>>) -> QueryParseError {
+        match self.tokens.front() {
+            Some((_, range)) => Self::error_at(range.clone(), message),
+            None => self.error_at_last(message),
+        }
+    }
+}
 
 /// In charge of parsing the given query.
 /// We are defining the following language for a query (case-insensitive prefixes):
@@ -27,15 +157,21 @@ const AND_LIST: [&str; 2] = ["and", "&&"];
 /// - Write/s: Use prefix `w`.  Can compare.
 /// - Total read: Use prefix `read`.  Can compare.
 /// - Total write: Use prefix `write`.  Can compare.
+/// - PPID: Use prefix `ppid`, can use regex or match word (case is irrelevant).
+/// - Age: Use prefix `age`.  Can compare, with time units.
+/// - CPU time: Use prefix `cputime`.  Can compare, with time units.
+/// - Threads: Use prefix `threads`.  Can compare.
+/// - Nice: Use prefix `nice`.  Can compare.
 ///
 /// For queries, whitespaces are our delimiters.  We will merge together any adjacent non-prefixed
 /// or quoted elements after splitting to treat as process names.
-/// Furthermore, we want to support boolean joiners like AND and OR, and brackets.
+/// Furthermore, we want to support boolean joiners like AND and OR, brackets, and a unary NOT
+/// that can be placed before any prefix or bracketed group.
 pub fn parse_query(
     search_query: &str, is_searching_whole_word: bool, is_ignoring_case: bool,
     is_searching_with_regex: bool,
-) -> Result<Query> {
-    fn process_string_to_filter(query: &mut VecDeque<String>) -> Result<Query> {
+) -> ParseResult<Query> {
+    fn process_string_to_filter(query: &mut QueryTokens) -> ParseResult<Query> {
         let lhs = process_or(query)?;
         let mut list_of_ors = vec![lhs];
 
@@ -46,7 +182,7 @@ pub fn parse_query(
         Ok(Query { query: list_of_ors })
     }
 
-    fn process_or(query: &mut VecDeque<String>) -> Result<Or> {
+    fn process_or(query: &mut QueryTokens) -> ParseResult<Or> {
         let mut lhs = process_and(query)?;
         let mut rhs: Option<Box<And>> = None;
 
@@ -64,6 +200,7 @@ pub fn parse_query(
                                 or: Some(Box::new(Or { lhs, rhs })),
                                 regex_prefix: None,
                                 compare_prefix: None,
+                                is_negated: false,
                             },
                             rhs: None,
                         };
@@ -73,7 +210,7 @@ pub fn parse_query(
                     break;
                 }
             } else if COMPARISON_LIST.contains(&queue_top.to_lowercase().as_str()) {
-                return Err(QueryError(Cow::Borrowed("Comparison not valid here")));
+                return Err(query.error_at_front("Comparison not valid here"));
             } else {
                 break;
             }
@@ -82,7 +219,7 @@ pub fn parse_query(
         Ok(Or { lhs, rhs })
     }
 
-    fn process_and(query: &mut VecDeque<String>) -> Result<And> {
+    fn process_and(query: &mut QueryTokens) -> ParseResult<And> {
         let mut lhs = process_prefix(query, false)?;
         let mut rhs: Option<Box<Prefix>> = None;
 
@@ -103,6 +240,7 @@ pub fn parse_query(
                             })),
                             regex_prefix: None,
                             compare_prefix: None,
+                            is_negated: false,
                         };
                         rhs = None;
                     } else {
@@ -112,7 +250,7 @@ pub fn parse_query(
                     break;
                 }
             } else if COMPARISON_LIST.contains(&queue_top.to_lowercase().as_str()) {
-                return Err(QueryError(Cow::Borrowed("Comparison not valid here")));
+                return Err(query.error_at_front("Comparison not valid here"));
             } else {
                 break;
             }
@@ -121,7 +259,7 @@ pub fn parse_query(
         Ok(And { lhs, rhs })
     }
 
-    fn process_prefix(query: &mut VecDeque<String>, inside_quotation: bool) -> Result<Prefix> {
+    fn process_prefix(query: &mut QueryTokens, inside_quotation: bool) -> ParseResult<Prefix> {
         if let Some(queue_top) = query.pop_front() {
             if inside_quotation {
                 if queue_top == "\"" {
@@ -135,6 +273,7 @@ pub fn parse_query(
                             StringQuery::Value(String::default()),
                         )),
                         compare_prefix: None,
+                        is_negated: false,
                     });
                 } else {
                     let mut quoted_string = queue_top;
@@ -151,11 +290,35 @@ pub fn parse_query(
                         or: None,
                         regex_prefix: Some((PrefixType::Name, StringQuery::Value(quoted_string))),
                         compare_prefix: None,
+                        is_negated: false,
                     });
                 }
+            } else if NOT_LIST.contains(&queue_top.to_lowercase().as_str()) {
+                // A NOT applies to whatever single prefix (or group) follows it, so make sure
+                // there is actually something to negate first.
+                if let Some(queue_next) = query.front() {
+                    let queue_next = queue_next.to_lowercase();
+                    if queue_next == ")"
+                        || OR_LIST.contains(&queue_next.as_str())
+                        || AND_LIST.contains(&queue_next.as_str())
+                        || COMPARISON_LIST.contains(&queue_next.as_str())
+                    {
+                        return Err(query.error_at_front("NOT must be followed by a condition"));
+                    }
+                } else {
+                    return Err(query.error_at_last("Missing condition after NOT"));
+                }
+
+                let mut prefix = process_prefix(query, false)?;
+                prefix.is_negated = !prefix.is_negated;
+                return Ok(prefix);
             } else if queue_top == "(" {
+                let open_paren_range = query.last_range();
                 if query.is_empty() {
-                    return Err(QueryError(Cow::Borrowed("Missing closing parentheses")));
+                    return Err(QueryTokens::error_at(
+                        open_paren_range,
+                        "Missing closing parentheses",
+                    ));
                 }
 
                 let mut list_of_ors = VecDeque::new();
@@ -170,7 +333,7 @@ pub fn parse_query(
 
                 // Ensure not empty
                 if list_of_ors.is_empty() {
-                    return Err(QueryError("No values within parentheses group".into()));
+                    return Err(query.error_at_front("No values within parentheses group"));
                 }
 
                 // Now convert this back to a OR...
@@ -180,6 +343,7 @@ pub fn parse_query(
                             or: list_of_ors.pop_front().map(Box::new),
                             compare_prefix: None,
                             regex_prefix: None,
+                            is_negated: false,
                         },
                         rhs: None,
                     },
@@ -191,11 +355,13 @@ pub fn parse_query(
                             or: Some(Box::new(lhs)),
                             compare_prefix: None,
                             regex_prefix: None,
+                            is_negated: false,
                         },
                         rhs: Some(Box::new(Prefix {
                             or: Some(Box::new(rhs)),
                             compare_prefix: None,
                             regex_prefix: None,
+                            is_negated: false,
                         })),
                     },
                     rhs: None,
@@ -207,32 +373,62 @@ pub fn parse_query(
                             or: Some(Box::new(returned_or)),
                             regex_prefix: None,
                             compare_prefix: None,
+                            is_negated: false,
                         });
                     } else {
-                        return Err(QueryError("Missing closing parentheses".into()));
+                        return Err(QueryTokens::error_at(
+                            open_paren_range,
+                            "Missing closing parentheses",
+                        ));
                     }
                 } else {
-                    return Err(QueryError("Missing closing parentheses".into()));
+                    return Err(QueryTokens::error_at(
+                        open_paren_range,
+                        "Missing closing parentheses",
+                    ));
                 }
             } else if queue_top == ")" {
-                return Err(QueryError("Missing opening parentheses".into()));
+                return Err(query.error_at_last("Missing opening parentheses"));
             } else if queue_top == "\"" {
                 // Similar to parentheses, trap and check for missing closing quotes.  Note, however, that we
                 // will DIRECTLY call another process_prefix call...
 
-                let prefix = process_prefix(query, true)?;
+                let open_quote_range = query.last_range();
+                let prefix = process_prefix(query, true).map_err(|mut err| {
+                    if err.range.is_none() {
+                        err.range = Some(open_quote_range.clone());
+                    }
+                    err
+                })?;
                 if let Some(close_paren) = query.pop_front() {
                     if close_paren == "\"" {
                         return Ok(prefix);
                     } else {
-                        return Err(QueryError("Missing closing quotation".into()));
+                        return Err(QueryTokens::error_at(
+                            open_quote_range,
+                            "Missing closing quotation",
+                        ));
                     }
                 } else {
-                    return Err(QueryError("Missing closing quotation".into()));
+                    return Err(QueryTokens::error_at(
+                        open_quote_range,
+                        "Missing closing quotation",
+                    ));
                 }
             } else {
                 //  Get prefix type...
                 let prefix_type = queue_top.parse::<PrefixType>()?;
+
+                #[cfg(not(target_os = "linux"))]
+                if matches!(
+                    prefix_type,
+                    PrefixType::CpuTime | PrefixType::Threads | PrefixType::Nice
+                ) {
+                    return Err(query.error_at_last(format!(
+                        "{} is only supported on Linux",
+                        queue_top.to_lowercase()
+                    )));
+                }
                 let content = if let PrefixType::Name = prefix_type {
                     Some(queue_top)
                 } else {
@@ -246,9 +442,13 @@ pub fn parse_query(
                                 or: None,
                                 regex_prefix: Some((prefix_type, StringQuery::Value(content))),
                                 compare_prefix: None,
+                                is_negated: false,
                             })
                         }
-                        PrefixType::Pid | PrefixType::State | PrefixType::User => {
+                        PrefixType::Pid
+                        | PrefixType::Ppid
+                        | PrefixType::State
+                        | PrefixType::User => {
                             // We have to check if someone put an "="...
                             if content == "=" {
                                 // Check next string if possible
@@ -269,6 +469,7 @@ pub fn parse_query(
                                             StringQuery::Value(queue_next),
                                         )),
                                         compare_prefix: None,
+                                        is_negated: false,
                                     });
                                 }
                             } else {
@@ -276,6 +477,7 @@ pub fn parse_query(
                                     or: None,
                                     regex_prefix: Some((prefix_type, StringQuery::Value(content))),
                                     compare_prefix: None,
+                                    is_negated: false,
                                 });
                             }
                         }
@@ -283,14 +485,14 @@ pub fn parse_query(
                             // Now we gotta parse the content... yay.
 
                             let mut condition: Option<QueryComparison> = None;
-                            let mut value: Option<f64> = None;
+                            let mut value: Option<(f64, Option<String>)> = None;
 
                             if content == "=" {
                                 condition = Some(QueryComparison::Equal);
                                 if let Some(queue_next) = query.pop_front() {
-                                    value = queue_next.parse::<f64>().ok();
+                                    value = parse_value(&queue_next);
                                 } else {
-                                    return Err(QueryError("Missing value".into()));
+                                    return Err(query.error_at_last("Missing value"));
                                 }
                             } else if content == ">" || content == "<" {
                                 // We also have to check if the next string is an "="...
@@ -302,9 +504,9 @@ pub fn parse_query(
                                             QueryComparison::LessOrEqual
                                         });
                                         if let Some(queue_next_next) = query.pop_front() {
-                                            value = queue_next_next.parse::<f64>().ok();
+                                            value = parse_value(&queue_next_next);
                                         } else {
-                                            return Err(QueryError("Missing value".into()));
+                                            return Err(query.error_at_last("Missing value"));
                                         }
                                     } else {
                                         condition = Some(if content == ">" {
@@ -312,73 +514,36 @@ pub fn parse_query(
                                         } else {
                                             QueryComparison::Less
                                         });
-                                        value = queue_next.parse::<f64>().ok();
+                                        value = parse_value(&queue_next);
                                     }
                                 } else {
-                                    return Err(QueryError("Missing value".into()));
+                                    return Err(query.error_at_last("Missing value"));
                                 }
                             }
 
                             if let Some(condition) = condition {
-                                if let Some(read_value) = value {
+                                if let Some((read_value, attached_unit)) = value {
                                     // Now we want to check one last thing - is there a unit?
                                     // If no unit, assume base.
-                                    // Furthermore, base must be PEEKED at initially, and will
-                                    // require (likely) prefix_type specific checks
-                                    // Lastly, if it *is* a unit, remember to POP!
-
-                                    let mut value = read_value;
-
-                                    match prefix_type {
-                                        PrefixType::MemBytes
-                                        | PrefixType::Rps
-                                        | PrefixType::Wps
-                                        | PrefixType::TRead
-                                        | PrefixType::TWrite => {
-                                            if let Some(potential_unit) = query.front() {
-                                                match potential_unit.to_lowercase().as_str() {
-                                                    "tb" => {
-                                                        value *= 1_000_000_000_000.0;
-                                                        query.pop_front();
-                                                    }
-                                                    "tib" => {
-                                                        value *= 1_099_511_627_776.0;
-                                                        query.pop_front();
-                                                    }
-                                                    "gb" => {
-                                                        value *= 1_000_000_000.0;
-                                                        query.pop_front();
-                                                    }
-                                                    "gib" => {
-                                                        value *= 1_073_741_824.0;
-                                                        query.pop_front();
-                                                    }
-                                                    "mb" => {
-                                                        value *= 1_000_000.0;
-                                                        query.pop_front();
-                                                    }
-                                                    "mib" => {
-                                                        value *= 1_048_576.0;
-                                                        query.pop_front();
-                                                    }
-                                                    "kb" => {
-                                                        value *= 1000.0;
-                                                        query.pop_front();
-                                                    }
-                                                    "kib" => {
-                                                        value *= 1024.0;
-                                                        query.pop_front();
-                                                    }
-                                                    "b" => {
-                                                        // Just gotta pop.
-                                                        query.pop_front();
-                                                    }
-                                                    _ => {}
-                                                }
-                                            }
-                                        }
-                                        _ => {}
-                                    }
+                                    // The unit may either be attached to the value (e.g. `10m`), or
+                                    // be PEEKED at as the next string (e.g. `10 mb`), which will
+                                    // require prefix_type specific checks.
+                                    // Lastly, if it *is* a separate unit, remember to POP!
+
+                                    let multiplier = if let Some(unit) = attached_unit {
+                                        unit_multiplier(&prefix_type, &unit).ok_or_else(|| {
+                                            query.error_at_last(format!("Invalid unit '{}'", unit))
+                                        })?
+                                    } else if let Some(multiplier) = query
+                                        .front()
+                                        .and_then(|unit| unit_multiplier(&prefix_type, unit))
+                                    {
+                                        query.pop_front();
+                                        multiplier
+                                    } else {
+                                        1.0
+                                    };
+                                    let value = read_value * multiplier;
 
                                     return Ok(Prefix {
                                         or: None,
@@ -387,39 +552,25 @@ pub fn parse_query(
                                             prefix_type,
                                             NumericalQuery { condition, value },
                                         )),
+                                        is_negated: false,
                                     });
                                 }
                             }
                         }
                     }
                 } else {
-                    return Err(QueryError("Missing argument for search prefix".into()));
+                    return Err(query.error_at_last("Missing argument for search prefix"));
                 }
             }
         } else if inside_quotation {
-            // Uh oh, it's empty with quotes!
-            return Err(QueryError("Missing closing quotation".into()));
+            // Uh oh, it's empty with quotes!  The caller fills in where the quote started.
+            return Err(QueryError("Missing closing quotation".into()).into());
         }
 
-        Err(QueryError("Invalid query".into()))
+        Err(query.error_at_last("Invalid query"))
     }
 
-    let mut split_query = VecDeque::new();
-
-    search_query.split_whitespace().for_each(|s| {
-        // From https://stackoverflow.com/a/56923739 in order to get a split but include the parentheses
-        let mut last = 0;
-        for (index, matched) in s.match_indices(|x| DELIMITER_LIST.contains(&x)) {
-            if last != index {
-                split_query.push_back(s[last..index].to_owned());
-            }
-            split_query.push_back(matched.to_owned());
-            last = index + matched.len();
-        }
-        if last < s.len() {
-            split_query.push_back(s[last..].to_owned());
-        }
-    });
+    let mut split_query = QueryTokens::new(search_query);
 
     let mut process_filter = process_string_to_filter(&mut split_query)?;
     process_filter.process_regexes(
@@ -431,6 +582,234 @@ pub fn parse_query(
     Ok(process_filter)
 }
 
+/// Splits a numerical value like `1.5` or `10m` into the number and the unit attached to it, if any.
+fn parse_value(value: &str) -> Option<(f64, Option<String>)> {
+    if let Ok(number) = value.parse::<f64>() {
+        return Some((number, None));
+    }
+
+    let unit_start = value.find(|c: char| c.is_ascii_alphabetic())?;
+    let (number, unit) = value.split_at(unit_start);
+    number
+        .parse::<f64>()
+        .ok()
+        .map(|number| (number, Some(unit.to_string())))
+}
+
+/// Returns what a value should be multiplied by given a unit, for the given prefix type.  Byte
+/// prefixes are stored in bytes, and time prefixes in seconds.  Returns `None` if the unit is not
+/// valid for the prefix type.
+fn unit_multiplier(prefix_type: &PrefixType, unit: &str) -> Option<f64> {
+    match prefix_type {
+        PrefixType::MemBytes
+        | PrefixType::Rps
+        | PrefixType::Wps
+        | PrefixType::TRead
+        | PrefixType::TWrite => match unit.to_lowercase().as_str() {
+            "tb" => Some(1_000_000_000_000.0),
+            "tib" => Some(1_099_511_627_776.0),
+            "gb" => Some(1_000_000_000.0),
+            "gib" => Some(1_073_741_824.0),
+            "mb" => Some(1_000_000.0),
+            "mib" => Some(1_048_576.0),
+            "kb" => Some(1000.0),
+            "kib" => Some(1024.0),
+            "b" => Some(1.0),
+            _ => None,
+        },
+        PrefixType::Age | PrefixType::CpuTime => match unit.to_lowercase().as_str() {
+            "ms" => Some(0.001),
+            "s" | "sec" | "secs" => Some(1.0),
+            "m" | "min" | "mins" => Some(60.0),
+            "h" | "hr" | "hrs" => Some(3600.0),
+            "d" | "day" | "days" => Some(86400.0),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// What a part of a query is, for syntax highlighting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryTokenKind {
+    /// A search prefix, like `cpu` or `user`.
+    Prefix,
+    /// A boolean operator, comparison, parenthesis, or quote.
+    Operator,
+    /// The argument to a prefix, including any unit.
+    Value,
+    /// Anything else, which is searched for as a process name.
+    Name,
+}
+
+/// Classifies each part of a query for syntax highlighting, returning their byte ranges.  This is
+/// a best effort pass that works even if the query is incomplete or invalid.
+pub fn highlight_query(search_query: &str) -> Vec<(Range<usize>, QueryTokenKind)> {
+    let mut is_in_quotes = false;
+    let mut awaiting_argument: Option<PrefixType> = None;
+    let mut awaiting_unit: Option<PrefixType> = None;
+
+    tokenize(search_query)
+        .into_iter()
+        .map(|(token, range)| {
+            let lower_case = token.to_lowercase();
+            let unit_prefix = awaiting_unit.take();
+
+            let kind = if token == "\"" {
+                is_in_quotes = !is_in_quotes;
+                awaiting_argument = None;
+                QueryTokenKind::Operator
+            } else if is_in_quotes {
+                QueryTokenKind::Name
+            } else if COMPARISON_LIST.contains(&lower_case.as_str()) {
+                QueryTokenKind::Operator
+            } else if let Some(prefix_type) = awaiting_argument.take() {
+                awaiting_unit = Some(prefix_type);
+                QueryTokenKind::Value
+            } else if unit_prefix
+                .map(|prefix_type| unit_multiplier(&prefix_type, &token).is_some())
+                .unwrap_or(false)
+            {
+                QueryTokenKind::Value
+            } else if token == "("
+                || token == ")"
+                || OR_LIST.contains(&lower_case.as_str())
+                || AND_LIST.contains(&lower_case.as_str())
+                || NOT_LIST.contains(&lower_case.as_str())
+            {
+                QueryTokenKind::Operator
+            } else {
+                match token.parse::<PrefixType>() {
+                    Ok(PrefixType::Name) | Err(_) => QueryTokenKind::Name,
+                    Ok(prefix_type) => {
+                        awaiting_argument = Some(prefix_type);
+                        QueryTokenKind::Prefix
+                    }
+                }
+            };
+
+            (range, kind)
+        })
+        .collect()
+}
+
+/// What kind of word can be tab completed at some point in a query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+    /// A prefix or a process name.
+    PrefixOrName,
+    /// A process name inside quotes.
+    QuotedName,
+    /// A username, following the `user` prefix.
+    User,
+}
+
+/// Returns the byte range of the word ending at the cursor that can be completed, and what it can
+/// be completed with.  Returns `None` if nothing sensible can be completed there.
+pub fn completion_target(
+    search_query: &str, cursor_position: usize,
+) -> Option<(Range<usize>, CompletionKind)> {
+    if search_query[cursor_position..]
+        .chars()
+        .next()
+        .map(|c| !c.is_whitespace() && !DELIMITER_LIST.contains(&c))
+        .unwrap_or(false)
+    {
+        // Don't try to complete from the middle of a word.
+        return None;
+    }
+
+    let before_cursor = &search_query[..cursor_position];
+    let mut highlights = highlight_query(before_cursor);
+    let word_range = match highlights.last() {
+        Some((range, QueryTokenKind::Name | QueryTokenKind::Value | QueryTokenKind::Prefix))
+            if range.end == cursor_position =>
+        {
+            let range = range.clone();
+            highlights.pop();
+            range
+        }
+        _ => cursor_position..cursor_position,
+    };
+
+    let is_in_quotes = highlights
+        .iter()
+        .filter(|(range, _)| &before_cursor[range.clone()] == "\"")
+        .count()
+        % 2
+        == 1;
+    if is_in_quotes {
+        return if word_range.is_empty() {
+            None
+        } else {
+            Some((word_range, CompletionKind::QuotedName))
+        };
+    }
+
+    let previous = highlights
+        .iter()
+        .rev()
+        .find(|(range, _)| !COMPARISON_LIST.contains(&&before_cursor[range.clone()]));
+    match previous {
+        Some((range, QueryTokenKind::Prefix)) => {
+            if before_cursor[range.clone()].eq_ignore_ascii_case("user") {
+                Some((word_range, CompletionKind::User))
+            } else {
+                None
+            }
+        }
+        _ if word_range.is_empty() => None,
+        _ => Some((word_range, CompletionKind::PrefixOrName)),
+    }
+}
+
+/// Returns everything that the given partial word could be completed to, in the order they should
+/// be cycled through.  Matching is case-insensitive.
+pub fn completion_candidates<'a>(
+    partial: &str, kind: CompletionKind, usernames: impl IntoIterator<Item = &'a str>,
+    process_names: impl IntoIterator<Item = &'a str>,
+) -> Vec<String> {
+    fn matching<'a>(partial: &str, words: impl IntoIterator<Item = &'a str>) -> Vec<&'a str> {
+        let partial = partial.to_lowercase();
+        let mut matches = words
+            .into_iter()
+            .filter(|word| word.to_lowercase().starts_with(&partial))
+            .collect::<Vec<_>>();
+        matches.sort_unstable();
+        matches.dedup();
+        matches
+    }
+
+    match kind {
+        CompletionKind::User => matching(partial, usernames)
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+        CompletionKind::QuotedName => matching(partial, process_names)
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+        CompletionKind::PrefixOrName => matching(partial, PREFIX_NAMES)
+            .into_iter()
+            .chain(
+                matching(partial, process_names)
+                    .into_iter()
+                    .filter(|name| !PREFIX_NAMES.contains(name)),
+            )
+            .map(|word| {
+                // Names that would otherwise be split up or parsed as something else need quotes.
+                if word.starts_with(NOT_CHAR)
+                    || word.contains(|c: char| c.is_whitespace() || DELIMITER_LIST.contains(&c))
+                {
+                    format!("\"{}\"", word)
+                } else {
+                    word.to_string()
+                }
+            })
+            .collect(),
+    }
+}
+
 pub struct Query {
     /// Remember, AND > OR, but AND must come after OR when we parse.
     pub query: Vec<Or>,
@@ -565,6 +944,11 @@ pub enum PrefixType {
     Wps,
     TRead,
     TWrite,
+    Ppid,
+    Age,
+    CpuTime,
+    Threads,
+    Nice,
     Name,
     State,
     User,
@@ -589,6 +973,11 @@ impl std::str::FromStr for PrefixType {
             "tread" | "t.read" => Ok(TRead),
             "twrite" | "t.write" => Ok(TWrite),
             "pid" => Ok(Pid),
+            "ppid" => Ok(Ppid),
+            "age" => Ok(Age),
+            "cputime" | "cpu_time" => Ok(CpuTime),
+            "threads" => Ok(Threads),
+            "nice" => Ok(Nice),
             "state" => Ok(State),
             "user" => Ok(User),
             _ => Ok(Name),
@@ -601,6 +990,8 @@ pub struct Prefix {
     pub or: Option<Box<Or>>,
     pub regex_prefix: Option<(PrefixType, StringQuery)>,
     pub compare_prefix: Option<(PrefixType, NumericalQuery)>,
+    /// Whether the result of this prefix should be inverted, from a `NOT` or `!`.
+    pub is_negated: bool,
 }
 
 impl Prefix {
@@ -617,7 +1008,11 @@ impl Prefix {
         } else if let Some((prefix_type, StringQuery::Value(regex_string))) = &mut self.regex_prefix
         {
             match prefix_type {
-                PrefixType::Pid | PrefixType::Name | PrefixType::State | PrefixType::User => {
+                PrefixType::Pid
+                | PrefixType::Ppid
+                | PrefixType::Name
+                | PrefixType::State
+                | PrefixType::User => {
                     let escaped_regex: String;
                     let final_regex_string = &format!(
                         "{}{}{}{}",
@@ -658,7 +1053,7 @@ impl Prefix {
             }
         }
 
-        if let Some(and) = &self.or {
+        let is_match = if let Some(and) = &self.or {
             and.check(process, is_using_command)
         } else if let Some((prefix_type, query_content)) = &self.regex_prefix {
             if let StringQuery::Regex(r) = query_content {
@@ -669,6 +1064,10 @@ impl Prefix {
                         process.name.as_str()
                     }),
                     PrefixType::Pid => r.is_match(process.pid.to_string().as_str()),
+                    PrefixType::Ppid => process
+                        .parent_pid
+                        .map(|ppid| r.is_match(ppid.to_string().as_str()))
+                        .unwrap_or(false),
                     PrefixType::State => r.is_match(process.process_state.0.as_str()),
                     PrefixType::User => {
                         #[cfg(target_family = "unix")]
@@ -722,17 +1121,53 @@ impl Prefix {
                     process.total_write_bytes as f64,
                     numerical_query.value,
                 ),
+                PrefixType::Age => matches_condition(
+                    &numerical_query.condition,
+                    process.time.as_secs_f64(),
+                    numerical_query.value,
+                ),
+                // These aren't collected on every platform; a process with no value never matches.
+                PrefixType::CpuTime => match process.cpu_time {
+                    Some(value) => matches_condition(
+                        &numerical_query.condition,
+                        value.as_secs_f64(),
+                        numerical_query.value,
+                    ),
+                    None => false,
+                },
+                PrefixType::Threads => match process.num_threads {
+                    Some(value) => matches_condition(
+                        &numerical_query.condition,
+                        value as f64,
+                        numerical_query.value,
+                    ),
+                    None => false,
+                },
+                PrefixType::Nice => match process.nice {
+                    Some(value) => matches_condition(
+                        &numerical_query.condition,
+                        value as f64,
+                        numerical_query.value,
+                    ),
+                    None => false,
+                },
                 _ => true,
             }
         } else {
             // Somehow we have an empty condition... oh well.  Return true.
             true
-        }
+        };
+
+        is_match != self.is_negated
     }
 }
 
 impl Debug for Prefix {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_negated {
+            f.write_str("NOT ")?;
+        }
+
         if let Some(or) = &self.or {
             f.write_fmt(format_args!("{:?}", or))
         } else if let Some(regex_prefix) = &self.regex_prefix {
@@ -765,3 +1200,342 @@ pub struct NumericalQuery {
     pub condition: QueryComparison,
     pub value: f64,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn process(name: &str, pid: crate::Pid, cpu_usage_percent: f64) -> ProcessHarvest {
+        ProcessHarvest {
+            pid,
+            cpu_usage_percent,
+            name: name.to_string(),
+            command: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn parse(query: &str) -> ParseResult<Query> {
+        parse_query(query, false, true, false)
+    }
+
+    #[test]
+    fn test_not_name() {
+        let query = parse("not kworker").unwrap();
+        assert!(!query.check(&process("kworker/0:1", 10, 0.0), false));
+        assert!(query.check(&process("btm", 11, 0.0), false));
+
+        let query = parse("!kworker").unwrap();
+        assert!(!query.check(&process("kworker/0:1", 10, 0.0), false));
+        assert!(query.check(&process("btm", 11, 0.0), false));
+    }
+
+    #[test]
+    fn test_not_comparison() {
+        let query = parse("NOT cpu > 5").unwrap();
+        assert!(query.check(&process("btm", 1, 1.0), false));
+        assert!(!query.check(&process("btm", 1, 10.0), false));
+    }
+
+    #[test]
+    fn test_not_with_and() {
+        let query = parse("btm and not pid = 2").unwrap();
+        assert!(query.check(&process("btm", 1, 0.0), false));
+        assert!(!query.check(&process("btm", 2, 0.0), false));
+        assert!(!query.check(&process("firefox", 1, 0.0), false));
+
+        // Whitespace is an implicit AND, so this should behave the same.
+        let query = parse("btm !pid=2").unwrap();
+        assert!(query.check(&process("btm", 1, 0.0), false));
+        assert!(!query.check(&process("btm", 2, 0.0), false));
+    }
+
+    #[test]
+    fn test_not_binds_tighter_than_or() {
+        let query = parse("not btm or firefox").unwrap();
+        assert!(query.check(&process("firefox", 1, 0.0), false));
+        assert!(query.check(&process("zsh", 1, 0.0), false));
+        assert!(!query.check(&process("btm", 1, 0.0), false));
+    }
+
+    #[test]
+    fn test_not_group() {
+        let query = parse("not (btm or firefox)").unwrap();
+        assert!(!query.check(&process("firefox", 1, 0.0), false));
+        assert!(!query.check(&process("btm", 1, 0.0), false));
+        assert!(query.check(&process("zsh", 1, 0.0), false));
+
+        let query = parse("!(btm or firefox) and cpu < 5").unwrap();
+        assert!(query.check(&process("zsh", 1, 0.0), false));
+        assert!(!query.check(&process("zsh", 1, 10.0), false));
+        assert!(!query.check(&process("btm", 1, 0.0), false));
+    }
+
+    #[test]
+    fn test_double_not() {
+        let query = parse("not not btm").unwrap();
+        assert!(query.check(&process("btm", 1, 0.0), false));
+        assert!(!query.check(&process("zsh", 1, 0.0), false));
+    }
+
+    #[test]
+    fn test_quoted_not() {
+        let query = parse("\"not\"").unwrap();
+        assert!(query.check(&process("not", 1, 0.0), false));
+        assert!(!query.check(&process("btm", 1, 0.0), false));
+    }
+
+    #[test]
+    fn test_name_containing_not_char() {
+        let query = parse("foo!bar").unwrap();
+        assert!(query.check(&process("foo!bar", 1, 0.0), false));
+        assert!(!query.check(&process("foo", 1, 0.0), false));
+
+        let query = parse("zsh or foo!bar").unwrap();
+        assert!(query.check(&process("foo!bar", 1, 0.0), false));
+        assert!(query.check(&process("zsh", 1, 0.0), false));
+        assert!(!query.check(&process("bar", 1, 0.0), false));
+
+        let query = parse("btm !foo!bar").unwrap();
+        assert!(query.check(&process("btm", 1, 0.0), false));
+        assert!(!query.check(&process("btm foo!bar", 1, 0.0), false));
+    }
+
+    #[test]
+    fn test_not_errors() {
+        assert!(parse("not").is_err());
+        assert!(parse("btm and !").is_err());
+        assert!(parse("not and btm").is_err());
+        assert!(parse("not or btm").is_err());
+        assert!(parse("(btm not)").is_err());
+        assert!(parse("not > 5").is_err());
+    }
+
+    #[test]
+    fn test_byte_units() {
+        let proc = ProcessHarvest {
+            mem_usage_bytes: 2048,
+            ..Default::default()
+        };
+
+        assert!(parse("memb > 1 kib").unwrap().check(&proc, false));
+        assert!(parse("memb > 1kib").unwrap().check(&proc, false));
+        assert!(!parse("memb > 2.1 kb").unwrap().check(&proc, false));
+        assert!(parse("memb = 2048 b").unwrap().check(&proc, false));
+        assert!(parse("memb > 1days").is_err());
+    }
+
+    #[test]
+    fn test_ppid() {
+        let proc = ProcessHarvest {
+            pid: 20,
+            parent_pid: Some(1),
+            ..Default::default()
+        };
+
+        assert!(parse_query("ppid = 1", true, true, false)
+            .unwrap()
+            .check(&proc, false));
+        assert!(!parse_query("ppid 20", true, true, false)
+            .unwrap()
+            .check(&proc, false));
+        assert!(!parse("ppid = 1")
+            .unwrap()
+            .check(&ProcessHarvest::default(), false));
+    }
+
+    #[test]
+    fn test_age() {
+        let proc = ProcessHarvest {
+            time: Duration::from_secs(15 * 60),
+            ..Default::default()
+        };
+
+        assert!(parse("age > 10m").unwrap().check(&proc, false));
+        assert!(parse("age > 10 m").unwrap().check(&proc, false));
+        assert!(parse("age >= 900").unwrap().check(&proc, false));
+        assert!(parse("age < 1h").unwrap().check(&proc, false));
+        assert!(!parse("age > 1.5d").unwrap().check(&proc, false));
+
+        assert!(parse("age > 10mb").is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_cpu_time() {
+        let proc = ProcessHarvest {
+            cpu_time: Some(Duration::from_secs(90)),
+            ..Default::default()
+        };
+
+        assert!(parse("cputime > 1min").unwrap().check(&proc, false));
+        assert!(parse("cputime < 90001ms").unwrap().check(&proc, false));
+        assert!(!parse("cputime > 2m").unwrap().check(&proc, false));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_threads_and_nice() {
+        let proc = ProcessHarvest {
+            num_threads: Some(12),
+            nice: Some(-5),
+            ..Default::default()
+        };
+
+        assert!(parse("threads > 10").unwrap().check(&proc, false));
+        assert!(!parse("threads < 10").unwrap().check(&proc, false));
+        assert!(parse("nice < 0").unwrap().check(&proc, false));
+        assert!(parse("nice = -5").unwrap().check(&proc, false));
+        assert!(parse("not nice >= 0").unwrap().check(&proc, false));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_missing_values_never_match() {
+        let proc = ProcessHarvest::default();
+
+        assert!(!parse("nice = 0").unwrap().check(&proc, false));
+        assert!(!parse("threads < 5").unwrap().check(&proc, false));
+        assert!(!parse("cputime >= 0").unwrap().check(&proc, false));
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    #[test]
+    fn test_unsupported_prefixes() {
+        assert!(parse("nice = 0").is_err());
+        assert!(parse("threads < 5").is_err());
+        assert!(parse("cputime >= 0").is_err());
+    }
+
+    #[test]
+    fn test_tokenize_ranges() {
+        let query = "cpu>5 and  \"web content\"";
+        let tokens = tokenize(query);
+
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|(token, _)| token.as_str())
+                .collect::<Vec<_>>(),
+            vec!["cpu", ">", "5", "and", "\"", "web", "content", "\""]
+        );
+        for (token, range) in tokens {
+            assert_eq!(token, &query[range]);
+        }
+    }
+
+    #[test]
+    fn test_error_ranges() {
+        fn error_text(query: &str) -> &str {
+            &query[parse(query).unwrap_err().range.unwrap()]
+        }
+
+        assert_eq!(error_text("btm or (firefox"), "(");
+        assert_eq!(error_text("btm or firefox)"), ")");
+        assert_eq!(error_text("\"btm"), "\"");
+        assert_eq!(error_text("btm and cpu >"), ">");
+        assert_eq!(error_text("btm and cpu"), "cpu");
+        assert_eq!(error_text("age > 10mb"), "10mb");
+        assert_eq!(error_text("not and btm"), "and");
+        assert_eq!(error_text("btm and !"), "!");
+        assert_eq!(error_text("btm > 5"), ">");
+    }
+
+    #[test]
+    fn test_highlight_query() {
+        use QueryTokenKind::*;
+
+        let query = "btm or (cpu > 5 kb and !user = root) \"memb\" memb < 1 gib";
+        let highlights = highlight_query(query)
+            .into_iter()
+            .map(|(range, kind)| (&query[range], kind))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            highlights,
+            vec![
+                ("btm", Name),
+                ("or", Operator),
+                ("(", Operator),
+                ("cpu", Prefix),
+                (">", Operator),
+                ("5", Value),
+                ("kb", Name),
+                ("and", Operator),
+                ("!", Operator),
+                ("user", Prefix),
+                ("=", Operator),
+                ("root", Value),
+                (")", Operator),
+                ("\"", Operator),
+                ("memb", Name),
+                ("\"", Operator),
+                ("memb", Prefix),
+                ("<", Operator),
+                ("1", Value),
+                ("gib", Value),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_completion_target() {
+        fn target(query: &str) -> Option<(&str, CompletionKind)> {
+            completion_target(query, query.len()).map(|(range, kind)| (&query[range], kind))
+        }
+
+        assert_eq!(target("fire"), Some(("fire", CompletionKind::PrefixOrName)));
+        assert_eq!(
+            target("btm or thr"),
+            Some(("thr", CompletionKind::PrefixOrName))
+        );
+        assert_eq!(target("user ro"), Some(("ro", CompletionKind::User)));
+        assert_eq!(target("user = "), Some(("", CompletionKind::User)));
+        assert_eq!(target("\"web c"), Some(("c", CompletionKind::QuotedName)));
+        assert_eq!(target("cpu > 5"), None);
+        assert_eq!(target("btm "), None);
+        assert_eq!(target(""), None);
+
+        // The cursor is in the middle of a word.
+        assert_eq!(completion_target("firefox", 4), None);
+    }
+
+    #[test]
+    fn test_completion_candidates() {
+        let usernames = ["root", "rob", "root", "alice"];
+        let process_names = ["firefox", "Web Content", "fish", "cpuburn"];
+
+        assert_eq!(
+            completion_candidates("ro", CompletionKind::User, usernames, process_names),
+            vec!["rob", "root"]
+        );
+        assert_eq!(
+            completion_candidates("F", CompletionKind::PrefixOrName, usernames, process_names),
+            vec!["firefox", "fish"]
+        );
+        assert_eq!(
+            completion_candidates(
+                "cpu",
+                CompletionKind::PrefixOrName,
+                usernames,
+                process_names
+            ),
+            vec!["cpu", "cputime", "cpuburn"]
+        );
+        assert_eq!(
+            completion_candidates(
+                "web",
+                CompletionKind::PrefixOrName,
+                usernames,
+                process_names
+            ),
+            vec!["\"Web Content\""]
+        );
+        assert_eq!(
+            completion_candidates("web", CompletionKind::QuotedName, usernames, process_names),
+            vec!["Web Content"]
+        );
+    }
+}
@@ -0,0 +1,186 @@
+//! History of process search queries, which can be recalled from the search bar.
+
+use std::{fs, path::PathBuf};
+
+/// The most queries that are kept in the history.
+pub const MAX_SEARCH_HISTORY_LENGTH: usize = 100;
+
+/// The [`SearchHistory`] holds past process search queries, oldest first.  If it has a path, then
+/// it is loaded from and saved to that file, one query per line, so it persists between sessions.
+#[derive(Debug, Default)]
+pub struct SearchHistory {
+    entries: Vec<String>,
+    path: Option<PathBuf>,
+
+    /// Whether there are entries that haven't been saved yet.
+    is_dirty: bool,
+}
+
+impl SearchHistory {
+    /// Creates a [`SearchHistory`] backed by the file at the given path, reading any existing
+    /// entries from it.  A missing or unreadable file just means an empty history.
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let mut entries: Vec<String> = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|contents| {
+                contents
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        if entries.len() > MAX_SEARCH_HISTORY_LENGTH {
+            entries.drain(..entries.len() - MAX_SEARCH_HISTORY_LENGTH);
+        }
+
+        SearchHistory {
+            entries,
+            path,
+            is_dirty: false,
+        }
+    }
+
+    pub fn entries(&self) -> &[String] {
+        &self.entries
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.entries.get(index).map(String::as_str)
+    }
+
+    /// Adds a query as the newest entry, moving it to the end if it was already in the history.
+    /// Blank queries are ignored.  The history isn't written out until [`SearchHistory::save`].
+    pub fn push(&mut self, query: &str) {
+        let query = query.trim();
+        if query.is_empty() || self.entries.last().map(String::as_str) == Some(query) {
+            return;
+        }
+
+        self.entries.retain(|entry| entry != query);
+        self.entries.push(query.to_string());
+        if self.entries.len() > MAX_SEARCH_HISTORY_LENGTH {
+            self.entries.remove(0);
+        }
+
+        self.is_dirty = true;
+    }
+
+    /// Writes the history to its file if it has a path and has changed since it was last saved.
+    /// Failing to save the history isn't worth interrupting anything over, so errors are only logged.
+    pub fn save(&mut self) {
+        if !self.is_dirty {
+            return;
+        }
+
+        if let Some(path) = &self.path {
+            match fs::write(path, self.entries.join("\n") + "\n") {
+                Ok(()) => self.is_dirty = false,
+                Err(_err) => {
+                    #[cfg(feature = "log")]
+                    error!("Failed to save the search history to {:?}: {}", path, _err);
+                }
+            }
+        }
+    }
+
+    /// Returns the index of the entry older than the one at `index`, where `None` means starting
+    /// from after the newest entry.  Returns `None` if there is no older entry.
+    pub fn older(&self, index: Option<usize>) -> Option<usize> {
+        match index.unwrap_or(self.entries.len()) {
+            0 => None,
+            index => Some(index - 1),
+        }
+    }
+
+    /// Returns the index of the entry newer than the one at `index`, or `None` if it is the newest.
+    pub fn newer(&self, index: usize) -> Option<usize> {
+        if index + 1 < self.entries.len() {
+            Some(index + 1)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the index of the newest entry older than the one at `before` (or any entry if
+    /// `None`) that contains the pattern, ignoring case.
+    pub fn reverse_search(&self, pattern: &str, before: Option<usize>) -> Option<usize> {
+        let pattern = pattern.to_lowercase();
+        let end = before.unwrap_or(self.entries.len()).min(self.entries.len());
+
+        self.entries[..end]
+            .iter()
+            .rposition(|entry| entry.to_lowercase().contains(&pattern))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(entries: &[&str]) -> SearchHistory {
+        let mut history = SearchHistory::default();
+        for entry in entries {
+            history.push(entry);
+        }
+        history
+    }
+
+    #[test]
+    fn test_push() {
+        let mut history = history(&["btm", "cpu > 5", "  ", "btm"]);
+        assert_eq!(history.entries(), ["cpu > 5", "btm"]);
+
+        history.push("btm ");
+        assert_eq!(history.entries(), ["cpu > 5", "btm"]);
+
+        for i in 0..MAX_SEARCH_HISTORY_LENGTH {
+            history.push(&format!("pid = {}", i));
+        }
+        assert_eq!(history.entries().len(), MAX_SEARCH_HISTORY_LENGTH);
+        assert_eq!(history.get(0), Some("pid = 0"));
+    }
+
+    #[test]
+    fn test_navigation() {
+        let history = history(&["btm", "firefox", "cpu > 5"]);
+
+        assert_eq!(history.older(None), Some(2));
+        assert_eq!(history.older(Some(2)), Some(1));
+        assert_eq!(history.older(Some(0)), None);
+        assert_eq!(history.newer(0), Some(1));
+        assert_eq!(history.newer(2), None);
+
+        assert_eq!(SearchHistory::default().older(None), None);
+    }
+
+    #[test]
+    fn test_reverse_search() {
+        let history = history(&["btm", "firefox", "user = root", "BTM and cpu > 5"]);
+
+        assert_eq!(history.reverse_search("btm", None), Some(3));
+        assert_eq!(history.reverse_search("btm", Some(3)), Some(0));
+        assert_eq!(history.reverse_search("btm", Some(0)), None);
+        assert_eq!(history.reverse_search("fire", None), Some(1));
+        assert_eq!(history.reverse_search("zsh", None), None);
+    }
+
+    #[test]
+    fn test_persistence() {
+        let dir = crate::utils::test_utils::TempDir::new("search_history");
+        let path = dir.path().join("search_history");
+
+        let mut history = SearchHistory::load(Some(path.clone()));
+        assert!(history.entries().is_empty());
+        history.push("btm");
+        history.push("cpu > 5");
+        assert!(SearchHistory::load(Some(path.clone())).entries().is_empty());
+        history.save();
+
+        let history = SearchHistory::load(Some(path));
+        assert_eq!(history.entries(), ["btm", "cpu > 5"]);
+    }
+}
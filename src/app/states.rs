@@ -1,4 +1,4 @@
-use std::{collections::HashMap, time::Instant};
+use std::{collections::HashMap, ops::Range, time::Instant};
 
 use unicode_segmentation::GraphemeCursor;
 
@@ -8,8 +8,9 @@ use crate::{
 };
 
 use super::widgets::{
-    BatteryWidgetState, CpuWidgetState, DiskTableWidget, MemWidgetState, NetWidgetState,
-    ProcWidget, TempWidgetState,
+    BatteryWidgetState, CpuHeatmapWidgetState, CpuWidgetState, DiskIoWidgetState, DiskTableWidget,
+    KernelWidgetState, MemWidgetState, NetWidgetState, PressureWidgetState, ProcWidget,
+    TempWidgetState,
 };
 
 #[derive(Debug)]
@@ -92,6 +93,8 @@ pub struct AppSearchState {
     /// The query
     pub query: Option<Query>,
     pub error_message: Option<String>,
+    /// The byte range of the part of the query that caused the error, if known.
+    pub error_range: Option<Range<usize>>,
 }
 
 impl Default for AppSearchState {
@@ -107,6 +110,7 @@ impl Default for AppSearchState {
             char_cursor_position: 0,
             query: None,
             error_message: None,
+            error_range: None,
         }
     }
 }
@@ -187,6 +191,60 @@ impl CpuState {
     }
 }
 
+pub struct CpuHeatmapState {
+    pub widget_states: HashMap<u64, CpuHeatmapWidgetState>,
+}
+
+impl CpuHeatmapState {
+    pub fn init(widget_states: HashMap<u64, CpuHeatmapWidgetState>) -> Self {
+        CpuHeatmapState { widget_states }
+    }
+
+    pub fn get_mut_widget_state(&mut self, widget_id: u64) -> Option<&mut CpuHeatmapWidgetState> {
+        self.widget_states.get_mut(&widget_id)
+    }
+
+    pub fn get_widget_state(&self, widget_id: u64) -> Option<&CpuHeatmapWidgetState> {
+        self.widget_states.get(&widget_id)
+    }
+}
+
+pub struct PressureState {
+    pub widget_states: HashMap<u64, PressureWidgetState>,
+}
+
+impl PressureState {
+    pub fn init(widget_states: HashMap<u64, PressureWidgetState>) -> Self {
+        PressureState { widget_states }
+    }
+
+    pub fn get_mut_widget_state(&mut self, widget_id: u64) -> Option<&mut PressureWidgetState> {
+        self.widget_states.get_mut(&widget_id)
+    }
+
+    pub fn get_widget_state(&self, widget_id: u64) -> Option<&PressureWidgetState> {
+        self.widget_states.get(&widget_id)
+    }
+}
+
+pub struct KernelState {
+    pub widget_states: HashMap<u64, KernelWidgetState>,
+}
+
+impl KernelState {
+    pub fn init(widget_states: HashMap<u64, KernelWidgetState>) -> Self {
+        KernelState { widget_states }
+    }
+
+    pub fn get_mut_widget_state(&mut self, widget_id: u64) -> Option<&mut KernelWidgetState> {
+        self.widget_states.get_mut(&widget_id)
+    }
+
+    pub fn get_widget_state(&self, widget_id: u64) -> Option<&KernelWidgetState> {
+        self.widget_states.get(&widget_id)
+    }
+}
+
 pub struct MemState {
     pub force_update: Option<u64>,
     pub widget_states: HashMap<u64, MemWidgetState>,
@@ -244,6 +302,25 @@ impl DiskState {
         self.widget_states.get(&widget_id)
     }
 }
+
+pub struct DiskIoState {
+    pub widget_states: HashMap<u64, DiskIoWidgetState>,
+}
+
+impl DiskIoState {
+    pub fn init(widget_states: HashMap<u64, DiskIoWidgetState>) -> Self {
+        DiskIoState { widget_states }
+    }
+
+    pub fn get_mut_widget_state(&mut self, widget_id: u64) -> Option<&mut DiskIoWidgetState> {
+        self.widget_states.get_mut(&widget_id)
+    }
+
+    pub fn get_widget_state(&self, widget_id: u64) -> Option<&DiskIoWidgetState> {
+        self.widget_states.get(&widget_id)
+    }
+}
+
 pub struct BasicTableWidgetState {
     // Since this is intended (currently) to only be used for ONE widget, that's
     // how it's going to be written.  If we want to allow for multiple of these,
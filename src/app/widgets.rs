@@ -9,14 +9,26 @@ pub use temperature_table::*;
 pub mod disk_table;
 pub use disk_table::*;
 
+pub mod disk_io_graph;
+pub use disk_io_graph::*;
+
 pub mod cpu_graph;
 pub use cpu_graph::*;
 
+pub mod cpu_heatmap;
+pub use cpu_heatmap::*;
+
 pub mod net_graph;
 pub use net_graph::*;
 
 pub mod mem_graph;
 pub use mem_graph::*;
 
+pub mod pressure_graph;
+pub use pressure_graph::*;
+
+pub mod kernel_activity;
+pub use kernel_activity::*;
+
 pub mod battery_widget;
 pub use battery_widget::*;
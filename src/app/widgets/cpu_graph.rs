@@ -1,17 +1,20 @@
-use std::{borrow::Cow, time::Instant};
+use std::{borrow::Cow, collections::HashSet, time::Instant};
 
 use concat_string::concat_string;
 
 use tui::{style::Style, text::Text, widgets::Row};
 
 use crate::{
-    app::{data_harvester::cpu::CpuDataType, AppConfigFields},
+    app::{
+        data_harvester::cpu::{CpuDataType, CpuGroup},
+        AppConfigFields,
+    },
     canvas::{canvas_colours::CanvasColours, Painter},
     components::data_table::{
         Column, ColumnHeader, DataTable, DataTableColumn, DataTableProps, DataTableStyling,
         DataToCell,
     },
-    data_conversion::CpuWidgetData,
+    data_conversion::{frequency_string, CpuWidgetData},
     utils::gen_util::truncate_text,
 };
 
@@ -40,6 +43,7 @@ impl CpuWidgetStyling {
 
 pub enum CpuWidgetColumn {
     CPU,
+    Freq,
     Use,
 }
 
@@ -47,6 +51,7 @@ impl ColumnHeader for CpuWidgetColumn {
     fn text(&self) -> Cow<'static, str> {
         match self {
             CpuWidgetColumn::CPU => "CPU".into(),
+            CpuWidgetColumn::Freq => "Freq".into(),
             CpuWidgetColumn::Use => "Use%".into(),
         }
     }
@@ -55,6 +60,7 @@ impl ColumnHeader for CpuWidgetColumn {
 impl DataToCell<CpuWidgetColumn> for CpuWidgetData {
     fn to_cell<'a>(&'a self, column: &CpuWidgetColumn, calculated_width: u16) -> Option<Text<'a>> {
         const CPU_HIDE_BREAKPOINT: u16 = 5;
+        const FREQ_SHORTEN_BREAKPOINT: u16 = 6;
 
         // This is a bit of a hack, but apparently we can avoid having to do any fancy checks
         // of showing the "All" on a specific column if the other is hidden by just always
@@ -66,12 +72,14 @@ impl DataToCell<CpuWidgetColumn> for CpuWidgetData {
         match &self {
             CpuWidgetData::All => match column {
                 CpuWidgetColumn::CPU => Some(truncate_text("All", calculated_width)),
-                CpuWidgetColumn::Use => None,
+                CpuWidgetColumn::Freq | CpuWidgetColumn::Use => None,
             },
             CpuWidgetData::Entry {
                 data_type,
                 data: _,
                 last_entry,
+                frequency,
+                collapsed,
             } => match column {
                 CpuWidgetColumn::CPU => {
                     if calculated_width == 0 {
@@ -79,6 +87,24 @@ impl DataToCell<CpuWidgetColumn> for CpuWidgetData {
                     } else {
                         match data_type {
                             CpuDataType::Avg => Some(truncate_text("AVG", calculated_width)),
+                            CpuDataType::Group(group) => {
+                                // Like process trees, "+" marks a collapsed group and "-" an
+                                // expanded one.
+                                let prefix = if *collapsed { "+ " } else { "- " };
+                                let text = if calculated_width < CPU_HIDE_BREAKPOINT + 2 {
+                                    truncate_text(
+                                        &concat_string!(prefix, group.short_name()),
+                                        calculated_width,
+                                    )
+                                } else {
+                                    truncate_text(
+                                        &concat_string!(prefix, group.name()),
+                                        calculated_width,
+                                    )
+                                };
+
+                                Some(text)
+                            }
                             CpuDataType::Cpu(index) => {
                                 let index_str = index.to_string();
                                 let text = if calculated_width < CPU_HIDE_BREAKPOINT {
@@ -95,6 +121,16 @@ impl DataToCell<CpuWidgetColumn> for CpuWidgetData {
                         }
                     }
                 }
+                CpuWidgetColumn::Freq => frequency.map(|frequency| {
+                    // Drop down to something like "3.4G" if there isn't room for "3.40GHz".
+                    let text = if calculated_width < FREQ_SHORTEN_BREAKPOINT {
+                        format!("{:.1}G", frequency.current as f64 / 1000.0)
+                    } else {
+                        frequency_string(frequency.current)
+                    };
+
+                    truncate_text(&text, calculated_width)
+                }),
                 CpuWidgetColumn::Use => Some(truncate_text(
                     &format!("{:.0}%", last_entry.round()),
                     calculated_width,
@@ -111,8 +147,10 @@ impl DataToCell<CpuWidgetColumn> for CpuWidgetData {
                 data_type,
                 data: _,
                 last_entry: _,
+                frequency: _,
+                collapsed: _,
             } => match data_type {
-                CpuDataType::Avg => painter.colours.avg_colour_style,
+                CpuDataType::Avg | CpuDataType::Group(_) => painter.colours.avg_colour_style,
                 CpuDataType::Cpu(index) => {
                     painter.colours.cpu_colour_styles
                         [index % painter.colours.cpu_colour_styles.len()]
@@ -129,7 +167,33 @@ impl DataToCell<CpuWidgetColumn> for CpuWidgetData {
     where
         Self: Sized,
     {
-        vec![1, 3]
+        vec![1, 7, 3]
+    }
+}
+
+/// What the CPU graph plots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuGraphMode {
+    /// The usage of each entry.
+    Usage,
+    /// How the selected entry's time is split between states.
+    States,
+    /// The clock frequency of each entry.
+    Frequency,
+    /// The 1, 5, and 15 minute load averages.
+    LoadAverage,
+}
+
+impl CpuGraphMode {
+    /// Returns the mode that follows this one when cycling through them.
+    pub fn next(self) -> Self {
+        match self {
+            CpuGraphMode::Usage => CpuGraphMode::States,
+            CpuGraphMode::States => CpuGraphMode::Frequency,
+            // Load averages are only collected on Unix-like systems.
+            CpuGraphMode::Frequency if cfg!(target_family = "unix") => CpuGraphMode::LoadAverage,
+            CpuGraphMode::Frequency | CpuGraphMode::LoadAverage => CpuGraphMode::Usage,
+        }
     }
 }
 
@@ -137,6 +201,8 @@ pub struct CpuWidgetState {
     pub current_display_time: u64,
     pub is_legend_hidden: bool,
     pub show_avg: bool,
+    pub graph_mode: CpuGraphMode,
+    pub collapsed_groups: HashSet<CpuGroup>,
     pub autohide_timer: Option<Instant>,
     pub table: DataTable<CpuWidgetData, CpuWidgetColumn>,
     pub styling: CpuWidgetStyling,
@@ -147,9 +213,10 @@ impl CpuWidgetState {
         config: &AppConfigFields, current_display_time: u64, autohide_timer: Option<Instant>,
         colours: &CanvasColours,
     ) -> Self {
-        const COLUMNS: [Column<CpuWidgetColumn>; 2] = [
-            Column::soft(CpuWidgetColumn::CPU, Some(0.5)),
-            Column::soft(CpuWidgetColumn::Use, Some(0.5)),
+        const COLUMNS: [Column<CpuWidgetColumn>; 3] = [
+            Column::soft(CpuWidgetColumn::CPU, Some(0.4)),
+            Column::soft(CpuWidgetColumn::Freq, Some(0.3)),
+            Column::soft(CpuWidgetColumn::Use, Some(0.3)),
         ];
 
         let props = DataTableProps {
@@ -167,9 +234,90 @@ impl CpuWidgetState {
             current_display_time,
             is_legend_hidden: false,
             show_avg: config.show_average_cpu,
+            graph_mode: CpuGraphMode::Usage,
+            collapsed_groups: HashSet::new(),
             autohide_timer,
             table: DataTable::new(COLUMNS, props, styling),
             styling: CpuWidgetStyling::from_colours(colours),
         }
     }
+
+    /// Hides the frequency column if there is nothing to show in it, or if the legend is too narrow
+    /// to fit it alongside the CPU name and usage.  Returns whether this changed.
+    pub fn update_frequency_column(&mut self, legend_width: u16, has_frequency: bool) -> bool {
+        /// Enough for "CPU10", "3.4G", and "100%", along with the borders and gaps.
+        const FREQ_HIDE_BREAKPOINT: u16 = 18;
+
+        match self.table.columns.get_mut(1) {
+            Some(column) => {
+                let is_hidden = !has_frequency || legend_width < FREQ_HIDE_BREAKPOINT;
+                let changed = column.is_hidden() != is_hidden;
+                column.set_is_hidden(is_hidden);
+                changed
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the positions of the entries shown in the legend, skipping the cores of collapsed
+    /// groups.  A group's cores are the ones following it up until the next group.
+    pub fn visible_entries(&self, cpu_data: &[CpuWidgetData]) -> Vec<usize> {
+        let mut is_in_collapsed_group = false;
+
+        cpu_data
+            .iter()
+            .enumerate()
+            .filter_map(|(itx, cpu)| {
+                if let CpuWidgetData::Entry { data_type, .. } = cpu {
+                    match data_type {
+                        CpuDataType::Group(group) => {
+                            is_in_collapsed_group = self.collapsed_groups.contains(group);
+                        }
+                        CpuDataType::Cpu(_) if is_in_collapsed_group => return None,
+                        _ => {}
+                    }
+                }
+
+                Some(itx)
+            })
+            .collect()
+    }
+
+    /// Returns the entries to show in the legend given the positions from
+    /// [`CpuWidgetState::visible_entries`], marking which groups are collapsed.
+    pub fn legend_data(&self, cpu_data: &[CpuWidgetData], visible: &[usize]) -> Vec<CpuWidgetData> {
+        visible
+            .iter()
+            .filter_map(|itx| cpu_data.get(*itx))
+            .map(|cpu| {
+                let mut cpu = cpu.clone();
+                if let CpuWidgetData::Entry {
+                    data_type: CpuDataType::Group(group),
+                    collapsed,
+                    ..
+                } = &mut cpu
+                {
+                    *collapsed = self.collapsed_groups.contains(group);
+                }
+
+                cpu
+            })
+            .collect()
+    }
+
+    /// Collapses or expands the selected entry if it is a group.
+    pub fn toggle_current_group(&mut self, cpu_data: &[CpuWidgetData]) {
+        let visible = self.visible_entries(cpu_data);
+        if let Some(CpuWidgetData::Entry {
+            data_type: CpuDataType::Group(group),
+            ..
+        }) = visible
+            .get(self.table.state.current_index)
+            .and_then(|itx| cpu_data.get(*itx))
+        {
+            if !self.collapsed_groups.remove(group) {
+                self.collapsed_groups.insert(*group);
+            }
+        }
+    }
 }
@@ -0,0 +1,29 @@
+/// How the CPU heatmap lays out its cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuHeatmapMode {
+    /// One row per core, with time along the x-axis.
+    History,
+    /// A grid of cells showing only the current usage of each core.
+    Grid,
+}
+
+pub struct CpuHeatmapWidgetState {
+    pub current_display_time: u64,
+    pub mode: CpuHeatmapMode,
+}
+
+impl CpuHeatmapWidgetState {
+    pub fn init(current_display_time: u64) -> Self {
+        CpuHeatmapWidgetState {
+            current_display_time,
+            mode: CpuHeatmapMode::History,
+        }
+    }
+
+    pub fn toggle_mode(&mut self) {
+        self.mode = match self.mode {
+            CpuHeatmapMode::History => CpuHeatmapMode::Grid,
+            CpuHeatmapMode::Grid => CpuHeatmapMode::History,
+        };
+    }
+}
@@ -0,0 +1,71 @@
+use std::time::Instant;
+
+use crate::app::data_harvester::disks::IoRate;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiskIoGraphMode {
+    /// Bytes read and written per second.
+    Throughput,
+    /// Reads and writes completed per second.
+    Operations,
+    /// The average time that reads and writes took.
+    Latency,
+    /// The average number of requests queued, and the number in flight.
+    Queue,
+    /// The percentage of time that the device was busy.
+    Utilisation,
+}
+
+impl DiskIoGraphMode {
+    /// Returns the mode that follows this one when cycling through them.
+    pub fn next(self) -> Self {
+        match self {
+            // Everything but the throughput comes from `/proc/diskstats`, so is only collected on
+            // Linux.
+            DiskIoGraphMode::Throughput if cfg!(target_os = "linux") => DiskIoGraphMode::Operations,
+            DiskIoGraphMode::Operations => DiskIoGraphMode::Latency,
+            DiskIoGraphMode::Latency => DiskIoGraphMode::Queue,
+            DiskIoGraphMode::Queue => DiskIoGraphMode::Utilisation,
+            DiskIoGraphMode::Throughput | DiskIoGraphMode::Utilisation => {
+                DiskIoGraphMode::Throughput
+            }
+        }
+    }
+
+    /// Returns the values of the (up to) two series that this mode graphs.
+    pub fn values(self, rate: &IoRate) -> [Option<f64>; 2] {
+        let activity = rate.activity;
+        match self {
+            DiskIoGraphMode::Throughput => [Some(rate.read), Some(rate.write)],
+            DiskIoGraphMode::Operations => [
+                activity.map(|activity| activity.reads),
+                activity.map(|activity| activity.writes),
+            ],
+            DiskIoGraphMode::Latency => [
+                activity.map(|activity| activity.read_await.unwrap_or(0.0)),
+                activity.map(|activity| activity.write_await.unwrap_or(0.0)),
+            ],
+            DiskIoGraphMode::Queue => [
+                activity.map(|activity| activity.queue_depth),
+                activity.map(|activity| activity.in_flight as f64),
+            ],
+            DiskIoGraphMode::Utilisation => [activity.map(|activity| activity.utilisation), None],
+        }
+    }
+}
+
+pub struct DiskIoWidgetState {
+    pub current_display_time: u64,
+    pub autohide_timer: Option<Instant>,
+    pub graph_mode: DiskIoGraphMode,
+}
+
+impl DiskIoWidgetState {
+    pub fn init(current_display_time: u64, autohide_timer: Option<Instant>) -> Self {
+        DiskIoWidgetState {
+            current_display_time,
+            autohide_timer,
+            graph_mode: DiskIoGraphMode::Throughput,
+        }
+    }
+}
@@ -1,16 +1,16 @@
-use std::{borrow::Cow, cmp::max};
+use std::{borrow::Cow, cmp::max, time::Duration};
 
 use kstring::KString;
-use tui::text::Text;
+use tui::{text::Text, widgets::Row};
 
 use crate::{
-    app::AppConfigFields,
-    canvas::canvas_colours::CanvasColours,
+    app::{data_harvester::disks::DiskActivity, AppConfigFields},
+    canvas::{canvas_colours::CanvasColours, Painter},
     components::data_table::{
         Column, ColumnHeader, DataTable, DataTableColumn, DataTableProps, DataTableStyling,
         DataToCell,
     },
-    utils::gen_util::{get_decimal_bytes, truncate_text},
+    utils::gen_util::{get_decimal_bytes, get_decimal_prefix, truncate_text},
 };
 
 #[derive(Clone)]
@@ -22,9 +22,33 @@ pub struct DiskWidgetData {
     pub total_bytes: Option<u64>,
     pub io_read: KString,
     pub io_write: KString,
+    pub io_activity: Option<DiskActivity>,
+    pub fs_type: Option<KString>,
+    pub total_inodes: Option<u64>,
+    pub free_inodes: Option<u64>,
+    pub read_only: Option<bool>,
+    /// The branches that lead to this row in tree mode.
+    pub prefix: Option<KString>,
+    /// The name that device-mapper gave the device, which is shown instead of its name.
+    pub label: Option<KString>,
+    /// What kind of block device this is in tree mode, such as "part" or "lvm".
+    pub kind: Option<KString>,
+    /// How long until the disk is forecast to run out of free space, if it is filling up.
+    pub time_to_full: Option<Duration>,
+    /// Whether the disk is forecast to run out of free space soon enough to be highlighted.
+    pub is_filling_soon: bool,
 }
 
 impl DiskWidgetData {
+    /// Returns the name of the disk, with the branches that lead to it in tree mode.
+    pub fn prefixed_name(&self) -> KString {
+        let name = self.label.as_ref().unwrap_or(&self.name);
+        match &self.prefix {
+            Some(prefix) => format!("{}{}", prefix, name).into(),
+            None => name.clone(),
+        }
+    }
+
     pub fn free_space(&self) -> KString {
         if let Some(free_bytes) = self.free_bytes {
             let converted_free_space = get_decimal_bytes(free_bytes);
@@ -54,6 +78,110 @@ impl DiskWidgetData {
             "N/A".into()
         }
     }
+
+    pub fn eta_full(&self) -> KString {
+        let time_to_full = match self.time_to_full {
+            Some(time_to_full) => time_to_full.as_secs(),
+            None => return "-".into(),
+        };
+
+        let (days, hours, minutes) = (
+            time_to_full / 86400,
+            time_to_full / 3600 % 24,
+            time_to_full / 60 % 60,
+        );
+        if days > 0 {
+            format!("{}d{:02}h", days, hours).into()
+        } else if hours > 0 {
+            format!("{}h{:02}m", hours, minutes).into()
+        } else if minutes > 0 {
+            format!("{}m", minutes).into()
+        } else {
+            "<1m".into()
+        }
+    }
+
+    pub fn fs_type(&self) -> KString {
+        self.fs_type.clone().unwrap_or_else(|| "N/A".into())
+    }
+
+    pub fn used_inodes(&self) -> KString {
+        if let (Some(total_inodes), Some(free_inodes)) = (self.total_inodes, self.free_inodes) {
+            inode_count(total_inodes.saturating_sub(free_inodes))
+        } else {
+            "N/A".into()
+        }
+    }
+
+    pub fn free_inodes(&self) -> KString {
+        if let Some(free_inodes) = self.free_inodes {
+            inode_count(free_inodes)
+        } else {
+            "N/A".into()
+        }
+    }
+
+    pub fn inode_usage(&self) -> KString {
+        if let (Some(total_inodes), Some(free_inodes)) = (self.total_inodes, self.free_inodes) {
+            let used_inodes = total_inodes.saturating_sub(free_inodes);
+            format!("{:.0}%", used_inodes as f64 / total_inodes as f64 * 100_f64).into()
+        } else {
+            "N/A".into()
+        }
+    }
+
+    pub fn mode(&self) -> KString {
+        match self.read_only {
+            Some(true) => "ro".into(),
+            Some(false) => "rw".into(),
+            None => "N/A".into(),
+        }
+    }
+
+    pub fn iops(&self) -> KString {
+        if let Some(activity) = &self.io_activity {
+            format!("{:.0}", activity.iops()).into()
+        } else {
+            "N/A".into()
+        }
+    }
+
+    pub fn io_await(&self) -> KString {
+        match self.io_activity {
+            Some(DiskActivity {
+                io_await: Some(io_await),
+                ..
+            }) => format!("{:.1}ms", io_await).into(),
+            Some(_) => "0.0ms".into(),
+            None => "N/A".into(),
+        }
+    }
+
+    pub fn queue_depth(&self) -> KString {
+        if let Some(activity) = &self.io_activity {
+            format!("{:.2}", activity.queue_depth).into()
+        } else {
+            "N/A".into()
+        }
+    }
+
+    pub fn utilisation(&self) -> KString {
+        if let Some(activity) = &self.io_activity {
+            format!("{:.0}%", activity.utilisation).into()
+        } else {
+            "N/A".into()
+        }
+    }
+}
+
+/// Returns a short string for a number of inodes, such as "1.2M".
+fn inode_count(count: u64) -> KString {
+    let (value, unit) = get_decimal_prefix(count, "");
+    if unit.is_empty() {
+        format!("{}", count).into()
+    } else {
+        format!("{:.1}{}", value, unit).into()
+    }
 }
 
 pub enum DiskWidgetColumn {
@@ -62,8 +190,19 @@ pub enum DiskWidgetColumn {
     Used,
     Free,
     Total,
+    EtaFull,
     IoRead,
     IoWrite,
+    Iops,
+    Await,
+    QueueDepth,
+    Utilisation,
+    FsType,
+    InodesUsed,
+    InodesFree,
+    InodeUsage,
+    Mode,
+    Kind,
 }
 
 impl ColumnHeader for DiskWidgetColumn {
@@ -74,8 +213,19 @@ impl ColumnHeader for DiskWidgetColumn {
             DiskWidgetColumn::Used => "Used",
             DiskWidgetColumn::Free => "Free",
             DiskWidgetColumn::Total => "Total",
+            DiskWidgetColumn::EtaFull => "ETA Full",
             DiskWidgetColumn::IoRead => "R/s",
             DiskWidgetColumn::IoWrite => "W/s",
+            DiskWidgetColumn::Iops => "IOPS",
+            DiskWidgetColumn::Await => "Await",
+            DiskWidgetColumn::QueueDepth => "Queue",
+            DiskWidgetColumn::Utilisation => "Util",
+            DiskWidgetColumn::FsType => "Type",
+            DiskWidgetColumn::InodesUsed => "IUsed",
+            DiskWidgetColumn::InodesFree => "IFree",
+            DiskWidgetColumn::InodeUsage => "IUse%",
+            DiskWidgetColumn::Mode => "Mode",
+            DiskWidgetColumn::Kind => "Kind",
         }
         .into()
     }
@@ -84,29 +234,55 @@ impl ColumnHeader for DiskWidgetColumn {
 impl DataToCell<DiskWidgetColumn> for DiskWidgetData {
     fn to_cell<'a>(&'a self, column: &DiskWidgetColumn, calculated_width: u16) -> Option<Text<'a>> {
         let text = match column {
-            DiskWidgetColumn::Disk => truncate_text(&self.name, calculated_width),
+            DiskWidgetColumn::Disk => truncate_text(&self.prefixed_name(), calculated_width),
             DiskWidgetColumn::Mount => truncate_text(&self.mount_point, calculated_width),
             DiskWidgetColumn::Used => truncate_text(&self.usage(), calculated_width),
             DiskWidgetColumn::Free => truncate_text(&self.free_space(), calculated_width),
             DiskWidgetColumn::Total => truncate_text(&self.total_space(), calculated_width),
+            DiskWidgetColumn::EtaFull => truncate_text(&self.eta_full(), calculated_width),
             DiskWidgetColumn::IoRead => truncate_text(&self.io_read, calculated_width),
             DiskWidgetColumn::IoWrite => truncate_text(&self.io_write, calculated_width),
+            DiskWidgetColumn::Iops => truncate_text(&self.iops(), calculated_width),
+            DiskWidgetColumn::Await => truncate_text(&self.io_await(), calculated_width),
+            DiskWidgetColumn::QueueDepth => truncate_text(&self.queue_depth(), calculated_width),
+            DiskWidgetColumn::Utilisation => truncate_text(&self.utilisation(), calculated_width),
+            DiskWidgetColumn::FsType => truncate_text(&self.fs_type(), calculated_width),
+            DiskWidgetColumn::InodesUsed => truncate_text(&self.used_inodes(), calculated_width),
+            DiskWidgetColumn::InodesFree => truncate_text(&self.free_inodes(), calculated_width),
+            DiskWidgetColumn::InodeUsage => truncate_text(&self.inode_usage(), calculated_width),
+            DiskWidgetColumn::Mode => truncate_text(&self.mode(), calculated_width),
+            DiskWidgetColumn::Kind => {
+                truncate_text(self.kind.as_deref().unwrap_or_default(), calculated_width)
+            }
         };
 
         Some(text)
     }
 
+    #[inline(always)]
+    fn style_row<'a>(&self, row: Row<'a>, painter: &Painter) -> Row<'a> {
+        if self.is_filling_soon {
+            row.style(painter.colours.disk_warning_style)
+        } else {
+            row
+        }
+    }
+
     fn column_widths<C: DataTableColumn<DiskWidgetColumn>>(
         data: &[Self], _columns: &[C],
     ) -> Vec<u16>
     where
         Self: Sized,
     {
-        let mut widths = vec![0; 7];
+        let mut widths = vec![0; 18];
 
         data.iter().for_each(|row| {
-            widths[0] = max(widths[0], row.name.len() as u16);
+            widths[0] = max(widths[0], row.prefixed_name().chars().count() as u16);
             widths[1] = max(widths[1], row.mount_point.len() as u16);
+            widths[12] = max(widths[12], row.fs_type().len() as u16);
+            if let Some(kind) = &row.kind {
+                widths[17] = max(widths[17], kind.len() as u16);
+            }
         });
 
         widths
@@ -115,18 +291,42 @@ impl DataToCell<DiskWidgetColumn> for DiskWidgetData {
 
 pub struct DiskTableWidget {
     pub table: DataTable<DiskWidgetData, DiskWidgetColumn>,
+    pub show_extended_stats: bool,
+    pub show_fs_details: bool,
+    pub is_tree_mode: bool,
 }
 
 impl DiskTableWidget {
+    /// The columns for the operation counts and timings, which are hidden by default.
+    const EXTENDED_STATS_COLUMNS: std::ops::Range<usize> = 8..12;
+
+    /// The columns for the filesystem type, inode usage, and access mode, which are hidden by
+    /// default.
+    const FS_DETAILS_COLUMNS: std::ops::Range<usize> = 12..17;
+
+    /// The column for the kind of each block device, which is only shown in tree mode.
+    const KIND_COLUMN: usize = 17;
+
     pub fn new(config: &AppConfigFields, colours: &CanvasColours) -> Self {
-        const COLUMNS: [Column<DiskWidgetColumn>; 7] = [
+        const COLUMNS: [Column<DiskWidgetColumn>; 18] = [
             Column::soft(DiskWidgetColumn::Disk, Some(0.2)),
             Column::soft(DiskWidgetColumn::Mount, Some(0.2)),
             Column::hard(DiskWidgetColumn::Used, 4),
             Column::hard(DiskWidgetColumn::Free, 6),
             Column::hard(DiskWidgetColumn::Total, 6),
+            Column::hard(DiskWidgetColumn::EtaFull, 8),
             Column::hard(DiskWidgetColumn::IoRead, 7),
             Column::hard(DiskWidgetColumn::IoWrite, 7),
+            Column::hard(DiskWidgetColumn::Iops, 6),
+            Column::hard(DiskWidgetColumn::Await, 8),
+            Column::hard(DiskWidgetColumn::QueueDepth, 6),
+            Column::hard(DiskWidgetColumn::Utilisation, 5),
+            Column::soft(DiskWidgetColumn::FsType, Some(0.1)),
+            Column::hard(DiskWidgetColumn::InodesUsed, 6),
+            Column::hard(DiskWidgetColumn::InodesFree, 6),
+            Column::hard(DiskWidgetColumn::InodeUsage, 5),
+            Column::hard(DiskWidgetColumn::Mode, 4),
+            Column::soft(DiskWidgetColumn::Kind, Some(0.15)),
         ];
 
         let props = DataTableProps {
@@ -140,8 +340,51 @@ impl DiskTableWidget {
 
         let styling = DataTableStyling::from_colours(colours);
 
-        Self {
+        let mut disk_table = Self {
             table: DataTable::new(COLUMNS, props, styling),
+            show_extended_stats: false,
+            show_fs_details: false,
+            is_tree_mode: false,
+        };
+        disk_table.set_extended_stats(false);
+        disk_table.set_fs_details(false);
+        disk_table.set_tree_mode(false);
+
+        disk_table
+    }
+
+    /// Shows or hides the IOPS, await, queue depth, and utilisation columns.
+    pub fn toggle_extended_stats(&mut self) {
+        self.set_extended_stats(!self.show_extended_stats);
+    }
+
+    fn set_extended_stats(&mut self, show_extended_stats: bool) {
+        self.show_extended_stats = show_extended_stats;
+        for column in &mut self.table.columns[Self::EXTENDED_STATS_COLUMNS] {
+            column.set_is_hidden(!show_extended_stats);
         }
     }
+
+    /// Shows or hides the filesystem type, inode usage, and access mode columns.
+    pub fn toggle_fs_details(&mut self) {
+        self.set_fs_details(!self.show_fs_details);
+    }
+
+    fn set_fs_details(&mut self, show_fs_details: bool) {
+        self.show_fs_details = show_fs_details;
+        for column in &mut self.table.columns[Self::FS_DETAILS_COLUMNS] {
+            column.set_is_hidden(!show_fs_details);
+        }
+    }
+
+    /// Switches between listing each disk, and showing them in a tree of the block devices that
+    /// they are built on.
+    pub fn toggle_tree_mode(&mut self) {
+        self.set_tree_mode(!self.is_tree_mode);
+    }
+
+    fn set_tree_mode(&mut self, is_tree_mode: bool) {
+        self.is_tree_mode = is_tree_mode;
+        self.table.columns[Self::KIND_COLUMN].set_is_hidden(!is_tree_mode);
+    }
 }
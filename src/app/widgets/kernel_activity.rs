@@ -0,0 +1,11 @@
+pub struct KernelWidgetState {
+    pub current_display_time: u64,
+}
+
+impl KernelWidgetState {
+    pub fn init(current_display_time: u64) -> Self {
+        KernelWidgetState {
+            current_display_time,
+        }
+    }
+}
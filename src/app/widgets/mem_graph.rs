@@ -1,8 +1,40 @@
 use std::time::Instant;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemGraphMode {
+    /// The usage of RAM, swap, and anything else that's enabled.
+    Usage,
+    /// RAM usage broken down into used, buffer, cache, and slab memory.
+    Breakdown,
+    /// Swap-in, swap-out, and major fault rates.
+    Paging,
+    /// The ZFS ARC's hit ratios and the sizes of its MRU and MFU lists.
+    Arc,
+}
+
+impl MemGraphMode {
+    /// Returns the mode that follows this one when cycling through them.
+    pub fn next(self) -> Self {
+        // ARC details are only collected on Linux and FreeBSD, and only with ZFS support.
+        let has_arc = cfg!(all(
+            feature = "zfs",
+            any(target_os = "linux", target_os = "freebsd")
+        ));
+
+        match self {
+            // The breakdown and paging activity are only collected on Linux.
+            MemGraphMode::Usage if cfg!(target_os = "linux") => MemGraphMode::Breakdown,
+            MemGraphMode::Breakdown => MemGraphMode::Paging,
+            MemGraphMode::Usage | MemGraphMode::Paging if has_arc => MemGraphMode::Arc,
+            MemGraphMode::Usage | MemGraphMode::Paging | MemGraphMode::Arc => MemGraphMode::Usage,
+        }
+    }
+}
+
 pub struct MemWidgetState {
     pub current_display_time: u64,
     pub autohide_timer: Option<Instant>,
+    pub graph_mode: MemGraphMode,
 }
 
 impl MemWidgetState {
@@ -10,6 +42,7 @@ impl MemWidgetState {
         MemWidgetState {
             current_display_time,
             autohide_timer,
+            graph_mode: MemGraphMode::Usage,
         }
     }
 }
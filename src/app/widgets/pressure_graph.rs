@@ -0,0 +1,18 @@
+use std::time::Instant;
+
+pub struct PressureWidgetState {
+    pub current_display_time: u64,
+    pub autohide_timer: Option<Instant>,
+    /// Whether to show the time that every task was stalled, rather than at least one.
+    pub show_full: bool,
+}
+
+impl PressureWidgetState {
+    pub fn init(current_display_time: u64, autohide_timer: Option<Instant>) -> Self {
+        PressureWidgetState {
+            current_display_time,
+            autohide_timer,
+            show_full: false,
+        }
+    }
+}
@@ -1,11 +1,12 @@
-use std::{borrow::Cow, collections::hash_map::Entry};
+use std::{borrow::Cow, collections::hash_map::Entry, ops::Range};
 
 use crate::{
     app::{
         data_farmer::{DataCollection, ProcessData},
         data_harvester::processes::ProcessHarvest,
         query::*,
-        AppConfigFields, AppSearchState,
+        search_history::SearchHistory,
+        AppConfigFields, AppSearchState, CursorDirection,
     },
     canvas::canvas_colours::CanvasColours,
     components::data_table::{
@@ -15,8 +16,13 @@ use crate::{
     Pid,
 };
 
+#[cfg(target_family = "unix")]
+use crate::app::data_harvester::processes::UserTable;
+
 use fxhash::{FxHashMap, FxHashSet};
 use itertools::Itertools;
+use unicode_segmentation::GraphemeCursor;
+use unicode_width::UnicodeWidthStr;
 
 pub mod proc_widget_column;
 pub use proc_widget_column::*;
@@ -33,6 +39,14 @@ pub struct ProcessSearchState {
     pub is_ignoring_case: bool,
     pub is_searching_whole_word: bool,
     pub is_searching_with_regex: bool,
+    completion: Option<SearchCompletion>,
+
+    /// Which entry of the search history is being shown, if the history is being browsed.
+    history_index: Option<usize>,
+
+    /// The query that was typed before browsing the search history, restored after the newest
+    /// entry and used as the pattern for reverse searches.
+    history_draft: String,
 }
 
 impl Default for ProcessSearchState {
@@ -42,10 +56,31 @@ impl Default for ProcessSearchState {
             is_ignoring_case: true,
             is_searching_whole_word: false,
             is_searching_with_regex: false,
+            completion: None,
+            history_index: None,
+            history_draft: String::default(),
         }
     }
 }
 
+/// The state of an in-progress tab completion, so repeated presses can cycle through candidates.
+struct SearchCompletion {
+    /// Where the completed word starts in the query.
+    start: usize,
+    candidates: Vec<String>,
+    index: usize,
+
+    /// The query right after the last completion, to tell if it has been edited since.
+    completed_query: String,
+}
+
+impl SearchCompletion {
+    fn is_continued_by(&self, query: &str, cursor_position: usize) -> bool {
+        self.completed_query == query
+            && cursor_position == self.start + self.candidates[self.index].len()
+    }
+}
+
 impl ProcessSearchState {
     pub fn search_toggle_ignore_case(&mut self) {
         self.is_ignoring_case = !self.is_ignoring_case;
@@ -60,6 +95,16 @@ impl ProcessSearchState {
     }
 }
 
+/// A named query from the config file that can be applied to a [`ProcWidget`].
+#[derive(Clone, Debug)]
+pub struct SavedProcessFilter {
+    pub name: String,
+    pub query: String,
+
+    /// The column to sort by when this filter is applied, if any.
+    pub sort_index: Option<usize>,
+}
+
 #[derive(Clone, Debug)]
 pub enum ProcWidgetMode {
     Tree { collapsed_pids: FxHashSet<Pid> },
@@ -89,6 +134,12 @@ pub struct ProcWidget {
     /// A name-to-pid mapping.
     pub id_pid_map: StringPidMap,
 
+    /// The saved filters that can be cycled through.
+    pub saved_filters: Vec<SavedProcessFilter>,
+
+    /// The index of the saved filter currently applied, if any.
+    pub current_saved_filter: Option<usize>,
+
     pub is_sort_open: bool,
     pub force_rerender: bool,
     pub force_update_data: bool,
@@ -110,6 +161,27 @@ impl ProcWidget {
     #[cfg(not(target_family = "unix"))]
     pub const STATE: usize = 8;
 
+    const TITLE: &'static str = " Processes ";
+
+    /// Returns the index of the column corresponding to a name, using the same names as the search
+    /// prefixes (e.g. `cpu`, `mem`, `pid`).
+    pub fn column_index_from_name(name: &str) -> Option<usize> {
+        match name.to_lowercase().as_str() {
+            "pid" | "count" => Some(Self::PID_OR_COUNT),
+            "name" | "command" => Some(Self::PROC_NAME_OR_CMD),
+            "cpu" | "cpu%" => Some(Self::CPU),
+            "mem" | "mem%" | "memb" => Some(Self::MEM),
+            "read" | "r/s" => Some(Self::RPS),
+            "write" | "w/s" => Some(Self::WPS),
+            "tread" | "t.read" => Some(Self::T_READ),
+            "twrite" | "t.write" => Some(Self::T_WRITE),
+            #[cfg(target_family = "unix")]
+            "user" => Some(Self::USER),
+            "state" => Some(Self::STATE),
+            _ => None,
+        }
+    }
+
     fn new_sort_table(config: &AppConfigFields, colours: &CanvasColours) -> SortTable {
         const COLUMNS: [Column<SortTableColumn>; 1] = [Column::hard(SortTableColumn, 7)];
 
@@ -171,7 +243,7 @@ impl ProcWidget {
         };
 
         let inner_props = DataTableProps {
-            title: Some(" Processes ".into()),
+            title: Some(Self::TITLE.into()),
             table_gap: config.table_gap,
             left_to_right: true,
             is_basic: config.use_basic_mode,
@@ -230,6 +302,8 @@ impl ProcWidget {
             table_data: vec![],
             sort_table,
             id_pid_map,
+            saved_filters: vec![],
+            current_saved_filter: None,
             is_sort_open: false,
             mode,
             force_rerender: true,
@@ -742,6 +816,7 @@ impl ProcWidget {
             self.proc_search.search_state.is_blank_search = true;
             self.proc_search.search_state.is_invalid_search = false;
             self.proc_search.search_state.error_message = None;
+            self.proc_search.search_state.error_range = None;
         } else {
             match parse_query(
                 &self.proc_search.search_state.current_search_query,
@@ -754,25 +829,225 @@ impl ProcWidget {
                     self.proc_search.search_state.is_blank_search = false;
                     self.proc_search.search_state.is_invalid_search = false;
                     self.proc_search.search_state.error_message = None;
+                    self.proc_search.search_state.error_range = None;
                 }
                 Err(err) => {
                     self.proc_search.search_state.is_blank_search = false;
                     self.proc_search.search_state.is_invalid_search = true;
                     self.proc_search.search_state.error_message = Some(err.to_string());
+                    self.proc_search.search_state.error_range = err.range;
                 }
             }
         }
         self.table.state.display_start_index = 0;
         self.table.state.current_index = 0;
 
+        // If the query was changed by hand, then it no longer corresponds to a saved filter.
+        if let Some(filter) = self
+            .current_saved_filter
+            .and_then(|index| self.saved_filters.get(index))
+        {
+            if filter.query != self.proc_search.search_state.current_search_query {
+                self.set_current_saved_filter(None);
+            }
+        }
+
         self.force_data_update();
     }
 
     pub fn clear_search(&mut self) {
         self.proc_search.search_state.reset();
+        self.set_current_saved_filter(None);
         self.force_data_update();
     }
 
+    fn set_current_saved_filter(&mut self, index: Option<usize>) {
+        self.current_saved_filter = index;
+        self.table.props.title = Some(
+            match index.and_then(|index| self.saved_filters.get(index)) {
+                Some(filter) => format!("{}({}) ", Self::TITLE, filter.name).into(),
+                None => Self::TITLE.into(),
+            },
+        );
+    }
+
+    /// Applies the saved filter at the given index, replacing the current search query.  Passing
+    /// `None` or an invalid index clears the search query.
+    pub fn apply_saved_filter(&mut self, index: Option<usize>) {
+        match index.and_then(|index| self.saved_filters.get(index).cloned()) {
+            Some(filter) => {
+                self.set_current_saved_filter(index);
+                self.replace_search_range(
+                    0..self.proc_search.search_state.current_search_query.len(),
+                    &filter.query,
+                );
+
+                if let Some(sort_index) = filter.sort_index {
+                    let is_shown = self
+                        .table
+                        .columns
+                        .get(sort_index)
+                        .map(|col| !col.is_hidden)
+                        .unwrap_or(false);
+
+                    if is_shown && self.table.sort_index() != sort_index {
+                        self.select_column(sort_index);
+                    }
+                }
+            }
+            None => {
+                self.clear_search();
+            }
+        }
+    }
+
+    /// Moves to the next saved filter, going back to no filter after the last one.
+    pub fn cycle_saved_filter(&mut self) {
+        if self.saved_filters.is_empty() {
+            return;
+        }
+
+        let next = match self.current_saved_filter {
+            Some(index) if index + 1 < self.saved_filters.len() => Some(index + 1),
+            Some(_) => None,
+            None => Some(0),
+        };
+
+        self.apply_saved_filter(next);
+    }
+
+    /// Replaces part of the search query, moving the cursor to the end of the replacement.
+    fn replace_search_range(&mut self, range: Range<usize>, replacement: &str) {
+        let search_state = &mut self.proc_search.search_state;
+        let cursor_position = range.start + replacement.len();
+
+        search_state
+            .current_search_query
+            .replace_range(range, replacement);
+        search_state.grapheme_cursor = GraphemeCursor::new(
+            cursor_position,
+            search_state.current_search_query.len(),
+            true,
+        );
+        search_state.char_cursor_position =
+            UnicodeWidthStr::width(&search_state.current_search_query[..cursor_position]);
+        search_state.cursor_direction = CursorDirection::Right;
+
+        self.update_query();
+    }
+
+    /// Tab completes the word before the search cursor with a prefix name, username, or process
+    /// name.  Repeated calls cycle through the possible completions.
+    pub fn complete_search_query(
+        &mut self, data_collection: &DataCollection,
+        #[cfg(target_family = "unix")] user_table: &mut UserTable,
+    ) {
+        let cursor_position = self.get_search_cursor_position();
+        let query = &self.proc_search.search_state.current_search_query;
+
+        let (range, mut completion) = match self.proc_search.completion.take() {
+            Some(mut completion) if completion.is_continued_by(query, cursor_position) => {
+                completion.index = (completion.index + 1) % completion.candidates.len();
+                (completion.start..cursor_position, completion)
+            }
+            _ => {
+                let (range, kind) = match completion_target(query, cursor_position) {
+                    Some(target) => target,
+                    None => return,
+                };
+
+                #[cfg(target_family = "unix")]
+                let usernames = if kind == CompletionKind::User {
+                    user_table.usernames()
+                } else {
+                    vec![]
+                };
+                #[cfg(not(target_family = "unix"))]
+                let usernames = vec![];
+
+                let candidates = completion_candidates(
+                    &query[range.clone()],
+                    kind,
+                    usernames,
+                    data_collection
+                        .process_data
+                        .process_harvest
+                        .values()
+                        .map(|process| process.name.as_str()),
+                );
+                if candidates.is_empty() {
+                    return;
+                }
+
+                let completion = SearchCompletion {
+                    start: range.start,
+                    candidates,
+                    index: 0,
+                    completed_query: String::default(),
+                };
+                (range, completion)
+            }
+        };
+
+        let replacement = completion.candidates[completion.index].clone();
+        self.replace_search_range(range, &replacement);
+        completion.completed_query = self.proc_search.search_state.current_search_query.clone();
+        self.proc_search.completion = Some(completion);
+    }
+
+    /// Returns which search history entry the current query is, or `None` if it isn't from the
+    /// history (or has been edited since), in which case it becomes the draft.
+    fn current_history_index(&mut self, history: &SearchHistory) -> Option<usize> {
+        let current_query = &self.proc_search.search_state.current_search_query;
+        match self.proc_search.history_index {
+            Some(index) if history.get(index) == Some(current_query.as_str()) => Some(index),
+            _ => {
+                self.proc_search.history_draft = current_query.clone();
+                None
+            }
+        }
+    }
+
+    /// Replaces the search query with the search history entry at `index`, or with the draft if
+    /// `index` is `None`.
+    fn recall_search_history(&mut self, history: &SearchHistory, index: Option<usize>) {
+        let query = match index.and_then(|index| history.get(index)) {
+            Some(entry) => entry.to_string(),
+            None => std::mem::take(&mut self.proc_search.history_draft),
+        };
+
+        self.proc_search.history_index = index;
+        self.replace_search_range(
+            0..self.proc_search.search_state.current_search_query.len(),
+            &query,
+        );
+    }
+
+    /// Replaces the search query with the previous (older) query in the search history.
+    pub fn recall_older_search(&mut self, history: &SearchHistory) {
+        let index = self.current_history_index(history);
+        if let Some(older) = history.older(index) {
+            self.recall_search_history(history, Some(older));
+        }
+    }
+
+    /// Replaces the search query with the next (newer) query in the search history, going back to
+    /// what was originally typed after the newest one.
+    pub fn recall_newer_search(&mut self, history: &SearchHistory) {
+        if let Some(index) = self.current_history_index(history) {
+            self.recall_search_history(history, history.newer(index));
+        }
+    }
+
+    /// Replaces the search query with the newest older query in the search history that contains
+    /// what was originally typed.  Repeated calls keep searching further back.
+    pub fn reverse_search_history(&mut self, history: &SearchHistory) {
+        let index = self.current_history_index(history);
+        if let Some(found) = history.reverse_search(&self.proc_search.history_draft, index) {
+            self.recall_search_history(history, Some(found));
+        }
+    }
+
     pub fn search_walk_forward(&mut self, start_position: usize) {
         self.proc_search
             .search_state
@@ -5,6 +5,7 @@
 extern crate log;
 
 use bottom::{
+    app::search_history::SearchHistory,
     canvas::{self, canvas_colours::CanvasColours},
     constants::*,
     data_conversion::*,
@@ -68,6 +69,7 @@ fn main() -> Result<()> {
         &default_widget_type_option,
         &colours,
     )?;
+    app.search_history = SearchHistory::load(get_search_history_path(&config_path));
 
     // Create painter and set colours.
     let mut painter = canvas::Painter::init(widget_layout, colours)?;
@@ -188,7 +190,11 @@ fn main() -> Result<()> {
 
                         // Disk
                         if app.used_widgets.use_disk {
-                            app.converted_data.ingest_disk_data(&app.data_collection);
+                            app.converted_data.ingest_disk_data(
+                                &app.data_collection,
+                                app.app_config_fields.forecast_window,
+                                app.app_config_fields.forecast_warning,
+                            );
                         }
 
                         // Temperatures
@@ -215,11 +221,18 @@ fn main() -> Result<()> {
                                 app.converted_data.gpu_data =
                                     convert_gpu_data(&app.data_collection);
                             }
+                            app.converted_data.numa_data = convert_numa_data(&app.data_collection);
+                            app.converted_data.compressed_data =
+                                convert_compressed_data(&app.data_collection);
+                            app.converted_data.huge_pages_data =
+                                convert_huge_pages_data_points(&app.data_collection);
                             let (memory_labels, swap_labels) =
                                 convert_mem_labels(&app.data_collection);
 
                             app.converted_data.mem_labels = memory_labels;
                             app.converted_data.swap_labels = swap_labels;
+                            app.converted_data.huge_pages_labels =
+                                convert_huge_pages_labels(&app.data_collection);
                             #[cfg(feature = "zfs")]
                             {
                                 let arc_labels = convert_arc_labels(&app.data_collection);
@@ -255,6 +268,8 @@ fn main() -> Result<()> {
                 BottomEvent::Clean => {
                     app.data_collection
                         .clean_data(constants::STALE_MAX_MILLISECONDS);
+                    app.data_collection
+                        .clean_free_space_history(app.app_config_fields.forecast_window);
                 }
             }
         }
@@ -263,6 +278,8 @@ fn main() -> Result<()> {
         try_drawing(&mut terminal, &mut app, &mut painter)?;
     }
 
+    app.search_history.save();
+
     // I think doing it in this order is safe...
 
     *thread_termination_lock.lock().unwrap() = true;
@@ -386,18 +386,42 @@ impl Painter {
                         rect[0],
                         app_state.current_widget.widget_id - 1,
                     ),
+                    CpuHeatmap => self.draw_cpu_heatmap(
+                        f,
+                        app_state,
+                        rect[0],
+                        app_state.current_widget.widget_id,
+                    ),
                     Mem | BasicMem => self.draw_memory_graph(
                         f,
                         app_state,
                         rect[0],
                         app_state.current_widget.widget_id,
                     ),
+                    Pressure => self.draw_pressure_graph(
+                        f,
+                        app_state,
+                        rect[0],
+                        app_state.current_widget.widget_id,
+                    ),
+                    Kernel => self.draw_kernel_activity(
+                        f,
+                        app_state,
+                        rect[0],
+                        app_state.current_widget.widget_id,
+                    ),
                     Disk => self.draw_disk_table(
                         f,
                         app_state,
                         rect[0],
                         app_state.current_widget.widget_id,
                     ),
+                    DiskIo => self.draw_disk_io_graph(
+                        f,
+                        app_state,
+                        rect[0],
+                        app_state.current_widget.widget_id,
+                    ),
                     Temp => self.draw_temp_table(
                         f,
                         app_state,
@@ -471,6 +495,18 @@ impl Painter {
                     }
                 }
 
+                if let Some(numa_data) = &app_state.converted_data.numa_data {
+                    mem_rows += numa_data.len() as u16; // add row(s) for NUMA nodes
+                }
+
+                if let Some(compressed_data) = &app_state.converted_data.compressed_data {
+                    mem_rows += compressed_data.len() as u16; // add row(s) for compressed memory
+                }
+
+                if app_state.converted_data.huge_pages_labels.is_some() {
+                    mem_rows += 1; // add row for huge pages
+                }
+
                 if mem_rows == 1 {
                     mem_rows += 1; // need at least 2 rows for RX and TX
                 }
@@ -657,10 +693,22 @@ impl Painter {
                 match &widget.widget_type {
                     Empty => {}
                     Cpu => self.draw_cpu(f, app_state, *widget_draw_loc, widget.widget_id),
+                    CpuHeatmap => {
+                        self.draw_cpu_heatmap(f, app_state, *widget_draw_loc, widget.widget_id)
+                    }
                     Mem => self.draw_memory_graph(f, app_state, *widget_draw_loc, widget.widget_id),
+                    Pressure => {
+                        self.draw_pressure_graph(f, app_state, *widget_draw_loc, widget.widget_id)
+                    }
+                    Kernel => {
+                        self.draw_kernel_activity(f, app_state, *widget_draw_loc, widget.widget_id)
+                    }
                     Net => self.draw_network(f, app_state, *widget_draw_loc, widget.widget_id),
                     Temp => self.draw_temp_table(f, app_state, *widget_draw_loc, widget.widget_id),
                     Disk => self.draw_disk_table(f, app_state, *widget_draw_loc, widget.widget_id),
+                    DiskIo => {
+                        self.draw_disk_io_graph(f, app_state, *widget_draw_loc, widget.widget_id)
+                    }
                     Proc => self.draw_process_widget(
                         f,
                         app_state,
@@ -18,7 +18,10 @@ pub struct CanvasColours {
     pub ram_style: Style,
     pub swap_style: Style,
     pub arc_style: Style,
+    pub huge_pages_style: Style,
     pub gpu_colour_styles: Vec<Style>,
+    pub numa_colour_styles: Vec<Style>,
+    pub compressed_colour_styles: Vec<Style>,
     pub rx_style: Style,
     pub tx_style: Style,
     pub total_rx_style: Style,
@@ -26,6 +29,7 @@ pub struct CanvasColours {
     pub all_colour_style: Style,
     pub avg_colour_style: Style,
     pub cpu_colour_styles: Vec<Style>,
+    pub cpu_heatmap_colour_styles: Vec<Style>,
     pub border_style: Style,
     pub highlighted_border_style: Style,
     pub text_style: Style,
@@ -34,8 +38,12 @@ pub struct CanvasColours {
     pub high_battery_colour: Style,
     pub medium_battery_colour: Style,
     pub low_battery_colour: Style,
+    pub disk_warning_style: Style,
     pub invalid_query_style: Style,
     pub disabled_text_style: Style,
+    pub search_prefix_style: Style,
+    pub search_operator_style: Style,
+    pub search_value_style: Style,
 }
 
 impl Default for CanvasColours {
@@ -52,6 +60,7 @@ impl Default for CanvasColours {
             ram_style: Style::default().fg(STANDARD_FIRST_COLOUR),
             swap_style: Style::default().fg(STANDARD_SECOND_COLOUR),
             arc_style: Style::default().fg(STANDARD_THIRD_COLOUR),
+            huge_pages_style: Style::default().fg(Color::LightMagenta),
             gpu_colour_styles: vec![
                 Style::default().fg(STANDARD_FOURTH_COLOUR),
                 Style::default().fg(Color::LightBlue),
@@ -61,6 +70,20 @@ impl Default for CanvasColours {
                 Style::default().fg(Color::Blue),
                 Style::default().fg(Color::Red),
             ],
+            numa_colour_styles: vec![
+                Style::default().fg(Color::LightCyan),
+                Style::default().fg(Color::LightYellow),
+                Style::default().fg(Color::Magenta),
+                Style::default().fg(Color::Yellow),
+                Style::default().fg(Color::LightGreen),
+                Style::default().fg(Color::Blue),
+            ],
+            compressed_colour_styles: vec![
+                Style::default().fg(Color::LightYellow),
+                Style::default().fg(Color::LightGreen),
+                Style::default().fg(Color::LightCyan),
+                Style::default().fg(Color::LightMagenta),
+            ],
             rx_style: Style::default().fg(STANDARD_FIRST_COLOUR),
             tx_style: Style::default().fg(STANDARD_SECOND_COLOUR),
             total_rx_style: Style::default().fg(STANDARD_THIRD_COLOUR),
@@ -79,6 +102,14 @@ impl Default for CanvasColours {
                 Style::default().fg(Color::Blue),
                 Style::default().fg(Color::Red),
             ],
+            cpu_heatmap_colour_styles: vec![
+                Style::default().fg(Color::Blue),
+                Style::default().fg(Color::Cyan),
+                Style::default().fg(Color::Green),
+                Style::default().fg(Color::Yellow),
+                Style::default().fg(Color::LightRed),
+                Style::default().fg(Color::Red),
+            ],
             border_style: Style::default().fg(text_colour),
             highlighted_border_style: Style::default().fg(STANDARD_HIGHLIGHT_COLOUR),
             text_style: Style::default().fg(text_colour),
@@ -87,8 +118,12 @@ impl Default for CanvasColours {
             high_battery_colour: Style::default().fg(Color::Green),
             medium_battery_colour: Style::default().fg(Color::Yellow),
             low_battery_colour: Style::default().fg(Color::Red),
+            disk_warning_style: Style::default().fg(Color::Red),
             invalid_query_style: Style::default().fg(tui::style::Color::Red),
             disabled_text_style: Style::default().fg(Color::DarkGray),
+            search_prefix_style: Style::default().fg(STANDARD_HIGHLIGHT_COLOUR),
+            search_operator_style: Style::default().fg(Color::LightMagenta),
+            search_value_style: Style::default().fg(Color::LightGreen),
         }
     }
 }
@@ -155,6 +190,11 @@ impl CanvasColours {
                 .context("Update 'cpu_core_colors' in your config file..")?;
         }
 
+        if let Some(cpu_heatmap_colors) = &colours.cpu_heatmap_colors {
+            self.set_cpu_heatmap_colours(cpu_heatmap_colors)
+                .context("Update 'cpu_heatmap_colors' in your config file..")?;
+        }
+
         if let Some(ram_color) = &colours.ram_color {
             self.set_ram_colour(ram_color)
                 .context("Update 'ram_color' in your config file..")?;
@@ -170,11 +210,26 @@ impl CanvasColours {
                 .context("Update 'arc_color' in your config file..")?;
         }
 
+        if let Some(huge_pages_color) = &colours.huge_pages_color {
+            self.set_huge_pages_colour(huge_pages_color)
+                .context("Update 'huge_pages_color' in your config file..")?;
+        }
+
         if let Some(gpu_core_colors) = &colours.gpu_core_colors {
             self.set_gpu_colours(gpu_core_colors)
                 .context("Update 'gpu_core_colors' in your config file..")?;
         }
 
+        if let Some(numa_node_colors) = &colours.numa_node_colors {
+            self.set_numa_colours(numa_node_colors)
+                .context("Update 'numa_node_colors' in your config file..")?;
+        }
+
+        if let Some(compressed_colors) = &colours.compressed_colors {
+            self.set_compressed_colours(compressed_colors)
+                .context("Update 'compressed_colors' in your config file..")?;
+        }
+
         if let Some(rx_color) = &colours.rx_color {
             self.set_rx_colour(rx_color)
                 .context("Update 'rx_color' in your config file..")?;
@@ -225,11 +280,31 @@ impl CanvasColours {
                 .context("Update 'low_battery_color' in your config file.")?;
         }
 
+        if let Some(disk_warning_color) = &colours.disk_warning_color {
+            self.set_disk_warning_colour(disk_warning_color)
+                .context("Update 'disk_warning_color' in your config file.")?;
+        }
+
         if let Some(disabled_text_color) = &colours.disabled_text_color {
             self.set_disabled_text_colour(disabled_text_color)
                 .context("Update 'disabled_text_color' in your config file.")?;
         }
 
+        if let Some(search_prefix_color) = &colours.search_prefix_color {
+            self.set_search_prefix_colour(search_prefix_color)
+                .context("Update 'search_prefix_color' in your config file.")?;
+        }
+
+        if let Some(search_operator_color) = &colours.search_operator_color {
+            self.set_search_operator_colour(search_operator_color)
+                .context("Update 'search_operator_color' in your config file.")?;
+        }
+
+        if let Some(search_value_color) = &colours.search_value_color {
+            self.set_search_value_colour(search_value_color)
+                .context("Update 'search_value_color' in your config file.")?;
+        }
+
         if let Some(rx_total_color) = &colours.rx_total_color {
             self.set_rx_total_colour(rx_total_color)?;
         }
@@ -283,6 +358,11 @@ impl CanvasColours {
         Ok(())
     }
 
+    pub fn set_huge_pages_colour(&mut self, colour: &str) -> error::Result<()> {
+        self.huge_pages_style = get_style_from_config(colour)?;
+        Ok(())
+    }
+
     pub fn set_gpu_colours(&mut self, colours: &[String]) -> error::Result<()> {
         self.gpu_colour_styles = colours
             .iter()
@@ -291,6 +371,22 @@ impl CanvasColours {
         Ok(())
     }
 
+    pub fn set_numa_colours(&mut self, colours: &[String]) -> error::Result<()> {
+        self.numa_colour_styles = colours
+            .iter()
+            .map(|colour| get_style_from_config(colour))
+            .collect::<error::Result<Vec<Style>>>()?;
+        Ok(())
+    }
+
+    pub fn set_compressed_colours(&mut self, colours: &[String]) -> error::Result<()> {
+        self.compressed_colour_styles = colours
+            .iter()
+            .map(|colour| get_style_from_config(colour))
+            .collect::<error::Result<Vec<Style>>>()?;
+        Ok(())
+    }
+
     pub fn set_rx_colour(&mut self, colour: &str) -> error::Result<()> {
         self.rx_style = get_style_from_config(colour)?;
         Ok(())
@@ -329,6 +425,31 @@ impl CanvasColours {
         Ok(())
     }
 
+    pub fn set_cpu_heatmap_colours(&mut self, colours: &[String]) -> error::Result<()> {
+        if colours.is_empty() {
+            return Err(error::BottomError::ConfigError(
+                "the heatmap needs at least one colour.".to_string(),
+            ));
+        }
+
+        self.cpu_heatmap_colour_styles = colours
+            .iter()
+            .map(|colour| get_style_from_config(colour))
+            .collect::<error::Result<Vec<Style>>>()?;
+        Ok(())
+    }
+
+    /// Returns the heatmap style for a usage percentage, picking evenly from the heatmap colours.
+    pub fn cpu_heatmap_style(&self, usage: f64) -> Style {
+        let num_colours = self.cpu_heatmap_colour_styles.len();
+        let index = ((usage / 100.0) * num_colours as f64).max(0.0) as usize;
+
+        self.cpu_heatmap_colour_styles
+            .get(index.min(num_colours.saturating_sub(1)))
+            .copied()
+            .unwrap_or_default()
+    }
+
     pub fn set_scroll_entry_text_color(&mut self, colour: &str) -> error::Result<()> {
         self.currently_selected_text_colour = get_colour_from_config(colour)?;
         self.currently_selected_text_style = Style::default()
@@ -369,4 +490,24 @@ impl CanvasColours {
         self.low_battery_colour = get_style_from_config(colour)?;
         Ok(())
     }
+
+    pub fn set_disk_warning_colour(&mut self, colour: &str) -> error::Result<()> {
+        self.disk_warning_style = get_style_from_config(colour)?;
+        Ok(())
+    }
+
+    pub fn set_search_prefix_colour(&mut self, colour: &str) -> error::Result<()> {
+        self.search_prefix_style = get_style_from_config(colour)?;
+        Ok(())
+    }
+
+    pub fn set_search_operator_colour(&mut self, colour: &str) -> error::Result<()> {
+        self.search_operator_style = get_style_from_config(colour)?;
+        Ok(())
+    }
+
+    pub fn set_search_value_colour(&mut self, colour: &str) -> error::Result<()> {
+        self.search_value_style = get_style_from_config(colour)?;
+        Ok(())
+    }
 }
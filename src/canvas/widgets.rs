@@ -2,10 +2,14 @@ pub mod basic_table_arrows;
 pub mod battery_display;
 pub mod cpu_basic;
 pub mod cpu_graph;
+pub mod cpu_heatmap;
+pub mod disk_io_graph;
 pub mod disk_table;
+pub mod kernel_activity;
 pub mod mem_basic;
 pub mod mem_graph;
 pub mod network_basic;
 pub mod network_graph;
+pub mod pressure_graph;
 pub mod process_table;
 pub mod temp_table;
@@ -60,9 +60,15 @@ impl Painter {
                         data_type,
                         data: _,
                         last_entry,
+                        frequency: _,
+                        collapsed: _,
                     } => {
                         let (outer, style) = match data_type {
                             CpuDataType::Avg => ("AVG".to_string(), self.colours.avg_colour_style),
+                            CpuDataType::Group(group) => (
+                                format!("{:<3}", group.short_name()),
+                                self.colours.avg_colour_style,
+                            ),
                             CpuDataType::Cpu(index) => (
                                 format!("{index:<3}",),
                                 self.colours.cpu_colour_styles
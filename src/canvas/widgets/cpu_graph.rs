@@ -1,13 +1,20 @@
 use std::borrow::Cow;
 
 use crate::{
-    app::{layout_manager::WidgetDirection, widgets::CpuWidgetState, App},
+    app::{
+        data_harvester::cpu::CpuDataType, layout_manager::WidgetDirection, widgets::CpuGraphMode,
+        App,
+    },
     canvas::{drawing_utils::should_hide_x_label, Painter},
     components::{
         data_table::{DrawInfo, SelectionState},
         time_graph::{GraphData, TimeGraph},
+        tui_widget::time_chart::Point,
+    },
+    data_conversion::{
+        convert_cpu_frequency_points, convert_cpu_state_points, convert_load_avg_points,
+        frequency_string, CpuWidgetData, CPU_STATE_GROUPS,
     },
-    data_conversion::CpuWidgetData,
 };
 
 use concat_string::concat_string;
@@ -18,7 +25,6 @@ use tui::{
     terminal::Frame,
 };
 
-const AVG_POSITION: usize = 1;
 const ALL_POSITION: usize = 0;
 
 impl Painter {
@@ -117,55 +123,38 @@ impl Painter {
         }
     }
 
+    /// Generates the graph lines for the CPU entries shown in the legend, where `get_points`
+    /// returns the points of the entry at a given position in `cpu_data`.
     fn generate_points<'a>(
-        &self, cpu_widget_state: &CpuWidgetState, cpu_data: &'a [CpuWidgetData], show_avg_cpu: bool,
+        &self, cpu_data: &[CpuWidgetData], visible: &[usize], current_position: usize,
+        get_points: impl Fn(usize) -> Option<&'a [Point]>,
     ) -> Vec<GraphData<'a>> {
-        let show_avg_offset = if show_avg_cpu { AVG_POSITION } else { 0 };
+        let style_at = |itx: usize| match cpu_data.get(itx) {
+            Some(CpuWidgetData::Entry {
+                data_type: CpuDataType::Cpu(index),
+                ..
+            }) => self.colours.cpu_colour_styles[index % self.colours.cpu_colour_styles.len()],
+            _ => self.colours.avg_colour_style,
+        };
 
-        let current_scroll_position = cpu_widget_state.table.state.current_index;
-        if current_scroll_position == ALL_POSITION {
+        if current_position == ALL_POSITION {
             // This case ensures the other cases cannot have the position be equal to 0.
-            cpu_data
+            visible
                 .iter()
-                .enumerate()
                 .rev()
-                .filter_map(|(itx, cpu)| {
-                    match &cpu {
-                        CpuWidgetData::All => None,
-                        CpuWidgetData::Entry { data, .. } => {
-                            let style = if show_avg_cpu && itx == AVG_POSITION {
-                                self.colours.avg_colour_style
-                            } else if itx == ALL_POSITION {
-                                self.colours.all_colour_style
-                            } else {
-                                let offset_position = itx - 1; // Because of the all position
-                                self.colours.cpu_colour_styles[(offset_position - show_avg_offset)
-                                    % self.colours.cpu_colour_styles.len()]
-                            };
-
-                            Some(GraphData {
-                                points: &data[..],
-                                style,
-                                name: None,
-                            })
-                        }
-                    }
+                .filter(|itx| **itx != ALL_POSITION)
+                .filter_map(|&itx| {
+                    get_points(itx).map(|points| GraphData {
+                        points,
+                        style: style_at(itx),
+                        name: None,
+                    })
                 })
                 .collect::<Vec<_>>()
-        } else if let Some(CpuWidgetData::Entry { data, .. }) =
-            cpu_data.get(current_scroll_position)
-        {
-            let style = if show_avg_cpu && current_scroll_position == AVG_POSITION {
-                self.colours.avg_colour_style
-            } else {
-                let offset_position = current_scroll_position - 1; // Because of the all position
-                self.colours.cpu_colour_styles
-                    [(offset_position - show_avg_offset) % self.colours.cpu_colour_styles.len()]
-            };
-
+        } else if let Some(points) = get_points(current_position) {
             vec![GraphData {
-                points: &data[..],
-                style,
+                points,
+                style: style_at(current_position),
                 name: None,
             }]
         } else {
@@ -173,6 +162,57 @@ impl Painter {
         }
     }
 
+    /// Generates the graph lines for the CPU state breakdown.  The topmost series are drawn first
+    /// so that lower ones are drawn over them where they overlap.
+    fn generate_state_points<'a>(&self, state_points: &'a [Vec<Point>]) -> Vec<GraphData<'a>> {
+        let mut previous_total = 0.0;
+
+        let mut points = CPU_STATE_GROUPS
+            .iter()
+            .zip(state_points)
+            .enumerate()
+            .map(|(itx, ((name, _), series))| {
+                let total = series.last().map(|(_, value)| *value).unwrap_or(0.0);
+                let current_value = total - previous_total;
+                previous_total = total;
+
+                GraphData {
+                    points: &series[..],
+                    style: self.colours.cpu_colour_styles
+                        [itx % self.colours.cpu_colour_styles.len()],
+                    name: Some(format!("{:<6} {:>3.0}%", name, current_value).into()),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        points.reverse();
+        points
+    }
+
+    /// Generates the graph lines for the 1, 5, and 15 minute load averages.
+    fn generate_load_avg_points<'a>(
+        &self, load_avg_points: &'a [Vec<Point>],
+    ) -> Vec<GraphData<'a>> {
+        const LOAD_AVG_NAMES: [&str; 3] = ["1m", "5m", "15m"];
+
+        LOAD_AVG_NAMES
+            .iter()
+            .zip(load_avg_points)
+            .enumerate()
+            .map(|(itx, (name, series))| {
+                let current_value = series.last().map(|(_, value)| *value).unwrap_or(0.0);
+
+                GraphData {
+                    points: &series[..],
+                    style: self.colours.cpu_colour_styles
+                        [itx % self.colours.cpu_colour_styles.len()],
+                    name: Some(format!("{:<3} {:.2}", name, current_value).into()),
+                }
+            })
+            .rev()
+            .collect()
+    }
+
     fn draw_cpu_graph<B: Backend>(
         &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect, widget_id: u64,
     ) {
@@ -190,11 +230,89 @@ impl Painter {
                 draw_loc,
             );
 
-            let points = self.generate_points(
-                cpu_widget_state,
-                cpu_data,
-                app_state.app_config_fields.show_average_cpu,
-            );
+            // The legend may hide the cores of collapsed groups, so map the selected row back to its
+            // position in the CPU data.
+            let visible = cpu_widget_state.visible_entries(cpu_data);
+            let current_position = visible
+                .get(cpu_widget_state.table.state.current_index)
+                .copied()
+                .unwrap_or(ALL_POSITION);
+
+            // The state breakdown is only shown for a single selected entry, not for "All".
+            let state_points = if cpu_widget_state.graph_mode == CpuGraphMode::States
+                && current_position != ALL_POSITION
+            {
+                let state_points = convert_cpu_state_points(
+                    app_state.frozen_state.data(&app_state.data_collection),
+                    current_position - 1, // Because of the all position
+                );
+                if state_points.iter().all(|series| series.is_empty()) {
+                    None
+                } else {
+                    Some(state_points)
+                }
+            } else {
+                None
+            };
+
+            // Frequencies are lined up with the legend, so the first one is an empty "All" entry.
+            let frequency_points = if cpu_widget_state.graph_mode == CpuGraphMode::Frequency {
+                let data_collection = app_state.frozen_state.data(&app_state.data_collection);
+                let frequency_points = (0..cpu_data.len())
+                    .map(|itx| {
+                        if itx == ALL_POSITION
+                            || (current_position != ALL_POSITION && itx != current_position)
+                            || !visible.contains(&itx)
+                        {
+                            vec![]
+                        } else {
+                            convert_cpu_frequency_points(data_collection, itx - 1)
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                if frequency_points.iter().all(|series| series.is_empty()) {
+                    None
+                } else {
+                    Some(frequency_points)
+                }
+            } else {
+                None
+            };
+
+            // Load averages can be divided by the number of cores so that 1 means fully loaded.
+            let load_avg_points = if cpu_widget_state.graph_mode == CpuGraphMode::LoadAverage {
+                let data_collection = app_state.frozen_state.data(&app_state.data_collection);
+                let divisor = if app_state.app_config_fields.normalize_load_avg {
+                    data_collection
+                        .cpu_harvest
+                        .iter()
+                        .filter(|cpu| matches!(cpu.data_type, CpuDataType::Cpu(_)))
+                        .count() as f64
+                } else {
+                    1.0
+                };
+
+                Some(convert_load_avg_points(data_collection, divisor))
+            } else {
+                None
+            };
+
+            let points = if let Some(state_points) = &state_points {
+                self.generate_state_points(state_points)
+            } else if let Some(load_avg_points) = &load_avg_points {
+                self.generate_load_avg_points(load_avg_points)
+            } else if let Some(frequency_points) = &frequency_points {
+                self.generate_points(cpu_data, &visible, current_position, |itx| {
+                    frequency_points.get(itx).map(|points| &points[..])
+                })
+            } else {
+                self.generate_points(cpu_data, &visible, current_position, |itx| {
+                    match cpu_data.get(itx) {
+                        Some(CpuWidgetData::Entry { data, .. }) => Some(&data[..]),
+                        _ => None,
+                    }
+                })
+            };
 
             // TODO: Maybe hide load avg if too long? Or maybe the CPU part.
             let title = if cfg!(target_family = "unix") {
@@ -204,23 +322,94 @@ impl Painter {
                     load_avg[0], load_avg[1], load_avg[2]
                 );
 
-                concat_string!(" CPU ", load_avg_str).into()
+                concat_string!(" CPU ", load_avg_str)
             } else {
-                " CPU ".into()
+                " CPU ".to_string()
+            };
+            let title = if app_state
+                .frozen_state
+                .data(&app_state.data_collection)
+                .cgroup_harvest
+                .is_some()
+            {
+                concat_string!(title, "─ Container ")
+            } else {
+                title
+            };
+            let title = if state_points.is_some() {
+                concat_string!(title, "─ States ").into()
+            } else if frequency_points.is_some() {
+                concat_string!(title, "─ Frequency ").into()
+            } else if load_avg_points.is_some() {
+                if app_state.app_config_fields.normalize_load_avg {
+                    concat_string!(title, "─ Load Average per Core ").into()
+                } else {
+                    concat_string!(title, "─ Load Average ").into()
+                }
+            } else {
+                title.into()
+            };
+
+            // Frequencies are scaled to the highest that any core can reach, or has reached.
+            let (y_bounds, y_labels) = if let Some(frequency_points) = &frequency_points {
+                let max_frequency = app_state
+                    .frozen_state
+                    .data(&app_state.data_collection)
+                    .cpu_harvest
+                    .iter()
+                    .filter_map(|cpu| cpu.frequency.and_then(|frequency| frequency.max))
+                    .max()
+                    .map(|max| max as f64)
+                    .unwrap_or(0.0)
+                    .max(
+                        frequency_points
+                            .iter()
+                            .flatten()
+                            .map(|(_, value)| *value)
+                            .fold(0.0, f64::max),
+                    );
+
+                let max_label = frequency_string(max_frequency.round() as u64);
+                let min_label = format!("{:>width$}", "0", width = max_label.len());
+                (
+                    [0.0, max_frequency * 1.005],
+                    vec![Cow::Owned(min_label), Cow::Owned(max_label)],
+                )
+            } else if let Some(load_avg_points) = &load_avg_points {
+                // Round up to a whole number so that the top of the graph is easier to read.
+                let max_load_avg = load_avg_points
+                    .iter()
+                    .flatten()
+                    .map(|(_, value)| *value)
+                    .fold(1.0, f64::max)
+                    .ceil();
+
+                let max_label = format!("{:.1}", max_load_avg);
+                let min_label = format!("{:>width$}", "0", width = max_label.len());
+                (
+                    [0.0, max_load_avg * 1.005],
+                    vec![Cow::Owned(min_label), Cow::Owned(max_label)],
+                )
+            } else {
+                (Y_BOUNDS, Y_LABELS.to_vec())
             };
 
             TimeGraph {
                 use_dot: app_state.app_config_fields.use_dot,
                 x_bounds,
                 hide_x_labels,
-                y_bounds: Y_BOUNDS,
-                y_labels: &Y_LABELS,
+                y_bounds,
+                y_labels: &y_labels,
                 graph_style: self.colours.graph_style,
                 border_style,
                 title,
                 is_expanded: app_state.is_expanded,
                 title_style: self.colours.widget_title_style,
-                legend_constraints: None,
+                legend_constraints: if state_points.is_some() || load_avg_points.is_some() {
+                    Some((Constraint::Ratio(3, 4), Constraint::Ratio(3, 4)))
+                } else {
+                    None
+                },
             }
             .draw_time_graph(f, draw_loc, &points);
         }
@@ -235,6 +424,16 @@ impl Painter {
             // TODO: This line (and the one above, see caller) is pretty dumb but I guess needed for now. Refactor if possible!
             cpu_widget_state.is_legend_hidden = false;
 
+            let has_frequency = app_state
+                .frozen_state
+                .data(&app_state.data_collection)
+                .cpu_harvest
+                .iter()
+                .any(|cpu| cpu.frequency.is_some());
+            let recalculate_column_widths = cpu_widget_state
+                .update_frequency_column(draw_loc.width, has_frequency)
+                || recalculate_column_widths;
+
             let is_on_widget = widget_id == app_state.current_widget.widget_id;
 
             let draw_info = DrawInfo {
@@ -244,10 +443,13 @@ impl Painter {
                 selection_state: SelectionState::new(app_state.is_expanded, is_on_widget),
             };
 
+            let cpu_data = &app_state.converted_data.cpu_data;
+            let visible = cpu_widget_state.visible_entries(cpu_data);
+            let legend_data = cpu_widget_state.legend_data(cpu_data, &visible);
             cpu_widget_state.table.draw(
                 f,
                 &draw_info,
-                app_state.converted_data.cpu_data.clone(),
+                legend_data,
                 app_state.widget_map.get_mut(&widget_id),
                 self,
             );
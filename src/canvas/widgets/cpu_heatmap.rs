@@ -0,0 +1,252 @@
+use crate::{
+    app::{data_harvester::cpu::CpuDataType, widgets::CpuHeatmapMode, App},
+    canvas::Painter,
+    data_conversion::{convert_cpu_heatmap_data, CpuHeatmapRow},
+};
+
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Style},
+    terminal::Frame,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph},
+};
+use unicode_segmentation::UnicodeSegmentation;
+
+impl Painter {
+    pub fn draw_cpu_heatmap<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect, widget_id: u64,
+    ) {
+        if let Some(heatmap_widget_state) =
+            app_state.cpu_heatmap_state.widget_states.get(&widget_id)
+        {
+            let border_style = self.get_border_style(widget_id, app_state.current_widget.widget_id);
+            let title = match heatmap_widget_state.mode {
+                CpuHeatmapMode::History => format!(
+                    " CPU Heatmap ─ {}s ",
+                    heatmap_widget_state.current_display_time / 1000
+                ),
+                CpuHeatmapMode::Grid => " CPU Heatmap ".to_string(),
+            };
+            let title = if app_state.is_expanded {
+                let title_base = format!("{}── Esc to go back ", title);
+                Spans::from(vec![
+                    Span::styled(title, self.colours.widget_title_style),
+                    Span::styled(
+                        format!(
+                            "─{}─ Esc to go back ",
+                            "─".repeat(usize::from(draw_loc.width).saturating_sub(
+                                UnicodeSegmentation::graphemes(title_base.as_str(), true).count()
+                                    + 2
+                            ))
+                        ),
+                        border_style,
+                    ),
+                ])
+            } else {
+                Spans::from(Span::styled(title, self.colours.widget_title_style))
+            };
+
+            let block = Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(border_style);
+            let inner_loc = block.inner(draw_loc);
+
+            let data = app_state.frozen_state.data(&app_state.data_collection);
+            let label_width = data
+                .cpu_harvest
+                .iter()
+                .filter_map(|cpu| match cpu.data_type {
+                    CpuDataType::Cpu(index) => Some(index.to_string().len()),
+                    CpuDataType::Avg | CpuDataType::Group(_) => None,
+                })
+                .max()
+                .unwrap_or(1);
+
+            let lines = match heatmap_widget_state.mode {
+                CpuHeatmapMode::History => {
+                    let num_buckets = usize::from(inner_loc.width).saturating_sub(label_width + 1);
+                    let rows = convert_cpu_heatmap_data(
+                        data,
+                        heatmap_widget_state.current_display_time,
+                        num_buckets,
+                    );
+                    self.cpu_heatmap_history_lines(
+                        &rows,
+                        usize::from(inner_loc.height),
+                        label_width,
+                    )
+                }
+                CpuHeatmapMode::Grid => {
+                    let rows = convert_cpu_heatmap_data(data, 0, 0);
+                    self.cpu_heatmap_grid_lines(
+                        &rows,
+                        usize::from(inner_loc.width),
+                        usize::from(inner_loc.height),
+                        label_width,
+                    )
+                }
+            };
+
+            f.render_widget(Paragraph::new(lines).block(block), draw_loc);
+        }
+
+        if app_state.should_get_widget_bounds() {
+            // Update draw loc in widget map
+            if let Some(widget) = app_state.widget_map.get_mut(&widget_id) {
+                widget.top_left_corner = Some((draw_loc.x, draw_loc.y));
+                widget.bottom_right_corner =
+                    Some((draw_loc.x + draw_loc.width, draw_loc.y + draw_loc.height));
+            }
+        }
+    }
+
+    fn cpu_core_style(&self, index: usize) -> Style {
+        self.colours.cpu_colour_styles[index % self.colours.cpu_colour_styles.len()]
+    }
+
+    fn cpu_heatmap_colour(&self, usage: f64) -> Color {
+        self.colours
+            .cpu_heatmap_style(usage)
+            .fg
+            .unwrap_or(Color::Reset)
+    }
+
+    /// Draws one row per core with time going from left to right.  If there are more cores than
+    /// lines, two cores are fit into each line with half blocks, and if there still isn't enough
+    /// room, neighbouring cores are averaged together.
+    fn cpu_heatmap_history_lines(
+        &self, rows: &[CpuHeatmapRow], height: usize, label_width: usize,
+    ) -> Vec<Spans<'_>> {
+        if rows.is_empty() || height == 0 {
+            return vec![];
+        }
+
+        let (cores_per_line, group_size) = if rows.len() <= height {
+            (1, 1)
+        } else {
+            (2, rows.len().div_ceil(2 * height))
+        };
+
+        let groups = rows
+            .chunks(group_size)
+            .map(|group| {
+                let num_buckets = group[0].buckets.len();
+                let buckets = (0..num_buckets)
+                    .map(|bucket| {
+                        let values = group
+                            .iter()
+                            .filter_map(|row| row.buckets[bucket])
+                            .collect::<Vec<_>>();
+                        if values.is_empty() {
+                            None
+                        } else {
+                            Some(values.iter().sum::<f64>() / values.len() as f64)
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                (group[0].index, buckets)
+            })
+            .collect::<Vec<_>>();
+
+        groups
+            .chunks(cores_per_line)
+            .map(|line| {
+                let (index, top) = &line[0];
+                let bottom = line.get(1).map(|(_, bottom)| bottom);
+
+                let mut spans = vec![Span::styled(
+                    format!("{:>width$} ", index, width = label_width),
+                    self.cpu_core_style(*index),
+                )];
+                spans.extend(top.iter().enumerate().map(|(bucket, top_value)| {
+                    let bottom_value = if cores_per_line == 1 {
+                        *top_value
+                    } else {
+                        bottom.and_then(|bottom| bottom[bucket])
+                    };
+
+                    match (top_value, bottom_value) {
+                        (Some(top_value), Some(bottom_value)) => Span::styled(
+                            "▀",
+                            Style::default()
+                                .fg(self.cpu_heatmap_colour(*top_value))
+                                .bg(self.cpu_heatmap_colour(bottom_value)),
+                        ),
+                        (Some(top_value), None) => Span::styled(
+                            "▀",
+                            Style::default().fg(self.cpu_heatmap_colour(*top_value)),
+                        ),
+                        (None, Some(bottom_value)) => Span::styled(
+                            "▄",
+                            Style::default().fg(self.cpu_heatmap_colour(bottom_value)),
+                        ),
+                        (None, None) => Span::raw(" "),
+                    }
+                }));
+
+                Spans::from(spans)
+            })
+            .collect()
+    }
+
+    /// Draws a grid of the current usage of each core, shrinking the cells from showing the core
+    /// number and usage, to just the core number, to a single character until every core fits.
+    fn cpu_heatmap_grid_lines(
+        &self, rows: &[CpuHeatmapRow], width: usize, height: usize, label_width: usize,
+    ) -> Vec<Spans<'_>> {
+        if rows.is_empty() || width == 0 || height == 0 {
+            return vec![];
+        }
+
+        let fits = |cell_width: usize, gap: usize| {
+            let cells_per_line = (width + gap) / (cell_width + gap);
+            if cells_per_line > 0 && rows.len().div_ceil(cells_per_line) <= height {
+                Some(cells_per_line)
+            } else {
+                None
+            }
+        };
+
+        let (cells_per_line, gap, cell_text): (usize, usize, fn(&CpuHeatmapRow, usize) -> String) =
+            if let Some(cells_per_line) = fits(label_width + 5, 1) {
+                (cells_per_line, 1, |row, label_width| {
+                    format!(
+                        "{:>width$} {:>3.0}%",
+                        row.index,
+                        row.current.round(),
+                        width = label_width
+                    )
+                })
+            } else if let Some(cells_per_line) = fits(label_width, 1) {
+                (cells_per_line, 1, |row, label_width| {
+                    format!("{:>width$}", row.index, width = label_width)
+                })
+            } else {
+                (width, 0, |_, _| " ".to_string())
+            };
+
+        rows.chunks(cells_per_line)
+            .take(height)
+            .map(|line| {
+                let mut spans = Vec::with_capacity(line.len() * 2);
+                for (itx, row) in line.iter().enumerate() {
+                    if itx > 0 && gap > 0 {
+                        spans.push(Span::raw(" ".repeat(gap)));
+                    }
+                    spans.push(Span::styled(
+                        cell_text(row, label_width),
+                        Style::default()
+                            .fg(Color::Black)
+                            .bg(self.cpu_heatmap_colour(row.current)),
+                    ));
+                }
+
+                Spans::from(spans)
+            })
+            .collect()
+    }
+}
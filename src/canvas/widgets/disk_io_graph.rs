@@ -0,0 +1,182 @@
+use std::borrow::Cow;
+
+use crate::{
+    app::{data_harvester::disks::io_device_name, widgets::DiskIoGraphMode, App},
+    canvas::{drawing_utils::should_hide_x_label, Painter},
+    components::time_graph::{GraphData, TimeGraph},
+    data_conversion::{convert_io_points, dec_bytes_per_second_string, rate_string},
+};
+
+use concat_string::concat_string;
+
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Rect},
+    terminal::Frame,
+};
+
+impl Painter {
+    pub fn draw_disk_io_graph<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect, widget_id: u64,
+    ) {
+        // Follow the entry selected in the first disk table, falling back to the first disk if
+        // there is no table.
+        let selected_disk = app_state
+            .disk_state
+            .widget_states
+            .iter()
+            .min_by_key(|(id, _)| **id)
+            .and_then(|(_, disk_widget_state)| disk_widget_state.table.current_item())
+            .and_then(|disk| io_device_name(&disk.name).map(|name| name.to_string()));
+
+        if let Some(disk_io_widget_state) =
+            app_state.disk_io_state.widget_states.get_mut(&widget_id)
+        {
+            let border_style = self.get_border_style(widget_id, app_state.current_widget.widget_id);
+            let x_bounds = [0, disk_io_widget_state.current_display_time];
+            let hide_x_labels = should_hide_x_label(
+                app_state.app_config_fields.hide_time,
+                app_state.app_config_fields.autohide_time,
+                &mut disk_io_widget_state.autohide_timer,
+                draw_loc,
+            );
+
+            let data_collection = app_state.frozen_state.data(&app_state.data_collection);
+            let device = selected_disk.or_else(|| {
+                data_collection
+                    .disk_harvest
+                    .first()
+                    .and_then(|disk| io_device_name(&disk.name).map(|name| name.to_string()))
+            });
+            let graph_mode = disk_io_widget_state.graph_mode;
+            let io_points = device
+                .as_ref()
+                .map(|device| convert_io_points(data_collection, device, graph_mode))
+                .unwrap_or_default();
+            let activity = device
+                .as_ref()
+                .and_then(|device| data_collection.timed_data_vec.last()?.1.io_data.get(device))
+                .and_then(|rate| rate.activity);
+
+            let series_formats: [(&str, fn(f64) -> String); 2] = match graph_mode {
+                DiskIoGraphMode::Throughput => [("R", bytes_rate_string), ("W", bytes_rate_string)],
+                DiskIoGraphMode::Operations => [("R", rate_string), ("W", rate_string)],
+                DiskIoGraphMode::Latency => {
+                    [("R", milliseconds_string), ("W", milliseconds_string)]
+                }
+                DiskIoGraphMode::Queue => [
+                    ("Queue", |value| format!("{:.2}", value)),
+                    ("In flight", |value| format!("{:.0}", value)),
+                ],
+                DiskIoGraphMode::Utilisation => [("Util", percent_string), ("", percent_string)],
+            };
+
+            // Merged operations are listed alongside the operations that they were merged into.
+            let merged = match (graph_mode, activity) {
+                (DiskIoGraphMode::Operations, Some(activity)) => {
+                    [Some(activity.reads_merged), Some(activity.writes_merged)]
+                }
+                _ => [None, None],
+            };
+
+            let name_width = series_formats
+                .iter()
+                .map(|(name, _)| name.len())
+                .max()
+                .unwrap_or(0);
+            let points = io_points
+                .iter()
+                .zip(&series_formats)
+                .zip(&merged)
+                .zip([self.colours.rx_style, self.colours.tx_style].iter())
+                .filter(|(((series, _), _), _)| !series.is_empty())
+                .map(|(((series, (name, format)), merged), style)| {
+                    let current_value = series.last().map(|(_, value)| *value).unwrap_or(0.0);
+                    let mut label = format!(
+                        "{:<width$}  {}",
+                        name,
+                        format(current_value),
+                        width = name_width
+                    );
+                    if let Some(merged) = merged {
+                        label.push_str(&format!("  ({} merged)", rate_string(*merged)));
+                    }
+
+                    GraphData {
+                        points: &series[..],
+                        style: *style,
+                        name: Some(label.into()),
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            // Utilisation is always out of 100%, while everything else is scaled to the highest
+            // value within the data, rounded up.
+            let (y_bounds, y_labels) = if graph_mode == DiskIoGraphMode::Utilisation {
+                ([0.0, 100.5], [Cow::Borrowed("  0%"), Cow::Borrowed("100%")])
+            } else {
+                let max_value = io_points
+                    .iter()
+                    .flatten()
+                    .map(|(_, value)| *value)
+                    .fold(1.0, f64::max)
+                    .ceil();
+                let format = series_formats[0].1;
+                let max_label = format(max_value);
+                let min_label = format!("{:>width$}", format(0.0), width = max_label.len());
+                (
+                    [0.0, max_value * 1.005],
+                    [Cow::Owned(min_label), Cow::Owned(max_label)],
+                )
+            };
+
+            let title = match &device {
+                Some(device) => format!(" Disk I/O ─ {} ", device),
+                None => " Disk I/O ".to_string(),
+            };
+            let title = match graph_mode {
+                DiskIoGraphMode::Throughput => title,
+                DiskIoGraphMode::Operations => concat_string!(title, "─ Operations "),
+                DiskIoGraphMode::Latency => concat_string!(title, "─ Latency "),
+                DiskIoGraphMode::Queue => concat_string!(title, "─ Queue "),
+                DiskIoGraphMode::Utilisation => concat_string!(title, "─ Utilisation "),
+            };
+
+            TimeGraph {
+                use_dot: app_state.app_config_fields.use_dot,
+                x_bounds,
+                hide_x_labels,
+                y_bounds,
+                y_labels: &y_labels,
+                graph_style: self.colours.graph_style,
+                border_style,
+                title: title.into(),
+                is_expanded: app_state.is_expanded,
+                title_style: self.colours.widget_title_style,
+                legend_constraints: Some((Constraint::Ratio(3, 4), Constraint::Ratio(3, 4))),
+            }
+            .draw_time_graph(f, draw_loc, &points);
+        }
+
+        if app_state.should_get_widget_bounds() {
+            // Update draw loc in widget map
+            if let Some(widget) = app_state.widget_map.get_mut(&widget_id) {
+                widget.top_left_corner = Some((draw_loc.x, draw_loc.y));
+                widget.bottom_right_corner =
+                    Some((draw_loc.x + draw_loc.width, draw_loc.y + draw_loc.height));
+            }
+        }
+    }
+}
+
+fn bytes_rate_string(bytes_per_second: f64) -> String {
+    dec_bytes_per_second_string(bytes_per_second.round() as u64)
+}
+
+fn milliseconds_string(milliseconds: f64) -> String {
+    format!("{:.1}ms", milliseconds)
+}
+
+fn percent_string(percent: f64) -> String {
+    format!("{:.0}%", percent)
+}
@@ -21,10 +21,16 @@ impl Painter {
                 selection_state: SelectionState::new(app_state.is_expanded, is_on_widget),
             };
 
+            let disk_data = if disk_widget_state.is_tree_mode {
+                &app_state.converted_data.disk_tree_data
+            } else {
+                &app_state.converted_data.disk_data
+            };
+
             disk_widget_state.table.draw(
                 f,
                 &draw_info,
-                app_state.converted_data.disk_data.clone(),
+                disk_data.clone(),
                 app_state.widget_map.get_mut(&widget_id),
                 self,
             );
@@ -0,0 +1,152 @@
+use crate::{
+    app::{data_harvester::kernel::KernelCounter, App},
+    canvas::Painter,
+    data_conversion::{convert_kernel_activity_data, rate_string, KernelActivityRow},
+};
+
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    terminal::Frame,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph},
+};
+use unicode_segmentation::UnicodeSegmentation;
+
+const NAME_WIDTH: usize = 12;
+const VALUE_WIDTH: usize = 8;
+const SPARKLINE_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+impl Painter {
+    pub fn draw_kernel_activity<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect, widget_id: u64,
+    ) {
+        if let Some(kernel_widget_state) = app_state.kernel_state.widget_states.get(&widget_id) {
+            let border_style = self.get_border_style(widget_id, app_state.current_widget.widget_id);
+            let title = format!(
+                " Kernel Activity ─ {}s ",
+                kernel_widget_state.current_display_time / 1000
+            );
+            let title = if app_state.is_expanded {
+                let title_base = format!("{}── Esc to go back ", title);
+                Spans::from(vec![
+                    Span::styled(title, self.colours.widget_title_style),
+                    Span::styled(
+                        format!(
+                            "─{}─ Esc to go back ",
+                            "─".repeat(usize::from(draw_loc.width).saturating_sub(
+                                UnicodeSegmentation::graphemes(title_base.as_str(), true).count()
+                                    + 2
+                            ))
+                        ),
+                        border_style,
+                    ),
+                ])
+            } else {
+                Spans::from(Span::styled(title, self.colours.widget_title_style))
+            };
+
+            let block = Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(border_style);
+            let width = usize::from(block.inner(draw_loc).width);
+
+            // Drop the average and maximum columns first, then the sparkline, if there isn't room.
+            let show_stats = width >= NAME_WIDTH + 3 * (VALUE_WIDTH + 1) + 11;
+            let num_columns = if show_stats { 3 } else { 1 };
+            let num_buckets =
+                width.saturating_sub(NAME_WIDTH + num_columns * (VALUE_WIDTH + 1) + 1);
+
+            let data = app_state.frozen_state.data(&app_state.data_collection);
+            let rows = convert_kernel_activity_data(
+                data,
+                kernel_widget_state.current_display_time,
+                num_buckets,
+            );
+
+            let header = if show_stats {
+                vec!["Now", "Avg", "Max"]
+            } else {
+                vec!["Now"]
+            };
+            let mut lines = vec![Spans::from(Span::styled(
+                header.iter().fold(" ".repeat(NAME_WIDTH), |line, column| {
+                    format!("{} {:>width$}", line, column, width = VALUE_WIDTH)
+                }),
+                self.colours.table_header_style,
+            ))];
+            lines.extend(
+                rows.iter()
+                    .enumerate()
+                    .map(|(itx, row)| self.kernel_activity_line(itx, row, show_stats)),
+            );
+
+            f.render_widget(Paragraph::new(lines).block(block), draw_loc);
+        }
+
+        if app_state.should_get_widget_bounds() {
+            // Update draw loc in widget map
+            if let Some(widget) = app_state.widget_map.get_mut(&widget_id) {
+                widget.top_left_corner = Some((draw_loc.x, draw_loc.y));
+                widget.bottom_right_corner =
+                    Some((draw_loc.x + draw_loc.width, draw_loc.y + draw_loc.height));
+            }
+        }
+    }
+
+    /// Draws the values of a counter followed by a sparkline of its history, scaled to its highest
+    /// value within the display time.
+    fn kernel_activity_line(
+        &self, itx: usize, row: &KernelActivityRow, show_stats: bool,
+    ) -> Spans<'_> {
+        let style = self.colours.cpu_colour_styles[itx % self.colours.cpu_colour_styles.len()];
+
+        let mut text = format!("{:<width$}", row.counter.name(), width = NAME_WIDTH);
+        let values = if show_stats {
+            vec![row.current, Some(row.average), Some(row.max)]
+        } else {
+            vec![row.current]
+        };
+        for value in values {
+            let value = value
+                .map(|value| kernel_value_string(row.counter, value))
+                .unwrap_or_else(|| "N/A".to_string());
+            text.push_str(&format!(" {:>width$}", value, width = VALUE_WIDTH));
+        }
+
+        let sparkline = row
+            .buckets
+            .iter()
+            .map(|bucket| match bucket {
+                Some(value) if row.max > 0.0 => {
+                    let level = (value / row.max * (SPARKLINE_CHARS.len() - 1) as f64).round();
+                    SPARKLINE_CHARS[(level as usize).min(SPARKLINE_CHARS.len() - 1)]
+                }
+                Some(_) => SPARKLINE_CHARS[0],
+                None => ' ',
+            })
+            .collect::<String>();
+
+        if sparkline.is_empty() {
+            Spans::from(Span::styled(text, style))
+        } else {
+            Spans::from(vec![
+                Span::styled(text, style),
+                Span::raw(" "),
+                Span::styled(sparkline, style),
+            ])
+        }
+    }
+}
+
+/// Returns a short string for a counter's value, such as "12.3K/s" for rates or "4" for counts.
+fn kernel_value_string(counter: KernelCounter, value: f64) -> String {
+    if counter.is_rate() {
+        rate_string(value)
+    } else if value.fract() == 0.0 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.1}", value)
+    }
+}
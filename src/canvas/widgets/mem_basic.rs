@@ -146,6 +146,77 @@ impl Painter {
             }
         }
 
+        if let Some(numa_data) = &app_state.converted_data.numa_data {
+            let numa_styles = &self.colours.numa_colour_styles;
+            for (itx, node) in numa_data.iter().enumerate() {
+                let node_percentage = node.points.last().map(|point| point.1).unwrap_or(0.0);
+                let node_fraction_label = if app_state.basic_mode_use_percent {
+                    format!("{:3.0}%", node_percentage.round())
+                } else {
+                    node.mem_total.trim().to_string()
+                };
+                let style = numa_styles
+                    .get(itx % numa_styles.len().max(1))
+                    .copied()
+                    .unwrap_or_default();
+                draw_widgets.push(
+                    PipeGauge::default()
+                        .ratio(node_percentage / 100.0)
+                        .start_label(node.name.clone())
+                        .inner_label(node_fraction_label)
+                        .label_style(style)
+                        .gauge_style(style),
+                );
+            }
+        }
+
+        if let Some(compressed_data) = &app_state.converted_data.compressed_data {
+            let compressed_styles = &self.colours.compressed_colour_styles;
+            for (itx, compressed) in compressed_data.iter().enumerate() {
+                let compressed_percentage =
+                    compressed.points.last().map(|point| point.1).unwrap_or(0.0);
+                let compressed_fraction_label = if app_state.basic_mode_use_percent {
+                    format!("{:3.0}%", compressed_percentage.round())
+                } else {
+                    compressed.mem_total.trim().to_string()
+                };
+                let style = compressed_styles
+                    .get(itx % compressed_styles.len().max(1))
+                    .copied()
+                    .unwrap_or_default();
+                draw_widgets.push(
+                    PipeGauge::default()
+                        .ratio(compressed_percentage / 100.0)
+                        .start_label(compressed.name.clone())
+                        .inner_label(compressed_fraction_label)
+                        .label_style(style)
+                        .gauge_style(style),
+                );
+            }
+        }
+
+        if let Some((_, label_frac)) = &app_state.converted_data.huge_pages_labels {
+            let huge_pages_percentage = app_state
+                .converted_data
+                .huge_pages_data
+                .last()
+                .map(|point| point.1)
+                .unwrap_or(0.0);
+            let huge_pages_fraction_label = if app_state.basic_mode_use_percent {
+                format!("{:3.0}%", huge_pages_percentage.round())
+            } else {
+                label_frac.trim().to_string()
+            };
+            draw_widgets.push(
+                PipeGauge::default()
+                    .ratio(huge_pages_percentage / 100.0)
+                    .start_label("HUGE")
+                    .inner_label(huge_pages_fraction_label)
+                    .label_style(self.colours.huge_pages_style)
+                    .gauge_style(self.colours.huge_pages_style),
+            );
+        }
+
         let margined_loc = Layout::default()
             .constraints(vec![Constraint::Length(1); draw_widgets.len()])
             .direction(Direction::Vertical)
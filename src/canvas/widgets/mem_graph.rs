@@ -1,11 +1,25 @@
 use std::borrow::Cow;
 
 use crate::{
-    app::App,
+    app::{
+        data_harvester::memory::{
+            arc::{ArcActivity, ArcHarvest},
+            MemBreakdown,
+        },
+        widgets::MemGraphMode,
+        App,
+    },
     canvas::{drawing_utils::should_hide_x_label, Painter},
-    components::time_graph::{GraphData, TimeGraph},
+    components::{
+        time_graph::{GraphData, TimeGraph},
+        tui_widget::time_chart::Point,
+    },
+    data_conversion::{convert_mem_breakdown_points, convert_paging_points, rate_string},
+    utils::gen_util::get_binary_bytes,
 };
 
+use concat_string::concat_string;
+
 use tui::{
     backend::Backend,
     layout::{Constraint, Rect},
@@ -28,7 +42,63 @@ impl Painter {
                 &mut mem_widget_state.autohide_timer,
                 draw_loc,
             );
-            let points = {
+            let data = app_state.frozen_state.data(&app_state.data_collection);
+
+            // Fall back to showing usage if there's nothing to show for the current mode.
+            let breakdown = if mem_widget_state.graph_mode == MemGraphMode::Breakdown {
+                data.memory_harvest
+                    .breakdown
+                    .filter(|breakdown| breakdown.total > 0)
+                    .map(|breakdown| (breakdown, convert_mem_breakdown_points(data)))
+            } else {
+                None
+            };
+            let paging_points = if mem_widget_state.graph_mode == MemGraphMode::Paging
+                && data.paging_harvest.is_some()
+            {
+                Some(convert_paging_points(data))
+            } else {
+                None
+            };
+            // ARC details are only collected with ZFS support.
+            let arc_stats: Option<(ArcHarvest, Option<ArcActivity>, [Vec<Point>; 4])> = {
+                #[cfg(feature = "zfs")]
+                {
+                    if mem_widget_state.graph_mode == MemGraphMode::Arc {
+                        data.arc_stats_harvest.map(|arc_stats| {
+                            let activity = data
+                                .timed_data_vec
+                                .last()
+                                .and_then(|(_, timed_data)| timed_data.arc_stats_data);
+                            (
+                                arc_stats,
+                                activity,
+                                crate::data_conversion::convert_arc_stats_points(data),
+                            )
+                        })
+                    } else {
+                        None
+                    }
+                }
+                #[cfg(not(feature = "zfs"))]
+                {
+                    None
+                }
+            };
+
+            let points = if let Some((breakdown, breakdown_points)) = &breakdown {
+                self.mem_breakdown_graph_data(breakdown, breakdown_points)
+            } else if let Some(paging_points) = &paging_points {
+                let faults = data
+                    .timed_data_vec
+                    .last()
+                    .and_then(|(_, timed_data)| timed_data.paging_data)
+                    .map(|paging| paging.faults)
+                    .unwrap_or(0.0);
+                self.paging_graph_data(paging_points, faults)
+            } else if let Some((arc_stats, activity, arc_points)) = &arc_stats {
+                self.arc_stats_graph_data(arc_stats, activity.as_ref(), arc_points)
+            } else {
                 let mut size = 1;
                 if app_state.converted_data.swap_labels.is_some() {
                     size += 1; // add capacity for SWAP
@@ -45,6 +115,15 @@ impl Painter {
                         size += gpu_data.len(); // add row(s) for gpu
                     }
                 }
+                if let Some(numa_data) = &app_state.converted_data.numa_data {
+                    size += numa_data.len(); // add row(s) for NUMA nodes
+                }
+                if let Some(compressed_data) = &app_state.converted_data.compressed_data {
+                    size += compressed_data.len(); // add row(s) for compressed memory
+                }
+                if app_state.converted_data.huge_pages_labels.is_some() {
+                    size += 1; // add capacity for huge pages
+                }
 
                 let mut points = Vec::with_capacity(size);
                 if let Some((label_percent, label_frac)) = &app_state.converted_data.mem_labels {
@@ -100,19 +179,100 @@ impl Painter {
                         });
                     }
                 }
+                if let Some(numa_data) = &app_state.converted_data.numa_data {
+                    let numa_styles = &self.colours.numa_colour_styles;
+                    for (itx, node) in numa_data.iter().enumerate() {
+                        let node_label =
+                            format!("{}:{}{}", node.name, node.mem_percent, node.mem_total);
+                        points.push(GraphData {
+                            points: node.points.as_slice(),
+                            style: numa_styles
+                                .get(itx % numa_styles.len().max(1))
+                                .copied()
+                                .unwrap_or_default(),
+                            name: Some(node_label.into()),
+                        });
+                    }
+                }
+                if let Some(compressed_data) = &app_state.converted_data.compressed_data {
+                    let compressed_styles = &self.colours.compressed_colour_styles;
+                    for (itx, compressed) in compressed_data.iter().enumerate() {
+                        let compressed_label = format!(
+                            "{}:{}{}",
+                            compressed.name, compressed.mem_percent, compressed.mem_total
+                        );
+                        points.push(GraphData {
+                            points: compressed.points.as_slice(),
+                            style: compressed_styles
+                                .get(itx % compressed_styles.len().max(1))
+                                .copied()
+                                .unwrap_or_default(),
+                            name: Some(compressed_label.into()),
+                        });
+                    }
+                }
+                if let Some((label_percent, label_frac)) =
+                    &app_state.converted_data.huge_pages_labels
+                {
+                    let huge_pages_label = format!("HUGE:{}{}", label_percent, label_frac);
+                    points.push(GraphData {
+                        points: &app_state.converted_data.huge_pages_data,
+                        style: self.colours.huge_pages_style,
+                        name: Some(huge_pages_label.into()),
+                    });
+                }
 
                 points
             };
 
+            // The number of processes killed for running out of memory since boot, if known.
+            let title = match data.paging_harvest.and_then(|paging| paging.oom_kills) {
+                Some(oom_kills) => format!(" Memory ─ OOM kills: {} ", oom_kills),
+                None => " Memory ".to_string(),
+            };
+            let title = if data.cgroup_harvest.is_some() {
+                concat_string!(title, "─ Container ")
+            } else {
+                title
+            };
+            let title = if breakdown.is_some() {
+                concat_string!(title, "─ Breakdown ")
+            } else if paging_points.is_some() {
+                concat_string!(title, "─ Paging ")
+            } else if arc_stats.is_some() {
+                concat_string!(title, "─ ARC ")
+            } else {
+                title
+            };
+
+            // Paging rates are scaled to the highest rate within the data, rounded up.
+            let (y_bounds, y_labels) = if let Some(paging_points) = &paging_points {
+                let max_rate = paging_points
+                    .iter()
+                    .flatten()
+                    .map(|(_, value)| *value)
+                    .fold(1.0, f64::max)
+                    .ceil();
+
+                let max_label = rate_string(max_rate);
+                let min_label = format!("{:>width$}", "0", width = max_label.len());
+                (
+                    [0.0, max_rate * 1.005],
+                    vec![Cow::Owned(min_label), Cow::Owned(max_label)],
+                )
+            } else {
+                (Y_BOUNDS, Y_LABELS.to_vec())
+            };
+
             TimeGraph {
                 use_dot: app_state.app_config_fields.use_dot,
                 x_bounds,
                 hide_x_labels,
-                y_bounds: Y_BOUNDS,
-                y_labels: &Y_LABELS,
+                y_bounds,
+                y_labels: &y_labels,
                 graph_style: self.colours.graph_style,
                 border_style,
-                title: " Memory ".into(),
+                title: title.into(),
                 is_expanded: app_state.is_expanded,
                 title_style: self.colours.widget_title_style,
                 legend_constraints: Some((Constraint::Ratio(3, 4), Constraint::Ratio(3, 4))),
@@ -129,4 +289,191 @@ impl Painter {
             }
         }
     }
+
+    /// Returns the stacked lines for each part of the RAM breakdown, followed by legend entries for
+    /// the values that overlap with them.
+    fn mem_breakdown_graph_data<'a>(
+        &self, breakdown: &MemBreakdown, points: &'a [Vec<Point>; 5],
+    ) -> Vec<GraphData<'a>> {
+        let label = |name: &str, kib: u64| {
+            let (value, unit) = get_binary_bytes(kib * 1024);
+            format!(
+                "{:<10}{:>3.0}%   {:.1}{}",
+                name,
+                kib as f64 / breakdown.total as f64 * 100.0,
+                value,
+                unit
+            )
+        };
+        let style =
+            |itx: usize| self.colours.cpu_colour_styles[itx % self.colours.cpu_colour_styles.len()];
+
+        let mut graph_data = vec![
+            GraphData {
+                points: &points[0],
+                style: self.colours.ram_style,
+                name: Some(label("Used", breakdown.used).into()),
+            },
+            GraphData {
+                points: &points[1],
+                style: style(0),
+                name: Some(label("Buffers", breakdown.buffers).into()),
+            },
+            GraphData {
+                points: &points[2],
+                style: style(1),
+                name: Some(label("Cache", breakdown.cache).into()),
+            },
+            GraphData {
+                points: &points[3],
+                style: style(2),
+                name: Some(label("Slab", breakdown.slab_reclaimable).into()),
+            },
+            GraphData {
+                points: &points[4],
+                style: style(3),
+                name: Some(label("Available", breakdown.available).into()),
+            },
+        ];
+        graph_data.extend(
+            [
+                ("Shared", breakdown.shared),
+                ("Dirty", breakdown.dirty),
+                ("Writeback", breakdown.writeback),
+            ]
+            .iter()
+            .map(|&(name, kib)| GraphData {
+                points: &[],
+                style: self.colours.text_style,
+                name: Some(label(name, kib).into()),
+            }),
+        );
+
+        graph_data
+    }
+
+    /// Returns the lines for the swap-in, swap-out, and major fault rates, followed by a legend
+    /// entry for the rate of all page faults, which would dwarf the others if it were drawn.
+    fn paging_graph_data<'a>(
+        &self, points: &'a [Vec<Point>; 3], faults: f64,
+    ) -> Vec<GraphData<'a>> {
+        const PAGING_NAMES: [&str; 3] = ["Swap in", "Swap out", "Major faults"];
+
+        let mut graph_data = PAGING_NAMES
+            .iter()
+            .zip(points)
+            .enumerate()
+            .map(|(itx, (name, series))| {
+                let current_value = series.last().map(|(_, value)| *value).unwrap_or(0.0);
+
+                GraphData {
+                    points: &series[..],
+                    style: self.colours.cpu_colour_styles
+                        [itx % self.colours.cpu_colour_styles.len()],
+                    name: Some(format!("{:<13}{:>9}", name, rate_string(current_value)).into()),
+                }
+            })
+            .collect::<Vec<_>>();
+        graph_data.push(GraphData {
+            points: &[],
+            style: self.colours.text_style,
+            name: Some(format!("{:<13}{:>9}", "Faults", rate_string(faults)).into()),
+        });
+
+        graph_data
+    }
+
+    /// Returns the lines for the ARC's hit ratios and the sizes of its MRU and MFU lists, followed
+    /// by legend entries for the hit and miss rates and the ARC and L2ARC sizes.  The L2ARC is
+    /// skipped if there are no cache devices.
+    fn arc_stats_graph_data<'a>(
+        &self, arc_stats: &ArcHarvest, activity: Option<&ArcActivity>, points: &'a [Vec<Point>; 4],
+    ) -> Vec<GraphData<'a>> {
+        let size_string = |kib: u64| {
+            let (value, unit) = get_binary_bytes(kib * 1024);
+            format!("{:.1}{}", value, unit)
+        };
+        let percent_string = |percent: Option<f64>| match percent {
+            Some(percent) => format!("{:3.0}%", percent),
+            None => "   -".to_string(),
+        };
+        let style =
+            |itx: usize| self.colours.cpu_colour_styles[itx % self.colours.cpu_colour_styles.len()];
+
+        let mut graph_data = vec![GraphData {
+            points: &points[0],
+            style: self.colours.arc_style,
+            name: Some(
+                format!(
+                    "{:<13}{}",
+                    "Hit ratio",
+                    percent_string(activity.and_then(|activity| activity.hit_ratio))
+                )
+                .into(),
+            ),
+        }];
+        if arc_stats.l2_size_in_kib > 0 {
+            graph_data.push(GraphData {
+                points: &points[1],
+                style: style(0),
+                name: Some(
+                    format!(
+                        "{:<13}{}",
+                        "L2 hit ratio",
+                        percent_string(activity.and_then(|activity| activity.l2_hit_ratio))
+                    )
+                    .into(),
+                ),
+            });
+        }
+        graph_data.extend(
+            [
+                ("MRU", &points[2], arc_stats.mru_in_kib, style(1)),
+                ("MFU", &points[3], arc_stats.mfu_in_kib, style(2)),
+            ]
+            .iter()
+            .map(|&(name, series, kib, style)| GraphData {
+                points: &series[..],
+                style,
+                name: Some(
+                    format!(
+                        "{:<13}{}   {}",
+                        name,
+                        percent_string(series.last().map(|(_, value)| *value)),
+                        size_string(kib)
+                    )
+                    .into(),
+                ),
+            }),
+        );
+
+        let mut legend = vec![
+            (
+                "Hits",
+                rate_string(activity.map(|activity| activity.hits).unwrap_or(0.0)),
+            ),
+            (
+                "Misses",
+                rate_string(activity.map(|activity| activity.misses).unwrap_or(0.0)),
+            ),
+            (
+                "Size",
+                format!(
+                    "{}/{}",
+                    size_string(arc_stats.size_in_kib),
+                    size_string(arc_stats.max_size_in_kib)
+                ),
+            ),
+        ];
+        if arc_stats.l2_size_in_kib > 0 {
+            legend.push(("L2 size", size_string(arc_stats.l2_size_in_kib)));
+        }
+        graph_data.extend(legend.into_iter().map(|(name, value)| GraphData {
+            points: &[],
+            style: self.colours.text_style,
+            name: Some(format!("{:<13}{}", name, value).into()),
+        }));
+
+        graph_data
+    }
 }
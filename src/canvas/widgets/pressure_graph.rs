@@ -0,0 +1,107 @@
+use std::borrow::Cow;
+
+use crate::{
+    app::{data_harvester::pressure::PressureResource, App},
+    canvas::{drawing_utils::should_hide_x_label, Painter},
+    components::time_graph::{GraphData, TimeGraph},
+    data_conversion::convert_pressure_points,
+};
+
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Rect},
+    terminal::Frame,
+};
+
+impl Painter {
+    pub fn draw_pressure_graph<B: Backend>(
+        &self, f: &mut Frame<'_, B>, app_state: &mut App, draw_loc: Rect, widget_id: u64,
+    ) {
+        const Y_BOUNDS: [f64; 2] = [0.0, 100.5];
+        const Y_LABELS: [Cow<'static, str>; 2] = [Cow::Borrowed("  0%"), Cow::Borrowed("100%")];
+
+        if let Some(pressure_widget_state) =
+            app_state.pressure_state.widget_states.get_mut(&widget_id)
+        {
+            let border_style = self.get_border_style(widget_id, app_state.current_widget.widget_id);
+            let x_bounds = [0, pressure_widget_state.current_display_time];
+            let hide_x_labels = should_hide_x_label(
+                app_state.app_config_fields.hide_time,
+                app_state.app_config_fields.autohide_time,
+                &mut pressure_widget_state.autohide_timer,
+                draw_loc,
+            );
+
+            let show_full = pressure_widget_state.show_full;
+            let data_collection = app_state.frozen_state.data(&app_state.data_collection);
+            let pressure_points = convert_pressure_points(data_collection, show_full);
+
+            // Each entry shows the latest stall percentage, followed by the kernel's 10, 60, and
+            // 300 second averages.
+            let points = PressureResource::ALL
+                .iter()
+                .zip(&pressure_points)
+                .enumerate()
+                .filter_map(|(itx, (resource, series))| {
+                    let pressure = data_collection
+                        .pressure_harvest
+                        .as_ref()
+                        .and_then(|harvest| harvest.get(*resource))?;
+                    let stats = if show_full {
+                        pressure.full?
+                    } else {
+                        pressure.some
+                    };
+                    let current_value = series.last().map(|(_, value)| *value).unwrap_or(0.0);
+
+                    Some(GraphData {
+                        points: &series[..],
+                        style: self.colours.cpu_colour_styles
+                            [itx % self.colours.cpu_colour_styles.len()],
+                        name: Some(
+                            format!(
+                                "{:<3} {:>5.1}%  {:.2} {:.2} {:.2}",
+                                resource.name(),
+                                current_value,
+                                stats.avg10,
+                                stats.avg60,
+                                stats.avg300
+                            )
+                            .into(),
+                        ),
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            let title = if show_full {
+                " Pressure ─ Full "
+            } else {
+                " Pressure ─ Some "
+            };
+
+            TimeGraph {
+                use_dot: app_state.app_config_fields.use_dot,
+                x_bounds,
+                hide_x_labels,
+                y_bounds: Y_BOUNDS,
+                y_labels: &Y_LABELS,
+                graph_style: self.colours.graph_style,
+                border_style,
+                title: title.into(),
+                is_expanded: app_state.is_expanded,
+                title_style: self.colours.widget_title_style,
+                legend_constraints: Some((Constraint::Ratio(3, 4), Constraint::Ratio(3, 4))),
+            }
+            .draw_time_graph(f, draw_loc, &points);
+        }
+
+        if app_state.should_get_widget_bounds() {
+            // Update draw loc in widget map
+            if let Some(widget) = app_state.widget_map.get_mut(&widget_id) {
+                widget.top_left_corner = Some((draw_loc.x, draw_loc.y));
+                widget.bottom_right_corner =
+                    Some((draw_loc.x + draw_loc.width, draw_loc.y + draw_loc.height));
+            }
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    app::App,
+    app::{query::*, App},
     canvas::{drawing_utils::get_search_start_position, Painter},
     components::data_table::{DrawInfo, SelectionState},
     constants::*,
@@ -8,6 +8,7 @@ use crate::{
 use tui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
     terminal::Frame,
     text::{Span, Spans},
     widgets::{Block, Borders, Paragraph},
@@ -109,8 +110,8 @@ impl Painter {
     ) {
         fn build_query<'a>(
             is_on_widget: bool, grapheme_indices: GraphemeIndices<'a>, start_position: usize,
-            cursor_position: usize, query: &str, currently_selected_text_style: tui::style::Style,
-            text_style: tui::style::Style,
+            cursor_position: usize, query: &str, currently_selected_text_style: Style,
+            style_at: impl Fn(usize) -> Style,
         ) -> Vec<Span<'a>> {
             let mut current_grapheme_pos = 0;
 
@@ -125,7 +126,7 @@ impl Painter {
                             let styled = if grapheme.0 == cursor_position {
                                 Span::styled(grapheme.1, currently_selected_text_style)
                             } else {
-                                Span::styled(grapheme.1, text_style)
+                                Span::styled(grapheme.1, style_at(grapheme.0))
                             };
                             Some(styled)
                         }
@@ -138,10 +139,12 @@ impl Painter {
 
                 res
             } else {
-                // This is easier - we just need to get a range of graphemes, rather than
+                // This is easier - we just need to style each grapheme, rather than
                 // dealing with possibly inserting a cursor (as none is shown!)
 
-                vec![Span::styled(query.to_string(), text_style)]
+                grapheme_indices
+                    .map(|(index, grapheme)| Span::styled(grapheme, style_at(index)))
+                    .collect()
             }
         }
 
@@ -168,6 +171,38 @@ impl Painter {
             let query = proc_widget_state.get_current_search_query().as_str();
             let grapheme_indices = UnicodeSegmentation::grapheme_indices(query, true);
 
+            let highlights = highlight_query(query);
+            let error_range = if proc_widget_state.proc_search.search_state.is_invalid_search {
+                proc_widget_state
+                    .proc_search
+                    .search_state
+                    .error_range
+                    .clone()
+            } else {
+                None
+            };
+            let style_at = |index: usize| {
+                if let Some(error_range) = &error_range {
+                    if error_range.contains(&index) {
+                        return self
+                            .colours
+                            .invalid_query_style
+                            .add_modifier(Modifier::UNDERLINED);
+                    }
+                }
+
+                match highlights
+                    .iter()
+                    .find(|(range, _)| range.contains(&index))
+                    .map(|(_, kind)| kind)
+                {
+                    Some(QueryTokenKind::Prefix) => self.colours.search_prefix_style,
+                    Some(QueryTokenKind::Operator) => self.colours.search_operator_style,
+                    Some(QueryTokenKind::Value) => self.colours.search_value_style,
+                    Some(QueryTokenKind::Name) | None => self.colours.text_style,
+                }
+            };
+
             // TODO: [CURSOR] blank cursor if not selected
             // TODO: [CURSOR] blinking cursor?
             let query_with_cursor = build_query(
@@ -177,7 +212,7 @@ impl Painter {
                 cursor_position,
                 query,
                 self.colours.currently_selected_text_style,
-                self.colours.text_style,
+                style_at,
             );
 
             let mut search_text = vec![Spans::from({
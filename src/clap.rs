@@ -34,8 +34,14 @@ Supported widget names:
 +--------------------------+
 |            cpu           |
 +--------------------------+
+|   cpu_heatmap, heatmap   |
++--------------------------+
 |        mem, memory       |
 +--------------------------+
+|      pressure, psi       |
++--------------------------+
+| kernel, kernel_activity  |
++--------------------------+
 |       net, network       |
 +--------------------------+
 | proc, process, processes |
@@ -44,6 +50,8 @@ Supported widget names:
 +--------------------------+
 |           disk           |
 +--------------------------+
+|       disk_io, io        |
++--------------------------+
 |       batt, battery      |
 +--------------------------+
 "
@@ -67,8 +75,14 @@ Supported widget names:
 +--------------------------+
 |            cpu           |
 +--------------------------+
+|   cpu_heatmap, heatmap   |
++--------------------------+
 |        mem, memory       |
 +--------------------------+
+|      pressure, psi       |
++--------------------------+
+| kernel, kernel_activity  |
++--------------------------+
 |       net, network       |
 +--------------------------+
 | proc, process, processes |
@@ -77,6 +91,8 @@ Supported widget names:
 +--------------------------+
 |           disk           |
 +--------------------------+
+|       disk_io, io        |
++--------------------------+
 "
 };
 
@@ -106,6 +122,16 @@ pub fn build_app() -> Command<'static> {
         .long_help("Sets the temperature type to Celsius. This is the default option.");
 
     // All flags. These are in alphabetical order
+    let all_mounts = Arg::new("all_mounts")
+        .long("all_mounts")
+        .help("Shows virtual and network filesystems in the disk widget.")
+        .long_help(
+            "Shows every mounted filesystem in the disk widget, including virtual filesystems \
+            (such as tmpfs), network filesystems (such as NFS and CIFS), FUSE filesystems, and \
+            bind mounts. Filesystems without any space, such as proc, are still hidden. This can \
+            also be toggled with the 'v' key in the disk widget.",
+        );
+
     let autohide_time = Arg::new("autohide_time")
         .long("autohide_time")
         .help("Temporarily shows the time scale in graphs.")
@@ -129,6 +155,41 @@ pub fn build_app() -> Command<'static> {
         .help("Enables case sensitivity by default.")
         .long_help("When searching for a process, enables case sensitivity by default.");
 
+    let container_limits = Arg::new("container_limits")
+        .long("container_limits")
+        .help("Measures memory and CPU usage against container limits.")
+        .long_help(
+            "Measures memory and CPU usage against the limits of the cgroup that bottom runs in, \
+            such as those of a container. Memory percentages, including the memory usage of \
+            processes, are relative to the memory limit, the CPU widget only shows the cores that \
+            can be used, and the average CPU usage is relative to the CPU quota. This is only \
+            supported on Linux.",
+        );
+
+    let cpu_group_by = Arg::new("cpu_group_by")
+        .long("cpu_group_by")
+        .takes_value(true)
+        .value_name("GROUPING")
+        .possible_values(["socket", "numa", "core_type"])
+        .hide_possible_values(true)
+        .help("Groups CPU cores by socket, NUMA node, or core type, use --help for info.")
+        .long_help(
+            "\
+Groups cores in the CPU widget, with an entry for the combined usage of each
+group followed by its cores. Supported values are:
+
++-------------------------------------------------------------+
+| socket (the physical package each core is on)               |
++-------------------------------------------------------------+
+| numa (the NUMA node each core belongs to)                   |
++-------------------------------------------------------------+
+| core_type (performance and efficiency cores on hybrid CPUs) |
++-------------------------------------------------------------+
+
+This is only supported on Linux.
+",
+        );
+
     let current_usage = Arg::new("current_usage")
         .short('u')
         .long("current_usage")
@@ -267,6 +328,11 @@ Defaults to \"default\".
         .help("Defaults to showing process memory usage by value.")
         .long_help("Defaults to showing process memory usage by value. Otherwise, it defaults to showing it by percentage.");
 
+    let normalize_load_avg = Arg::new("normalize_load_avg")
+        .long("normalize_load_avg")
+        .help("Divides the load average graph by the number of cores.")
+        .long_help("Divides the values in the CPU widget's load average graph by the number of cores, so that 1 means every core is fully loaded.");
+
     let default_time_value = Arg::new("default_time_value")
         .short('t')
         .long("default_time_value")
@@ -309,6 +375,37 @@ use CPU (3) as the default instead.
         .help("Sets the default widget type, use --help for info.")
         .long_help(DEFAULT_WIDGET_TYPE_STR);
 
+    let filter = Arg::new("filter")
+        .long("filter")
+        .takes_value(true)
+        .value_name("NAME")
+        .help("Starts with a saved process filter applied.")
+        .long_help(
+            "Starts with the process filter of the given name applied to the process widget. \
+            Process filters are defined with [[process_filter]] entries in the config file.",
+        );
+
+    let forecast_warning = Arg::new("forecast_warning")
+        .long("forecast_warning")
+        .takes_value(true)
+        .value_name("HOURS")
+        .help("Highlights disks forecast to fill within this many hours.")
+        .long_help(
+            "Highlights disks in the disk widget that are forecast to run out of space within \
+            this many hours. Defaults to 24.",
+        );
+
+    let forecast_window = Arg::new("forecast_window")
+        .long("forecast_window")
+        .takes_value(true)
+        .value_name("MINUTES")
+        .help("How many minutes of usage to forecast disks filling from.")
+        .long_help(
+            "How many minutes of past disk usage the disk widget uses to forecast when each disk \
+            will run out of space. A longer window is steadier, but slower to notice a disk that \
+            has started to fill quickly. Defaults to 60.",
+        );
+
     let rate = Arg::new("rate")
         .short('r')
         .long("rate")
@@ -363,18 +460,24 @@ use CPU (3) as the default instead.
         .arg(fahrenheit)
         .arg(celsius)
         .group(ArgGroup::new("TEMPERATURE_TYPE").args(&["kelvin", "fahrenheit", "celsius"]))
+        .arg(all_mounts)
         .arg(autohide_time)
         .arg(basic)
         .arg(case_sensitive)
         .arg(process_command)
         .arg(config_location)
         .arg(color)
+        .arg(container_limits)
+        .arg(cpu_group_by)
         .arg(mem_as_value)
         .arg(default_time_value)
         .arg(default_widget_count)
         .arg(default_widget_type)
         .arg(disable_click)
         .arg(dot_marker)
+        .arg(filter)
+        .arg(forecast_warning)
+        .arg(forecast_window)
         .arg(group)
         .arg(hide_avg_cpu)
         .arg(hide_table_gap)
@@ -389,6 +492,7 @@ use CPU (3) as the default instead.
         .arg(network_use_bytes)
         .arg(network_use_log)
         .arg(network_use_binary_prefix)
+        .arg(normalize_load_avg)
         .arg(current_usage)
         .arg(use_old_network_legend)
         .arg(whole_word);
@@ -13,6 +13,10 @@ pub const STALE_MIN_MILLISECONDS: u64 = 30 * 1000; // Lowest is 30 seconds
 pub const TIME_CHANGE_MILLISECONDS: u64 = 15 * 1000; // How much to increment each time
 pub const AUTOHIDE_TIMEOUT_MILLISECONDS: u64 = 5000; // 5 seconds to autohide
 
+// How disks filling up are forecast
+pub const DEFAULT_FORECAST_WINDOW_MINUTES: u64 = 60;
+pub const DEFAULT_FORECAST_WARNING_HOURS: u64 = 24;
+
 pub const TICK_RATE_IN_MILLISECONDS: u64 = 200;
 // How fast the screen refreshes
 pub const DEFAULT_REFRESH_RATE_IN_MILLISECONDS: u64 = 1000;
@@ -38,9 +42,13 @@ pub static DEFAULT_LIGHT_MODE_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(||
     selected_text_color: Some("white".to_string()),
     graph_color: Some("black".to_string()),
     disabled_text_color: Some("gray".to_string()),
+    search_prefix_color: Some("blue".to_string()),
+    search_operator_color: Some("magenta".to_string()),
+    search_value_color: Some("green".to_string()),
     ram_color: Some("blue".to_string()),
     swap_color: Some("red".to_string()),
     arc_color: Some("LightBlue".to_string()),
+    huge_pages_color: Some("Magenta".to_string()),
     gpu_core_colors: Some(vec![
         "LightGreen".to_string(),
         "LightCyan".to_string(),
@@ -50,6 +58,20 @@ pub static DEFAULT_LIGHT_MODE_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(||
         "Blue".to_string(),
         "Red".to_string(),
     ]),
+    numa_node_colors: Some(vec![
+        "Cyan".to_string(),
+        "Magenta".to_string(),
+        "Green".to_string(),
+        "Yellow".to_string(),
+        "Blue".to_string(),
+        "Red".to_string(),
+    ]),
+    compressed_colors: Some(vec![
+        "LightMagenta".to_string(),
+        "Yellow".to_string(),
+        "Green".to_string(),
+        "Cyan".to_string(),
+    ]),
     rx_color: Some("blue".to_string()),
     tx_color: Some("red".to_string()),
     rx_total_color: Some("LightBlue".to_string()),
@@ -92,9 +114,18 @@ pub static GRUVBOX_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| ConfigColo
         "#d65d03".to_string(),
         "#af3a03".to_string(),
     ]),
+    cpu_heatmap_colors: Some(vec![
+        "#458588".to_string(),
+        "#689d6a".to_string(),
+        "#98971a".to_string(),
+        "#d79921".to_string(),
+        "#d65d0e".to_string(),
+        "#cc241d".to_string(),
+    ]),
     ram_color: Some("#8ec07c".to_string()),
     swap_color: Some("#fabd2f".to_string()),
     arc_color: Some("#689d6a".to_string()),
+    huge_pages_color: Some("#b16286".to_string()),
     gpu_core_colors: Some(vec![
         "#d79921".to_string(),
         "#458588".to_string(),
@@ -104,6 +135,20 @@ pub static GRUVBOX_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| ConfigColo
         "#cc241d".to_string(),
         "#98971a".to_string(),
     ]),
+    numa_node_colors: Some(vec![
+        "#83a598".to_string(),
+        "#d3869b".to_string(),
+        "#b8bb26".to_string(),
+        "#fe8019".to_string(),
+        "#458588".to_string(),
+        "#fb4934".to_string(),
+    ]),
+    compressed_colors: Some(vec![
+        "#d3869b".to_string(),
+        "#fabd2f".to_string(),
+        "#8ec07c".to_string(),
+        "#83a598".to_string(),
+    ]),
     rx_color: Some("#8ec07c".to_string()),
     tx_color: Some("#fabd2f".to_string()),
     rx_total_color: Some("#689d6a".to_string()),
@@ -119,6 +164,10 @@ pub static GRUVBOX_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| ConfigColo
     high_battery_color: Some("#98971a".to_string()),
     medium_battery_color: Some("#fabd2f".to_string()),
     low_battery_color: Some("#fb4934".to_string()),
+    disk_warning_color: Some("#fb4934".to_string()),
+    search_prefix_color: Some("#83a598".to_string()),
+    search_operator_color: Some("#d3869b".to_string()),
+    search_value_color: Some("#b8bb26".to_string()),
 });
 
 pub static GRUVBOX_LIGHT_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| ConfigColours {
@@ -147,9 +196,18 @@ pub static GRUVBOX_LIGHT_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| Conf
         "#d65d03".to_string(),
         "#af3a03".to_string(),
     ]),
+    cpu_heatmap_colors: Some(vec![
+        "#076678".to_string(),
+        "#427b58".to_string(),
+        "#79740e".to_string(),
+        "#b57614".to_string(),
+        "#af3a03".to_string(),
+        "#9d0006".to_string(),
+    ]),
     ram_color: Some("#427b58".to_string()),
     swap_color: Some("#cc241d".to_string()),
     arc_color: Some("#689d6a".to_string()),
+    huge_pages_color: Some("#8f3f71".to_string()),
     gpu_core_colors: Some(vec![
         "#9d0006".to_string(),
         "#98971a".to_string(),
@@ -159,6 +217,20 @@ pub static GRUVBOX_LIGHT_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| Conf
         "#fe8019".to_string(),
         "#b8bb26".to_string(),
     ]),
+    numa_node_colors: Some(vec![
+        "#076678".to_string(),
+        "#8f3f71".to_string(),
+        "#79740e".to_string(),
+        "#af3a03".to_string(),
+        "#427b58".to_string(),
+        "#9d0006".to_string(),
+    ]),
+    compressed_colors: Some(vec![
+        "#b16286".to_string(),
+        "#b57614".to_string(),
+        "#427b58".to_string(),
+        "#076678".to_string(),
+    ]),
     rx_color: Some("#427b58".to_string()),
     tx_color: Some("#cc241d".to_string()),
     rx_total_color: Some("#689d6a".to_string()),
@@ -174,6 +246,10 @@ pub static GRUVBOX_LIGHT_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| Conf
     high_battery_color: Some("#98971a".to_string()),
     medium_battery_color: Some("#d79921".to_string()),
     low_battery_color: Some("#cc241d".to_string()),
+    disk_warning_color: Some("#cc241d".to_string()),
+    search_prefix_color: Some("#076678".to_string()),
+    search_operator_color: Some("#8f3f71".to_string()),
+    search_value_color: Some("#79740e".to_string()),
 });
 
 pub static NORD_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| ConfigColours {
@@ -190,9 +266,18 @@ pub static NORD_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| ConfigColours
         "#d08770".to_string(),
         "#bf616a".to_string(),
     ]),
+    cpu_heatmap_colors: Some(vec![
+        "#5e81ac".to_string(),
+        "#88c0d0".to_string(),
+        "#a3be8c".to_string(),
+        "#ebcb8b".to_string(),
+        "#d08770".to_string(),
+        "#bf616a".to_string(),
+    ]),
     ram_color: Some("#88c0d0".to_string()),
     swap_color: Some("#d08770".to_string()),
     arc_color: Some("#5e81ac".to_string()),
+    huge_pages_color: Some("#b48ead".to_string()),
     gpu_core_colors: Some(vec![
         "#8fbcbb".to_string(),
         "#81a1c1".to_string(),
@@ -202,6 +287,20 @@ pub static NORD_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| ConfigColours
         "#ebcb8b".to_string(),
         "#bf616a".to_string(),
     ]),
+    numa_node_colors: Some(vec![
+        "#88c0d0".to_string(),
+        "#b48ead".to_string(),
+        "#a3be8c".to_string(),
+        "#d08770".to_string(),
+        "#5e81ac".to_string(),
+        "#bf616a".to_string(),
+    ]),
+    compressed_colors: Some(vec![
+        "#b48ead".to_string(),
+        "#ebcb8b".to_string(),
+        "#a3be8c".to_string(),
+        "#88c0d0".to_string(),
+    ]),
     rx_color: Some("#88c0d0".to_string()),
     tx_color: Some("#d08770".to_string()),
     rx_total_color: Some("#5e81ac".to_string()),
@@ -217,6 +316,10 @@ pub static NORD_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| ConfigColours
     high_battery_color: Some("#a3be8c".to_string()),
     medium_battery_color: Some("#ebcb8b".to_string()),
     low_battery_color: Some("#bf616a".to_string()),
+    disk_warning_color: Some("#bf616a".to_string()),
+    search_prefix_color: Some("#81a1c1".to_string()),
+    search_operator_color: Some("#b48ead".to_string()),
+    search_value_color: Some("#a3be8c".to_string()),
 });
 
 pub static NORD_LIGHT_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| ConfigColours {
@@ -233,9 +336,18 @@ pub static NORD_LIGHT_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| ConfigC
         "#d08770".to_string(),
         "#bf616a".to_string(),
     ]),
+    cpu_heatmap_colors: Some(vec![
+        "#5e81ac".to_string(),
+        "#88c0d0".to_string(),
+        "#a3be8c".to_string(),
+        "#ebcb8b".to_string(),
+        "#d08770".to_string(),
+        "#bf616a".to_string(),
+    ]),
     ram_color: Some("#81a1c1".to_string()),
     swap_color: Some("#d08770".to_string()),
     arc_color: Some("#5e81ac".to_string()),
+    huge_pages_color: Some("#b48ead".to_string()),
     gpu_core_colors: Some(vec![
         "#8fbcbb".to_string(),
         "#88c0d0".to_string(),
@@ -245,6 +357,20 @@ pub static NORD_LIGHT_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| ConfigC
         "#ebcb8b".to_string(),
         "#bf616a".to_string(),
     ]),
+    numa_node_colors: Some(vec![
+        "#5e81ac".to_string(),
+        "#b48ead".to_string(),
+        "#a3be8c".to_string(),
+        "#d08770".to_string(),
+        "#88c0d0".to_string(),
+        "#bf616a".to_string(),
+    ]),
+    compressed_colors: Some(vec![
+        "#b48ead".to_string(),
+        "#d08770".to_string(),
+        "#a3be8c".to_string(),
+        "#5e81ac".to_string(),
+    ]),
     rx_color: Some("#81a1c1".to_string()),
     tx_color: Some("#d08770".to_string()),
     rx_total_color: Some("#5e81ac".to_string()),
@@ -260,10 +386,14 @@ pub static NORD_LIGHT_COLOUR_PALETTE: Lazy<ConfigColours> = Lazy::new(|| ConfigC
     high_battery_color: Some("#a3be8c".to_string()),
     medium_battery_color: Some("#ebcb8b".to_string()),
     low_battery_color: Some("#bf616a".to_string()),
+    disk_warning_color: Some("#bf616a".to_string()),
+    search_prefix_color: Some("#5e81ac".to_string()),
+    search_operator_color: Some("#b48ead".to_string()),
+    search_value_color: Some("#a3be8c".to_string()),
 });
 
 // Help text
-pub const HELP_CONTENTS_TEXT: [&str; 8] = [
+pub const HELP_CONTENTS_TEXT: [&str; 11] = [
     "Either scroll or press the number key to go to the corresponding help menu section:",
     "1 - General",
     "2 - CPU widget",
@@ -272,6 +402,9 @@ pub const HELP_CONTENTS_TEXT: [&str; 8] = [
     "5 - Process sort widget",
     "6 - Battery widget",
     "7 - Basic memory widget",
+    "8 - Pressure widget",
+    "9 - Memory widget",
+    "10 - Disk widgets",
 ];
 
 // TODO [Help]: Search in help?
@@ -311,12 +444,15 @@ pub const GENERAL_HELP_TEXT: [&str; 32] = [
     "Mouse click      Selects the clicked widget, table entry, dialog option, or tab",
 ];
 
-pub const CPU_HELP_TEXT: [&str; 2] = [
+pub const CPU_HELP_TEXT: [&str; 5] = [
     "2 - CPU widget\n",
     "Mouse scroll     Scrolling over an CPU core/average shows only that entry on the chart",
+    "b                Cycle the graph between usage, states, frequency, and load average",
+    "b                In the CPU heatmap, switch between history and current usage",
+    "+, -             Collapse or expand the selected CPU group in the legend",
 ];
 
-pub const PROCESS_HELP_TEXT: [&str; 15] = [
+pub const PROCESS_HELP_TEXT: [&str; 16] = [
     "3 - Process widget",
     "dd, F9           Kill the selected process",
     "c                Sort by CPU usage, press again to reverse sorting order",
@@ -328,13 +464,14 @@ pub const PROCESS_HELP_TEXT: [&str; 15] = [
     "P                Toggle between showing the full command or just the process name",
     "s, F6            Open process sort widget",
     "I                Invert current sort",
+    "F                Cycle through the process filters saved in the config file",
     "%                Toggle between values and percentages for memory usage",
     "t, F5            Toggle tree mode",
     "+, -, click      Collapse/expand a branch while in tree mode",
     "click on header  Sorts the entries by that column, click again to invert the sort",
 ];
 
-pub const SEARCH_HELP_TEXT: [&str; 48] = [
+pub const SEARCH_HELP_TEXT: [&str; 64] = [
     "4 - Process search widget",
     "Esc              Close the search widget (retains the filter)",
     "Ctrl-a           Skip to the start of the search query",
@@ -349,6 +486,9 @@ pub const SEARCH_HELP_TEXT: [&str; 48] = [
     "Alt-r, F3        Toggle using regex",
     "Left, Alt-h      Move cursor left",
     "Right, Alt-l     Move cursor right",
+    "Tab              Complete a prefix, username, or process name",
+    "Up, Down         Browse previous search queries",
+    "Ctrl-r           Search previous queries for the current query",
     "",
     "Supported search types:",
     "<by name/cmd>    ex: btm",
@@ -360,8 +500,13 @@ pub const SEARCH_HELP_TEXT: [&str; 48] = [
     "write, w/s       ex: write <= 1 tb",
     "tread, t.read    ex: tread = 1",
     "twrite, t.write  ex: twrite = 1",
-    "user            ex: user = root",
+    "user             ex: user = root",
     "state            ex: state = running",
+    "ppid             ex: ppid 1",
+    "age              ex: age > 10m",
+    "cputime          ex: cputime >= 1h",
+    "threads          ex: threads > 10",
+    "nice             ex: nice < 0",
     "",
     "Comparison operators:",
     "=                ex: cpu = 1",
@@ -373,6 +518,7 @@ pub const SEARCH_HELP_TEXT: [&str; 48] = [
     "Logical operators:",
     "and, &&, <Space> ex: btm and cpu > 1 and mem > 1",
     "or, ||           ex: btm or firefox",
+    "not, !           ex: not btm and !(user = root)",
     "",
     "Supported units:",
     "B                ex: read > 1 b",
@@ -383,6 +529,13 @@ pub const SEARCH_HELP_TEXT: [&str; 48] = [
     "MiB              ex: read > 1 mib",
     "GiB              ex: read > 1 gib",
     "TiB              ex: read > 1 tib",
+    "ms               ex: age > 500 ms",
+    "s, sec           ex: age > 30s",
+    "m, min           ex: age > 10 m",
+    "h, hr            ex: cputime > 1h",
+    "d, day           ex: age > 2 days",
+    "",
+    "Units can be attached to the value, ex: memb > 100kb",
 ];
 
 pub const SORT_HELP_TEXT: [&str; 6] = [
@@ -405,6 +558,25 @@ pub const BASIC_MEM_HELP_TEXT: [&str; 2] = [
     "%                Toggle between values and percentages for memory usage",
 ];
 
+pub const PRESSURE_HELP_TEXT: [&str; 2] = [
+    "8 - Pressure widget",
+    "b                Switch between the time some or all tasks were stalled",
+];
+
+pub const MEM_HELP_TEXT: [&str; 2] = [
+    "9 - Memory widget",
+    "b                Cycle the graph between usage, a RAM breakdown, paging, and ARC details",
+];
+
+pub const DISK_HELP_TEXT: [&str; 6] = [
+    "10 - Disk widgets",
+    "x                Toggle the IOPS, await, queue, and utilisation columns of the disk table",
+    "i                Toggle the filesystem type, inode, and read-only columns of the disk table",
+    "v                Toggle showing virtual and network filesystems in the disk table",
+    "t, F5            Toggle showing the block devices under each disk as a tree",
+    "b                Cycle the disk I/O graph between throughput, ops, latency, queue, and utilisation",
+];
+
 pub const HELP_TEXT: &[&[&str]] = &[
     &HELP_CONTENTS_TEXT,
     &GENERAL_HELP_TEXT,
@@ -414,6 +586,9 @@ pub const HELP_TEXT: &[&[&str]] = &[
     &SORT_HELP_TEXT,
     &BATTERY_HELP_TEXT,
     &BASIC_MEM_HELP_TEXT,
+    &PRESSURE_HELP_TEXT,
+    &MEM_HELP_TEXT,
+    &DISK_HELP_TEXT,
 ];
 
 // Default layouts
@@ -473,6 +648,7 @@ pub const DEFAULT_BATTERY_LAYOUT: &str = r##"
 
 // Config and flags
 pub const DEFAULT_CONFIG_FILE_PATH: &str = "bottom/bottom.toml";
+pub const SEARCH_HISTORY_FILE_NAME: &str = "search_history";
 
 // TODO: Eventually deprecate this.
 pub const CONFIG_TEXT: &str = r##"# This is a default config file for bottom.  All of the settings are commented
@@ -486,6 +662,16 @@ pub const CONFIG_TEXT: &str = r##"# This is a default config file for bottom.  A
 [flags]
 # Whether to hide the average cpu entry.
 #hide_avg_cpu = false
+# Groups CPU cores by "socket", "numa", or "core_type" (Linux only).
+#cpu_group_by = "socket"
+# Whether to measure memory and CPU usage against container (cgroup) limits (Linux only).
+#container_limits = false
+# Whether to show virtual and network filesystems (such as tmpfs, NFS, and FUSE mounts) in the disk widget.
+#all_mounts = false
+# How many minutes of disk usage to forecast disks filling up from.
+#forecast_window = 60
+# Highlights disks that are forecast to fill up within this many hours.
+#forecast_warning = 24
 # Whether to use dot markers rather than braille.
 #dot_marker = false
 # The update rate of the application.
@@ -532,6 +718,8 @@ pub const CONFIG_TEXT: &str = r##"# This is a default config file for bottom.  A
 #color = "default"
 # Show memory values in the processes widget as values by default
 #mem_as_value = false
+# Divide the load average graph in the CPU widget by the number of cores.
+#normalize_load_avg = false
 # Show tree mode by default in the processes widget.
 #tree = false
 # Shows an indicator in table widgets tracking where in the list you are.
@@ -561,14 +749,22 @@ pub const CONFIG_TEXT: &str = r##"# This is a default config file for bottom.  A
 #avg_cpu_color="Red"
 # Represents the colour the core will use in the CPU legend and graph.
 #cpu_core_colors=["LightMagenta", "LightYellow", "LightCyan", "LightGreen", "LightBlue", "LightRed", "Cyan", "Green", "Blue", "Red"]
+# Represents the colours the CPU heatmap will use, from low to high usage.
+#cpu_heatmap_colors=["Blue", "Cyan", "Green", "Yellow", "LightRed", "Red"]
 # Represents the colour RAM will use in the memory legend and graph.
 #ram_color="LightMagenta"
 # Represents the colour SWAP will use in the memory legend and graph.
 #swap_color="LightYellow"
 # Represents the colour ARC will use in the memory legend and graph.
 #arc_color="LightCyan"
+# Represents the colour huge pages will use in the memory legend and graph.
+#huge_pages_color="LightMagenta"
 # Represents the colour the GPU will use in the memory legend and graph.
 #gpu_core_colors=["LightGreen", "LightBlue", "LightRed", "Cyan", "Green", "Blue", "Red"]
+# Represents the colour of each NUMA node in the memory legend and graph, read in order.
+#numa_node_colors=["LightCyan", "LightYellow", "Magenta", "Yellow", "LightGreen", "Blue"]
+# Represents the colour of each zram device and zswap in the memory legend and graph, read in order.
+#compressed_colors=["LightYellow", "LightGreen", "LightCyan", "LightMagenta"]
 # Represents the colour rx will use in the network legend and graph.
 #rx_color="LightCyan"
 # Represents the colour tx will use in the network legend and graph.
@@ -589,6 +785,12 @@ pub const CONFIG_TEXT: &str = r##"# This is a default config file for bottom.  A
 #high_battery_color="green"
 #medium_battery_color="yellow"
 #low_battery_color="red"
+# Represents the colour of disks that are forecast to fill up soon.
+#disk_warning_color="red"
+# Represents the colours of prefixes, operators, and values in the process search bar.
+#search_prefix_color="LightBlue"
+#search_operator_color="LightMagenta"
+#search_value_color="LightGreen"
 
 # Layout - layouts follow a pattern like this:
 # [[row]] represents a row in the application.
@@ -638,6 +840,13 @@ pub const CONFIG_TEXT: &str = r##"# This is a default config file for bottom.  A
 #case_sensitive = false
 #whole_word = false
 
+#[fs_filter]
+#is_list_ignored = true
+#list = ["squashfs", "overlay"]
+#regex = false
+#case_sensitive = false
+#whole_word = true
+
 #[temp_filter]
 #is_list_ignored = true
 #list = ["cpu", "wifi"]
@@ -651,6 +860,13 @@ pub const CONFIG_TEXT: &str = r##"# This is a default config file for bottom.  A
 #regex = true
 #case_sensitive = false
 #whole_word = false
+
+# Process filters - named process search queries that can be cycled through in the process widget with F,
+# or applied on startup with --filter <NAME>.  default_sort is optional, and uses the same names as the search keywords.
+#[[process_filter]]
+#name = "postgres"
+#query = "user=postgres and not idle"
+#default_sort = "mem"
 "##;
 
 pub const CONFIG_TOP_HEAD: &str = r##"# This is bottom's config file.
@@ -2,17 +2,22 @@
 //! can actually handle.
 
 use crate::app::data_farmer::DataCollection;
-use crate::app::data_harvester::cpu::CpuDataType;
+use crate::app::data_harvester::cpu::{CpuDataType, CpuFrequency, CpuStates};
+use crate::app::data_harvester::disks;
+use crate::app::data_harvester::kernel::KernelCounter;
 use crate::app::{
     data_harvester::temperature::TemperatureType,
-    widgets::{DiskWidgetData, TempWidgetData},
+    widgets::{DiskIoGraphMode, DiskWidgetData, TempWidgetData},
 };
 use crate::components::tui_widget::time_chart::Point;
 use crate::utils::gen_util::*;
 use crate::{app::AxisScaling, units::data_units::DataUnit};
 
+use fxhash::{FxHashMap, FxHashSet};
 use kstring::KString;
 
+use std::time::{Duration, Instant};
+
 #[derive(Default, Debug)]
 pub struct ConvertedBatteryData {
     pub battery_name: String,
@@ -48,6 +53,9 @@ pub enum CpuWidgetData {
         /// A point here represents time (x) and value (y).
         data: Vec<Point>,
         last_entry: f64,
+        frequency: Option<CpuFrequency>,
+        /// Whether this is a group entry whose cores are hidden in the legend.
+        collapsed: bool,
     },
 }
 
@@ -60,14 +68,19 @@ pub struct ConvertedData {
     pub network_data_rx: Vec<Point>,
     pub network_data_tx: Vec<Point>,
     pub disk_data: Vec<DiskWidgetData>,
+    /// The rows of the disk table in tree mode, with each disk under the block devices it is
+    /// built on.
+    pub disk_tree_data: Vec<DiskWidgetData>,
     pub temp_data: Vec<TempWidgetData>,
 
     pub mem_labels: Option<(String, String)>,
     pub swap_labels: Option<(String, String)>,
+    pub huge_pages_labels: Option<(String, String)>,
     #[cfg(feature = "zfs")]
     pub arc_labels: Option<(String, String)>,
     pub mem_data: Vec<Point>, // TODO: Switch this and all data points over to a better data structure...
     pub swap_data: Vec<Point>,
+    pub huge_pages_data: Vec<Point>,
     #[cfg(feature = "zfs")]
     pub arc_data: Vec<Point>,
     pub load_avg_data: [f32; 3],
@@ -75,17 +88,34 @@ pub struct ConvertedData {
     pub battery_data: Vec<ConvertedBatteryData>,
     #[cfg(feature = "gpu")]
     pub gpu_data: Option<Vec<ConvertedGpuData>>,
+    pub numa_data: Option<Vec<ConvertedNumaData>>,
+    pub compressed_data: Option<Vec<ConvertedCompressedData>>,
 }
 
 impl ConvertedData {
     // TODO: Can probably heavily reduce this step to avoid clones.
-    pub fn ingest_disk_data(&mut self, data: &DataCollection) {
+    pub fn ingest_disk_data(
+        &mut self, data: &DataCollection, forecast_window: Duration, forecast_warning: Duration,
+    ) {
         self.disk_data.clear();
 
+        let io_data = data.timed_data_vec.last().map(|(_, data)| &data.io_data);
+
         data.disk_harvest
             .iter()
             .zip(&data.io_labels)
             .for_each(|(disk, (io_read, io_write))| {
+                let io_activity = io_data.and_then(|io_data| {
+                    let rate = io_data.get(disks::io_device_name(&disk.name)?)?;
+                    rate.activity
+                });
+                let time_to_full =
+                    data.free_space_history
+                        .get(&disk.mount_point)
+                        .and_then(|history| {
+                            forecast_time_to_full(history, data.current_instant, forecast_window)
+                        });
+
                 self.disk_data.push(DiskWidgetData {
                     name: KString::from_ref(&disk.name),
                     mount_point: KString::from_ref(&disk.mount_point),
@@ -94,10 +124,23 @@ impl ConvertedData {
                     total_bytes: disk.total_space,
                     io_read: io_read.into(),
                     io_write: io_write.into(),
+                    io_activity,
+                    fs_type: disk.fs_type.as_deref().map(KString::from_ref),
+                    total_inodes: disk.total_inodes,
+                    free_inodes: disk.free_inodes,
+                    read_only: disk.read_only,
+                    prefix: None,
+                    label: None,
+                    kind: None,
+                    time_to_full,
+                    is_filling_soon: time_to_full
+                        .map(|time_to_full| time_to_full <= forecast_warning)
+                        .unwrap_or(false),
                 });
             });
 
         self.disk_data.shrink_to_fit();
+        self.disk_tree_data = convert_disk_tree(&self.disk_data, &data.block_harvest, io_data);
     }
 
     pub fn ingest_temp_data(&mut self, data: &DataCollection, temperature_type: TemperatureType) {
@@ -130,6 +173,8 @@ impl ConvertedData {
                             data_type: data.data_type,
                             data: vec![],
                             last_entry: *cpu_usage,
+                            frequency: data.frequency,
+                            collapsed: false,
                         })
                         .collect::<Vec<CpuWidgetData>>(),
                 );
@@ -137,17 +182,20 @@ impl ConvertedData {
                 self.cpu_data
                     .iter_mut()
                     .skip(1)
-                    .zip(&data.cpu_data)
-                    .for_each(|(mut cpu, cpu_usage)| match &mut cpu {
+                    .zip(data.cpu_data.iter().zip(&current_data.cpu_harvest))
+                    .for_each(|(mut cpu, (cpu_usage, harvest))| match &mut cpu {
                         CpuWidgetData::All => unreachable!(),
                         CpuWidgetData::Entry {
                             data_type: _,
                             data,
                             last_entry,
+                            frequency,
+                            collapsed: _,
                         } => {
                             // A bit faster to just update all the times, so we just clear the vector.
                             data.clear();
                             *last_entry = *cpu_usage;
+                            *frequency = harvest.frequency;
                         }
                     });
             }
@@ -162,6 +210,8 @@ impl ConvertedData {
                     data_type: _,
                     data,
                     last_entry: _,
+                    frequency: _,
+                    collapsed: _,
                 } => {
                     for (time, timed_data) in &current_data.timed_data_vec {
                         let time_start: f64 =
@@ -183,6 +233,575 @@ impl ConvertedData {
     }
 }
 
+/// The groups of CPU states shown in the CPU state breakdown, from the bottom of the stack up.
+pub const CPU_STATE_GROUPS: [(&str, fn(&CpuStates) -> f64); 5] = [
+    ("User", |states| states.user + states.nice),
+    ("System", |states| states.system),
+    ("IRQ", |states| states.irq + states.soft_irq),
+    ("IOWait", |states| states.io_wait),
+    ("Steal", |states| states.steal),
+];
+
+/// Converts the per-state usage of the CPU entry at `index` (in the order it was harvested) into
+/// one series per group in [`CPU_STATE_GROUPS`].  The series are stacked, so each one is the running
+/// total of itself and the groups below it.
+pub fn convert_cpu_state_points(current_data: &DataCollection, index: usize) -> Vec<Vec<Point>> {
+    let mut result: Vec<Vec<Point>> = vec![Vec::new(); CPU_STATE_GROUPS.len()];
+    let current_time = current_data.current_instant;
+
+    for (time, data) in &current_data.timed_data_vec {
+        if let Some(Some(states)) = data.cpu_state_data.get(index) {
+            let time_from_start: f64 =
+                (current_time.duration_since(*time).as_millis() as f64).floor();
+
+            let mut total = 0.0;
+            for ((_, value), series) in CPU_STATE_GROUPS.iter().zip(result.iter_mut()) {
+                total += value(states);
+                series.push((-time_from_start, total));
+            }
+        }
+
+        if *time == current_time {
+            break;
+        }
+    }
+
+    result
+}
+
+/// Converts the frequency (in MHz) of the CPU entry at `index` (in the order it was harvested) into
+/// points for the frequency graph.
+pub fn convert_cpu_frequency_points(current_data: &DataCollection, index: usize) -> Vec<Point> {
+    let mut result: Vec<Point> = Vec::new();
+    let current_time = current_data.current_instant;
+
+    for (time, data) in &current_data.timed_data_vec {
+        if let Some(Some(frequency)) = data.cpu_freq_data.get(index) {
+            let time_from_start: f64 =
+                (current_time.duration_since(*time).as_millis() as f64).floor();
+            result.push((-time_from_start, *frequency));
+        }
+
+        if *time == current_time {
+            break;
+        }
+    }
+
+    result
+}
+
+/// Converts the 1, 5, and 15 minute load averages into one series each, dividing each value by
+/// `divisor` (such as the number of cores, to normalise them).
+pub fn convert_load_avg_points(current_data: &DataCollection, divisor: f64) -> [Vec<Point>; 3] {
+    let mut result: [Vec<Point>; 3] = Default::default();
+    let current_time = current_data.current_instant;
+    let divisor = if divisor > 0.0 { divisor } else { 1.0 };
+
+    for (time, data) in &current_data.timed_data_vec {
+        let time_from_start: f64 = (current_time.duration_since(*time).as_millis() as f64).floor();
+        for (load_avg, series) in data.load_avg_data.iter().zip(result.iter_mut()) {
+            series.push((-time_from_start, f64::from(*load_avg) / divisor));
+        }
+
+        if *time == current_time {
+            break;
+        }
+    }
+
+    result
+}
+
+/// Converts the stall percentages of each pressure resource into one series each, using
+/// the time that every task was stalled if `show_full` is set, or at least one task otherwise.
+pub fn convert_pressure_points(current_data: &DataCollection, show_full: bool) -> [Vec<Point>; 3] {
+    let mut result: [Vec<Point>; 3] = Default::default();
+    let current_time = current_data.current_instant;
+
+    for (time, data) in &current_data.timed_data_vec {
+        let time_from_start: f64 = (current_time.duration_since(*time).as_millis() as f64).floor();
+        for (stall, series) in data.pressure_data.iter().zip(result.iter_mut()) {
+            let value = stall.and_then(|stall| {
+                if show_full {
+                    stall.full
+                } else {
+                    Some(stall.some)
+                }
+            });
+            if let Some(value) = value {
+                series.push((-time_from_start, value));
+            }
+        }
+
+        if *time == current_time {
+            break;
+        }
+    }
+
+    result
+}
+
+/// Arranges the rows of the disk table into a tree of the block devices that they are built on,
+/// like `lsblk`, such as a disk, then a partition, then a LUKS volume, then an LVM volume with a
+/// filesystem mounted on it.  Only devices that lead to a mounted filesystem or a RAID array are
+/// shown, and disks that aren't on any block device (such as tmpfs) are listed after the tree.
+pub fn convert_disk_tree(
+    disk_data: &[DiskWidgetData], block_devices: &[disks::BlockDevice],
+    io_data: Option<&FxHashMap<String, disks::IoRate>>,
+) -> Vec<DiskWidgetData> {
+    const BRANCH_END: char = '└';
+    const BRANCH_VERTICAL: char = '│';
+    const BRANCH_SPLIT: char = '├';
+    const BRANCH_HORIZONTAL: char = '─';
+
+    let devices: FxHashMap<&str, &disks::BlockDevice> = block_devices
+        .iter()
+        .map(|device| (device.name.as_str(), device))
+        .collect();
+
+    // Disks are usually named after the kernel's name for their device, but may use the name
+    // that device-mapper gave it instead.
+    let mut mounts: FxHashMap<&str, Vec<&DiskWidgetData>> = FxHashMap::default();
+    let mut unattached = vec![];
+    for disk in disk_data {
+        let device = disks::io_device_name(&disk.name).and_then(|name| {
+            if devices.contains_key(name) {
+                Some(name)
+            } else {
+                block_devices
+                    .iter()
+                    .find(|device| device.label.as_deref() == Some(name))
+                    .map(|device| device.name.as_str())
+            }
+        });
+
+        match device {
+            Some(device) => mounts.entry(device).or_default().push(disk),
+            None => unattached.push(disk),
+        }
+    }
+
+    let mut shown = FxHashSet::default();
+    let mut stack = block_devices
+        .iter()
+        .filter(|device| mounts.contains_key(device.name.as_str()) || device.raid.is_some())
+        .map(|device| device.name.as_str())
+        .collect::<Vec<_>>();
+    while let Some(name) = stack.pop() {
+        if shown.insert(name) {
+            if let Some(device) = devices.get(name) {
+                stack.extend(device.parents.iter().map(String::as_str));
+            }
+        }
+    }
+
+    let mut children: FxHashMap<&str, Vec<&str>> = FxHashMap::default();
+    for device in block_devices {
+        if shown.contains(device.name.as_str()) {
+            for parent in &device.parents {
+                children
+                    .entry(parent.as_str())
+                    .or_default()
+                    .push(device.name.as_str());
+            }
+        }
+    }
+    children
+        .values_mut()
+        .for_each(|children| children.sort_unstable());
+
+    let mut roots = block_devices
+        .iter()
+        .filter(|device| {
+            shown.contains(device.name.as_str())
+                && !device
+                    .parents
+                    .iter()
+                    .any(|parent| devices.contains_key(parent.as_str()))
+        })
+        .map(|device| device.name.as_str())
+        .collect::<Vec<_>>();
+    roots.sort_unstable();
+
+    // Each entry is a device, the prefix of its ancestors, and whether it is the last of its
+    // siblings (or `None` if it is a root).  A device on more than one parent, such as a RAID
+    // array, is listed under each of them.
+    let mut data = vec![];
+    let mut stack = roots
+        .into_iter()
+        .rev()
+        .map(|root| (root, String::default(), None))
+        .collect::<Vec<_>>();
+    while let Some((name, ancestors, is_last)) = stack.pop() {
+        let device = match devices.get(name) {
+            Some(device) => device,
+            None => continue,
+        };
+
+        let (prefix, child_ancestors) = match is_last {
+            None => (String::default(), String::default()),
+            Some(is_last) => (
+                format!(
+                    "{}{}{} ",
+                    ancestors,
+                    if is_last { BRANCH_END } else { BRANCH_SPLIT },
+                    BRANCH_HORIZONTAL
+                ),
+                if is_last {
+                    format!("{}   ", ancestors)
+                } else {
+                    format!("{}{}  ", ancestors, BRANCH_VERTICAL)
+                },
+            ),
+        };
+
+        let rate = io_data.and_then(|io_data| io_data.get(name));
+        let device_mounts = mounts.get(name);
+        let mounted = device_mounts.and_then(|mounts| mounts.first());
+        let (io_read, io_write) = match (rate, mounted) {
+            (Some(rate), _) => (
+                dec_bytes_per_second_string(rate.read.round() as u64).into(),
+                dec_bytes_per_second_string(rate.write.round() as u64).into(),
+            ),
+            (None, Some(mounted)) => (mounted.io_read.clone(), mounted.io_write.clone()),
+            (None, None) => ("N/A".into(), "N/A".into()),
+        };
+
+        data.push(DiskWidgetData {
+            name: KString::from_ref(name),
+            mount_point: device_mounts
+                .map(|mounts| {
+                    mounts
+                        .iter()
+                        .map(|mount| mount.mount_point.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                        .into()
+                })
+                .unwrap_or_default(),
+            free_bytes: mounted.and_then(|mounted| mounted.free_bytes),
+            used_bytes: mounted.and_then(|mounted| mounted.used_bytes),
+            total_bytes: match mounted {
+                Some(mounted) => mounted.total_bytes,
+                None => device.size,
+            },
+            io_read,
+            io_write,
+            io_activity: rate.and_then(|rate| rate.activity),
+            fs_type: mounted.and_then(|mounted| mounted.fs_type.clone()),
+            total_inodes: mounted.and_then(|mounted| mounted.total_inodes),
+            free_inodes: mounted.and_then(|mounted| mounted.free_inodes),
+            read_only: mounted.and_then(|mounted| mounted.read_only),
+            prefix: Some(prefix.into()),
+            label: device.label.as_deref().map(KString::from_ref),
+            kind: Some(block_device_kind(device).into()),
+            time_to_full: mounted.and_then(|mounted| mounted.time_to_full),
+            is_filling_soon: mounted
+                .map(|mounted| mounted.is_filling_soon)
+                .unwrap_or(false),
+        });
+
+        if let Some(children) = children.get(name) {
+            stack.extend(children.iter().enumerate().rev().map(|(index, child)| {
+                (
+                    *child,
+                    child_ancestors.clone(),
+                    Some(index == children.len() - 1),
+                )
+            }));
+        }
+    }
+
+    data.extend(unattached.into_iter().cloned());
+    data
+}
+
+/// Forecasts how long until a disk runs out of free space, by fitting a line through the samples
+/// of its free space within the forecast window (with least squares).  Returns `None` if the disk
+/// isn't filling up, won't fill up within a year, or hasn't been sampled enough yet.
+pub fn forecast_time_to_full(
+    history: &[(Instant, u64)], current_time: Instant, forecast_window: Duration,
+) -> Option<Duration> {
+    /// About a minute of samples, so that one burst of writes isn't taken as a trend.
+    const MIN_SAMPLES: usize = 6;
+    const MAX_FORECAST_SECS: f64 = 365.0 * 24.0 * 60.0 * 60.0;
+
+    let samples = history
+        .iter()
+        .filter(|(time, _)| current_time.saturating_duration_since(*time) <= forecast_window)
+        .map(|(time, free)| {
+            (
+                -current_time.saturating_duration_since(*time).as_secs_f64(),
+                *free as f64,
+            )
+        })
+        .collect::<Vec<_>>();
+    if samples.len() < MIN_SAMPLES {
+        return None;
+    }
+
+    let count = samples.len() as f64;
+    let mean_time = samples.iter().map(|(time, _)| time).sum::<f64>() / count;
+    let mean_free = samples.iter().map(|(_, free)| free).sum::<f64>() / count;
+    let (covariance, variance) =
+        samples
+            .iter()
+            .fold((0.0, 0.0), |(covariance, variance), (time, free)| {
+                (
+                    covariance + (time - mean_time) * (free - mean_free),
+                    variance + (time - mean_time) * (time - mean_time),
+                )
+            });
+
+    // The rate at which free space is being used up, in bytes per second.
+    let fill_rate = -covariance / variance;
+    if fill_rate.is_nan() || fill_rate <= 0.0 {
+        return None;
+    }
+
+    let (_, latest_free) = samples.last()?;
+    let secs_to_full = latest_free / fill_rate;
+    if secs_to_full <= MAX_FORECAST_SECS {
+        Some(Duration::from_secs_f64(secs_to_full))
+    } else {
+        None
+    }
+}
+
+/// Returns a short description of a block device for the disk table, such as "part", or
+/// "raid1 degraded recovery 8.5%" for a RAID array.
+fn block_device_kind(device: &disks::BlockDevice) -> String {
+    if let Some(raid) = &device.raid {
+        let mut description = vec![raid.level.clone().unwrap_or_else(|| "raid".to_string())];
+        if !raid.active {
+            description.push("inactive".to_string());
+        }
+        if raid.is_degraded() {
+            description.push("degraded".to_string());
+        }
+        if let Some(action) = &raid.action {
+            description.push(match raid.progress {
+                Some(progress) => format!("{} {:.1}%", action, progress),
+                None => format!("{} pending", action),
+            });
+        }
+
+        return description.join(" ");
+    }
+
+    match device.kind {
+        disks::BlockDeviceKind::Disk => "disk",
+        disks::BlockDeviceKind::Partition => "part",
+        disks::BlockDeviceKind::Crypt => "crypt",
+        disks::BlockDeviceKind::Lvm => "lvm",
+        disks::BlockDeviceKind::Mapper => "dm",
+        disks::BlockDeviceKind::Raid => "raid",
+        disks::BlockDeviceKind::Loop => "loop",
+    }
+    .to_string()
+}
+
+/// Converts the activity of an IO device into the (up to) two series that the given graph mode
+/// shows.
+pub fn convert_io_points(
+    current_data: &DataCollection, device: &str, mode: DiskIoGraphMode,
+) -> [Vec<Point>; 2] {
+    let mut result: [Vec<Point>; 2] = Default::default();
+    let current_time = current_data.current_instant;
+
+    for (time, data) in &current_data.timed_data_vec {
+        if let Some(rate) = data.io_data.get(device) {
+            let time_from_start: f64 =
+                (current_time.duration_since(*time).as_millis() as f64).floor();
+            for (value, series) in mode.values(rate).iter().zip(result.iter_mut()) {
+                if let Some(value) = value {
+                    series.push((-time_from_start, *value));
+                }
+            }
+        }
+
+        if *time == current_time {
+            break;
+        }
+    }
+
+    result
+}
+
+/// The usage of a single core for the CPU heatmap.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CpuHeatmapRow {
+    /// The core number.
+    pub index: usize,
+    /// The latest usage.
+    pub current: f64,
+    /// The average usage within each time bucket, oldest first.  A bucket with no data is `None`.
+    pub buckets: Vec<Option<f64>>,
+}
+
+/// Converts the usage of each core (skipping the average) over the last `display_time` milliseconds
+/// into `num_buckets` evenly sized time buckets for the CPU heatmap.
+pub fn convert_cpu_heatmap_data(
+    current_data: &DataCollection, display_time: u64, num_buckets: usize,
+) -> Vec<CpuHeatmapRow> {
+    let current_time = current_data.current_instant;
+
+    // The position of each core within the harvested data, along with its core number.
+    let cores = current_data
+        .cpu_harvest
+        .iter()
+        .enumerate()
+        .filter_map(|(itx, cpu)| match cpu.data_type {
+            CpuDataType::Cpu(index) => Some((itx, index, cpu.cpu_usage)),
+            CpuDataType::Avg | CpuDataType::Group(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut sums = vec![vec![(0.0, 0_u32); num_buckets]; cores.len()];
+    if num_buckets > 0 && display_time > 0 {
+        for (time, data) in &current_data.timed_data_vec {
+            let age = current_time.duration_since(*time).as_millis() as u64;
+            if age <= display_time {
+                let buckets_from_end = (age as usize * num_buckets) / display_time as usize;
+                let bucket = (num_buckets - 1).saturating_sub(buckets_from_end);
+
+                for ((itx, _, _), core_sums) in cores.iter().zip(sums.iter_mut()) {
+                    if let Some(value) = data.cpu_data.get(*itx) {
+                        core_sums[bucket].0 += *value;
+                        core_sums[bucket].1 += 1;
+                    }
+                }
+            }
+
+            if *time == current_time {
+                break;
+            }
+        }
+    }
+
+    cores
+        .into_iter()
+        .zip(sums)
+        .map(|((_, index, current), core_sums)| CpuHeatmapRow {
+            index,
+            current,
+            buckets: core_sums
+                .into_iter()
+                .map(|(sum, count)| {
+                    if count > 0 {
+                        Some(sum / f64::from(count))
+                    } else {
+                        None
+                    }
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// The history of a single kernel activity counter.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KernelActivityRow {
+    pub counter: KernelCounter,
+    /// The latest value, if there is one.
+    pub current: Option<f64>,
+    /// The mean of the values within the display time.
+    pub average: f64,
+    /// The highest value within the display time.
+    pub max: f64,
+    /// The average value within each time bucket, oldest first.  A bucket with no data is `None`.
+    pub buckets: Vec<Option<f64>>,
+}
+
+/// Converts each kernel activity counter over the last `display_time` milliseconds into
+/// `num_buckets` evenly sized time buckets, along with its latest, mean, and highest values.
+pub fn convert_kernel_activity_data(
+    current_data: &DataCollection, display_time: u64, num_buckets: usize,
+) -> Vec<KernelActivityRow> {
+    let current_time = current_data.current_instant;
+
+    let mut rows = KernelCounter::ALL
+        .iter()
+        .map(|counter| KernelActivityRow {
+            counter: *counter,
+            current: None,
+            average: 0.0,
+            max: 0.0,
+            buckets: vec![None; num_buckets],
+        })
+        .collect::<Vec<_>>();
+    let mut sums = vec![vec![(0.0, 0_u32); num_buckets]; rows.len()];
+    let mut num_values = 0_u32;
+
+    for (time, data) in &current_data.timed_data_vec {
+        if let Some(activity) = &data.kernel_data {
+            let age = current_time.duration_since(*time).as_millis() as u64;
+            let bucket = if num_buckets > 0 && display_time > 0 && age <= display_time {
+                let buckets_from_end = (age as usize * num_buckets) / display_time as usize;
+                Some((num_buckets - 1).saturating_sub(buckets_from_end))
+            } else {
+                None
+            };
+
+            if age <= display_time {
+                num_values += 1;
+            }
+            for (row, row_sums) in rows.iter_mut().zip(sums.iter_mut()) {
+                let value = row.counter.value(activity);
+                row.current = Some(value);
+                if age <= display_time {
+                    row.average += value;
+                    row.max = row.max.max(value);
+                }
+                if let Some(bucket) = bucket {
+                    row_sums[bucket].0 += value;
+                    row_sums[bucket].1 += 1;
+                }
+            }
+        }
+
+        if *time == current_time {
+            break;
+        }
+    }
+
+    for (row, row_sums) in rows.iter_mut().zip(sums) {
+        if num_values > 0 {
+            row.average /= f64::from(num_values);
+        }
+        row.buckets = row_sums
+            .into_iter()
+            .map(|(sum, count)| {
+                if count > 0 {
+                    Some(sum / f64::from(count))
+                } else {
+                    None
+                }
+            })
+            .collect();
+    }
+
+    rows
+}
+
+/// Returns a short string for a frequency given in MHz, such as "3.40GHz" or "800MHz".
+pub fn frequency_string(mhz: u64) -> String {
+    if mhz >= 1000 {
+        format!("{:.2}GHz", mhz as f64 / 1000.0)
+    } else {
+        format!("{}MHz", mhz)
+    }
+}
+
+/// Returns a short string for a rate per second, such as "12.3K/s".
+pub fn rate_string(per_second: f64) -> String {
+    if per_second < 1000.0 {
+        format!("{:.1}/s", per_second)
+    } else {
+        let (value, unit) = get_decimal_prefix(per_second.round() as u64, "/s");
+        format!("{:.1}{}", value, unit)
+    }
+}
+
 pub fn convert_mem_data_points(current_data: &DataCollection) -> Vec<Point> {
     let mut result: Vec<Point> = Vec::new();
     let current_time = current_data.current_instant;
@@ -201,6 +820,104 @@ pub fn convert_mem_data_points(current_data: &DataCollection) -> Vec<Point> {
     result
 }
 
+/// Converts the RAM breakdown into points for a stacked graph, as percentages of the total.  The
+/// first four series are the running totals of used, buffer, cache, and reclaimable slab memory, so
+/// that each line sits on top of the one before it.  The last series is the available memory.
+pub fn convert_mem_breakdown_points(current_data: &DataCollection) -> [Vec<Point>; 5] {
+    let mut result: [Vec<Point>; 5] = Default::default();
+    let current_time = current_data.current_instant;
+
+    for (time, data) in &current_data.timed_data_vec {
+        if let Some(breakdown) = &data.mem_breakdown_data {
+            if breakdown.total > 0 {
+                let time_from_start: f64 =
+                    (current_time.duration_since(*time).as_millis() as f64).floor();
+                let percent = |kib: u64| kib as f64 / breakdown.total as f64 * 100.0;
+
+                let mut stacked = 0;
+                for (component, series) in [
+                    breakdown.used,
+                    breakdown.buffers,
+                    breakdown.cache,
+                    breakdown.slab_reclaimable,
+                ]
+                .iter()
+                .zip(result.iter_mut())
+                {
+                    stacked += component;
+                    series.push((-time_from_start, percent(stacked)));
+                }
+                result[4].push((-time_from_start, percent(breakdown.available)));
+            }
+        }
+
+        if *time == current_time {
+            break;
+        }
+    }
+
+    result
+}
+
+/// Converts the swap-in, swap-out, and major fault rates into one series each.
+pub fn convert_paging_points(current_data: &DataCollection) -> [Vec<Point>; 3] {
+    let mut result: [Vec<Point>; 3] = Default::default();
+    let current_time = current_data.current_instant;
+
+    for (time, data) in &current_data.timed_data_vec {
+        if let Some(paging) = &data.paging_data {
+            let time_from_start: f64 =
+                (current_time.duration_since(*time).as_millis() as f64).floor();
+            for (value, series) in [paging.swap_in, paging.swap_out, paging.major_faults]
+                .iter()
+                .zip(result.iter_mut())
+            {
+                series.push((-time_from_start, *value));
+            }
+        }
+
+        if *time == current_time {
+            break;
+        }
+    }
+
+    result
+}
+
+/// Converts the ARC's hit ratio, L2ARC hit ratio, and MRU and MFU sizes into one series each.
+/// Hit ratios are skipped for updates without any reads.
+#[cfg(feature = "zfs")]
+pub fn convert_arc_stats_points(current_data: &DataCollection) -> [Vec<Point>; 4] {
+    let mut result: [Vec<Point>; 4] = Default::default();
+    let current_time = current_data.current_instant;
+
+    for (time, data) in &current_data.timed_data_vec {
+        if let Some(arc_stats) = &data.arc_stats_data {
+            let time_from_start: f64 =
+                (current_time.duration_since(*time).as_millis() as f64).floor();
+            for (value, series) in [
+                arc_stats.hit_ratio,
+                arc_stats.l2_hit_ratio,
+                Some(arc_stats.mru_percent),
+                Some(arc_stats.mfu_percent),
+            ]
+            .iter()
+            .zip(result.iter_mut())
+            {
+                if let Some(value) = value {
+                    series.push((-time_from_start, *value));
+                }
+            }
+        }
+
+        if *time == current_time {
+            break;
+        }
+    }
+
+    result
+}
+
 pub fn convert_swap_data_points(current_data: &DataCollection) -> Vec<Point> {
     let mut result: Vec<Point> = Vec::new();
     let current_time = current_data.current_instant;
@@ -219,26 +936,26 @@ pub fn convert_swap_data_points(current_data: &DataCollection) -> Vec<Point> {
     result
 }
 
+/// Returns the unit type and denominator for given total amount of memory in kibibytes.
+fn return_unit_and_denominator_for_mem_kib(mem_total_kib: u64) -> (&'static str, f64) {
+    if mem_total_kib < 1024 {
+        // Stay with KiB
+        ("KiB", 1.0)
+    } else if mem_total_kib < MEBI_LIMIT {
+        // Use MiB
+        ("MiB", KIBI_LIMIT_F64)
+    } else if mem_total_kib < GIBI_LIMIT {
+        // Use GiB
+        ("GiB", MEBI_LIMIT_F64)
+    } else {
+        // Use TiB
+        ("TiB", GIBI_LIMIT_F64)
+    }
+}
+
 pub fn convert_mem_labels(
     current_data: &DataCollection,
 ) -> (Option<(String, String)>, Option<(String, String)>) {
-    /// Returns the unit type and denominator for given total amount of memory in kibibytes.
-    fn return_unit_and_denominator_for_mem_kib(mem_total_kib: u64) -> (&'static str, f64) {
-        if mem_total_kib < 1024 {
-            // Stay with KiB
-            ("KiB", 1.0)
-        } else if mem_total_kib < MEBI_LIMIT {
-            // Use MiB
-            ("MiB", KIBI_LIMIT_F64)
-        } else if mem_total_kib < GIBI_LIMIT {
-            // Use GiB
-            ("GiB", MEBI_LIMIT_F64)
-        } else {
-            // Use TiB
-            ("TiB", GIBI_LIMIT_F64)
-        }
-    }
-
     (
         if current_data.memory_harvest.mem_total_in_kib > 0 {
             Some((
@@ -558,23 +1275,6 @@ pub fn convert_battery_harvest(current_data: &DataCollection) -> Vec<ConvertedBa
 pub fn convert_arc_labels(
     current_data: &crate::app::data_farmer::DataCollection,
 ) -> Option<(String, String)> {
-    /// Returns the unit type and denominator for given total amount of memory in kibibytes.
-    fn return_unit_and_denominator_for_mem_kib(mem_total_kib: u64) -> (&'static str, f64) {
-        if mem_total_kib < 1024 {
-            // Stay with KiB
-            ("KiB", 1.0)
-        } else if mem_total_kib < MEBI_LIMIT {
-            // Use MiB
-            ("MiB", KIBI_LIMIT_F64)
-        } else if mem_total_kib < GIBI_LIMIT {
-            // Use GiB
-            ("GiB", MEBI_LIMIT_F64)
-        } else {
-            // Use TiB
-            ("TiB", GIBI_LIMIT_F64)
-        }
-    }
-
     if current_data.arc_harvest.mem_total_in_kib > 0 {
         Some((
             format!(
@@ -634,23 +1334,6 @@ pub struct ConvertedGpuData {
 pub fn convert_gpu_data(
     current_data: &crate::app::data_farmer::DataCollection,
 ) -> Option<Vec<ConvertedGpuData>> {
-    /// Returns the unit type and denominator for given total amount of memory in kibibytes.
-    fn return_unit_and_denominator_for_mem_kib(mem_total_kib: u64) -> (&'static str, f64) {
-        if mem_total_kib < 1024 {
-            // Stay with KiB
-            ("KiB", 1.0)
-        } else if mem_total_kib < MEBI_LIMIT {
-            // Use MiB
-            ("MiB", KIBI_LIMIT_F64)
-        } else if mem_total_kib < GIBI_LIMIT {
-            // Use GiB
-            ("GiB", MEBI_LIMIT_F64)
-        } else {
-            // Use TiB
-            ("TiB", GIBI_LIMIT_F64)
-        }
-    }
-
     let current_time = current_data.current_instant;
 
     // convert points
@@ -712,9 +1395,737 @@ pub fn convert_gpu_data(
     }
 }
 
+#[derive(Default, Debug)]
+pub struct ConvertedNumaData {
+    pub name: String,
+    pub mem_total: String,
+    pub mem_percent: String,
+    pub points: Vec<Point>,
+}
+
+/// Converts the memory usage of each NUMA node into points and labels, in the same way as for RAM.
+pub fn convert_numa_data(current_data: &DataCollection) -> Option<Vec<ConvertedNumaData>> {
+    let current_time = current_data.current_instant;
+
+    let mut point_vec: Vec<Vec<Point>> = vec![Vec::new(); current_data.numa_harvest.len()];
+    for (time, data) in &current_data.timed_data_vec {
+        let time_from_start: f64 = (current_time.duration_since(*time).as_millis() as f64).floor();
+        for (point, points) in data.numa_data.iter().zip(point_vec.iter_mut()) {
+            if let Some(point) = point {
+                points.push((-time_from_start, *point));
+            }
+        }
+
+        if *time == current_time {
+            break;
+        }
+    }
+
+    let results = current_data
+        .numa_harvest
+        .iter()
+        .zip(point_vec)
+        .map(|((node, mem), points)| {
+            let (unit, denominator) = return_unit_and_denominator_for_mem_kib(mem.mem_total_in_kib);
+
+            ConvertedNumaData {
+                name: format!("N{}", node),
+                mem_total: format!(
+                    "   {:.1}{}/{:.1}{}",
+                    mem.mem_used_in_kib as f64 / denominator,
+                    unit,
+                    (mem.mem_total_in_kib as f64 / denominator),
+                    unit
+                ),
+                mem_percent: format!("{:3.0}%", mem.use_percent.unwrap_or(0.0)),
+                points,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if !results.is_empty() {
+        Some(results)
+    } else {
+        None
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct ConvertedCompressedData {
+    pub name: String,
+    pub mem_total: String,
+    pub mem_percent: String,
+    pub points: Vec<Point>,
+}
+
+/// Converts each store of compressed memory into points and labels.  The percentage is of all RAM,
+/// while the label shows the size of the data before and after compression.
+pub fn convert_compressed_data(
+    current_data: &DataCollection,
+) -> Option<Vec<ConvertedCompressedData>> {
+    let current_time = current_data.current_instant;
+
+    let mut point_vec: Vec<Vec<Point>> = vec![Vec::new(); current_data.compressed_harvest.len()];
+    for (time, data) in &current_data.timed_data_vec {
+        let time_from_start: f64 = (current_time.duration_since(*time).as_millis() as f64).floor();
+        for (point, points) in data.compressed_data.iter().zip(point_vec.iter_mut()) {
+            if let Some(point) = point {
+                points.push((-time_from_start, *point));
+            }
+        }
+
+        if *time == current_time {
+            break;
+        }
+    }
+
+    let results = current_data
+        .compressed_harvest
+        .iter()
+        .zip(point_vec)
+        .map(|(compressed, points)| {
+            let (unit, denominator) =
+                return_unit_and_denominator_for_mem_kib(compressed.original_in_kib);
+            let mem_percent = points.last().map(|point| point.1).unwrap_or(0.0);
+
+            ConvertedCompressedData {
+                name: compressed.name.clone(),
+                mem_total: format!(
+                    "   {:.1}{}→{:.1}{} ({:.1}x)",
+                    compressed.original_in_kib as f64 / denominator,
+                    unit,
+                    compressed.compressed_in_kib as f64 / denominator,
+                    unit,
+                    compressed.ratio().unwrap_or(0.0)
+                ),
+                mem_percent: format!("{:3.0}%", mem_percent),
+                points,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if !results.is_empty() {
+        Some(results)
+    } else {
+        None
+    }
+}
+
+/// Returns the labels for huge pages, if there are any.  Reserved pages are shown separately, as
+/// they are still counted as free.
+pub fn convert_huge_pages_labels(current_data: &DataCollection) -> Option<(String, String)> {
+    let huge_pages = current_data
+        .huge_pages_harvest
+        .filter(|huge_pages| huge_pages.total > 0)?;
+
+    let total_in_kib = huge_pages.total * huge_pages.page_size_in_kib;
+    let used_in_kib =
+        huge_pages.total.saturating_sub(huge_pages.free) * huge_pages.page_size_in_kib;
+    let reserved_in_kib = huge_pages.reserved * huge_pages.page_size_in_kib;
+    let (unit, denominator) = return_unit_and_denominator_for_mem_kib(total_in_kib);
+
+    Some((
+        format!("{:3.0}%", huge_pages.use_percent().unwrap_or(0.0)),
+        format!(
+            "   {:.1}{}/{:.1}{} ({:.1}{} rsvd)",
+            used_in_kib as f64 / denominator,
+            unit,
+            total_in_kib as f64 / denominator,
+            unit,
+            reserved_in_kib as f64 / denominator,
+            unit
+        ),
+    ))
+}
+
+pub fn convert_huge_pages_data_points(current_data: &DataCollection) -> Vec<Point> {
+    let mut result: Vec<Point> = Vec::new();
+    let current_time = current_data.current_instant;
+
+    for (time, data) in &current_data.timed_data_vec {
+        if let Some(huge_pages_data) = data.huge_pages_data {
+            let time_from_start: f64 =
+                (current_time.duration_since(*time).as_millis() as f64).floor();
+            result.push((-time_from_start, huge_pages_data));
+            if *time == current_time {
+                break;
+            }
+        }
+    }
+
+    result
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::app::data_farmer::TimedData;
+
+    #[test]
+    fn test_convert_cpu_state_points() {
+        let mut data = DataCollection::default();
+        let states = CpuStates {
+            user: 10.0,
+            nice: 5.0,
+            system: 20.0,
+            irq: 1.0,
+            soft_irq: 2.0,
+            io_wait: 30.0,
+            steal: 4.0,
+        };
+        data.timed_data_vec.push((
+            data.current_instant,
+            TimedData {
+                cpu_state_data: vec![None, Some(states)],
+                ..Default::default()
+            },
+        ));
+
+        assert!(convert_cpu_state_points(&data, 0)
+            .iter()
+            .all(|series| series.is_empty()));
+
+        let values = convert_cpu_state_points(&data, 1)
+            .iter()
+            .map(|series| series[0].1)
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![15.0, 35.0, 38.0, 68.0, 72.0]);
+    }
+
+    #[test]
+    fn test_convert_cpu_frequency_points() {
+        let mut data = DataCollection::default();
+        data.timed_data_vec.push((
+            data.current_instant,
+            TimedData {
+                cpu_freq_data: vec![Some(2400.0), None],
+                ..Default::default()
+            },
+        ));
+
+        assert_eq!(convert_cpu_frequency_points(&data, 0), vec![(-0.0, 2400.0)]);
+        assert!(convert_cpu_frequency_points(&data, 1).is_empty());
+    }
+
+    #[test]
+    fn test_convert_load_avg_points() {
+        let mut data = DataCollection::default();
+        data.timed_data_vec.push((
+            data.current_instant,
+            TimedData {
+                load_avg_data: [2.0, 1.0, 0.5],
+                ..Default::default()
+            },
+        ));
+
+        let points = convert_load_avg_points(&data, 4.0);
+        assert_eq!(points[0], vec![(-0.0, 0.5)]);
+        assert_eq!(points[1], vec![(-0.0, 0.25)]);
+        assert_eq!(points[2], vec![(-0.0, 0.125)]);
+
+        assert_eq!(convert_load_avg_points(&data, 0.0)[0], vec![(-0.0, 2.0)]);
+    }
+
+    #[test]
+    fn test_convert_mem_breakdown_points() {
+        use crate::app::data_harvester::memory::MemBreakdown;
+
+        let mut data = DataCollection::default();
+        data.timed_data_vec.push((
+            data.current_instant,
+            TimedData {
+                mem_breakdown_data: Some(MemBreakdown {
+                    total: 1000,
+                    used: 400,
+                    buffers: 50,
+                    cache: 200,
+                    slab_reclaimable: 100,
+                    free: 250,
+                    available: 600,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        ));
+
+        let points = convert_mem_breakdown_points(&data);
+        assert_eq!(points[0], vec![(-0.0, 40.0)]);
+        assert_eq!(points[1], vec![(-0.0, 45.0)]);
+        assert_eq!(points[2], vec![(-0.0, 65.0)]);
+        assert_eq!(points[3], vec![(-0.0, 75.0)]);
+        assert_eq!(points[4], vec![(-0.0, 60.0)]);
+
+        assert!(convert_mem_breakdown_points(&DataCollection::default())[0].is_empty());
+    }
+
+    #[test]
+    fn test_convert_numa_data() {
+        use crate::app::data_harvester::memory::MemHarvest;
+
+        let mut data = DataCollection::default();
+        assert!(convert_numa_data(&data).is_none());
+
+        data.numa_harvest = vec![
+            (
+                0,
+                MemHarvest {
+                    mem_total_in_kib: 4 * 1024 * 1024,
+                    mem_used_in_kib: 1024 * 1024,
+                    use_percent: Some(25.0),
+                    breakdown: None,
+                },
+            ),
+            (1, MemHarvest::default()),
+        ];
+        data.timed_data_vec.push((
+            data.current_instant,
+            TimedData {
+                numa_data: vec![Some(25.0), None],
+                ..Default::default()
+            },
+        ));
+
+        let numa_data = convert_numa_data(&data).unwrap();
+        assert_eq!(numa_data.len(), 2);
+        assert_eq!(numa_data[0].name, "N0");
+        assert_eq!(numa_data[0].mem_percent, " 25%");
+        assert_eq!(numa_data[0].mem_total, "   1.0GiB/4.0GiB");
+        assert_eq!(numa_data[0].points, vec![(-0.0, 25.0)]);
+        assert!(numa_data[1].points.is_empty());
+    }
+
+    #[test]
+    fn test_convert_compressed_data() {
+        use crate::app::data_harvester::memory::compression::CompressedHarvest;
+
+        let mut data = DataCollection::default();
+        assert!(convert_compressed_data(&data).is_none());
+
+        data.compressed_harvest = vec![CompressedHarvest {
+            name: "zram0".to_string(),
+            original_in_kib: 4 * 1024 * 1024,
+            compressed_in_kib: 1024 * 1024,
+        }];
+        data.timed_data_vec.push((
+            data.current_instant,
+            TimedData {
+                compressed_data: vec![Some(12.5)],
+                ..Default::default()
+            },
+        ));
+
+        let compressed_data = convert_compressed_data(&data).unwrap();
+        assert_eq!(compressed_data.len(), 1);
+        assert_eq!(compressed_data[0].name, "zram0");
+        assert_eq!(compressed_data[0].mem_percent, " 12%");
+        assert_eq!(compressed_data[0].mem_total, "   4.0GiB→1.0GiB (4.0x)");
+        assert_eq!(compressed_data[0].points, vec![(-0.0, 12.5)]);
+    }
+
+    #[test]
+    fn test_convert_huge_pages_labels() {
+        use crate::app::data_harvester::memory::huge_pages::HugePagesHarvest;
+
+        let mut data = DataCollection::default();
+        assert!(convert_huge_pages_labels(&data).is_none());
+
+        data.huge_pages_harvest = Some(HugePagesHarvest::default());
+        assert!(convert_huge_pages_labels(&data).is_none());
+
+        data.huge_pages_harvest = Some(HugePagesHarvest {
+            total: 512,
+            free: 384,
+            reserved: 64,
+            page_size_in_kib: 2048,
+        });
+        assert_eq!(
+            convert_huge_pages_labels(&data),
+            Some((
+                " 25%".to_string(),
+                "   0.2GiB/1.0GiB (0.1GiB rsvd)".to_string()
+            ))
+        );
+    }
+
+    #[cfg(feature = "zfs")]
+    #[test]
+    fn test_convert_arc_stats_points() {
+        use crate::app::data_harvester::memory::arc::ArcActivity;
+
+        let mut data = DataCollection::default();
+        data.timed_data_vec.push((
+            data.current_instant,
+            TimedData {
+                arc_stats_data: Some(ArcActivity {
+                    hits: 90.0,
+                    misses: 10.0,
+                    hit_ratio: Some(90.0),
+                    l2_hit_ratio: None,
+                    mru_percent: 20.0,
+                    mfu_percent: 30.0,
+                }),
+                ..Default::default()
+            },
+        ));
+
+        let points = convert_arc_stats_points(&data);
+        assert_eq!(points[0], vec![(-0.0, 90.0)]);
+        assert!(points[1].is_empty());
+        assert_eq!(points[2], vec![(-0.0, 20.0)]);
+        assert_eq!(points[3], vec![(-0.0, 30.0)]);
+    }
+
+    #[test]
+    fn test_convert_paging_points() {
+        use crate::app::data_harvester::memory::paging::PagingActivity;
+        use std::time::Duration;
+
+        let mut data = DataCollection::default();
+        let now = data.current_instant;
+        data.timed_data_vec
+            .push((now - Duration::from_millis(1000), TimedData::default()));
+        data.timed_data_vec.push((
+            now,
+            TimedData {
+                paging_data: Some(PagingActivity {
+                    swap_in: 10.0,
+                    swap_out: 20.0,
+                    faults: 1000.0,
+                    major_faults: 5.0,
+                }),
+                ..Default::default()
+            },
+        ));
+
+        let points = convert_paging_points(&data);
+        assert_eq!(points[0], vec![(-0.0, 10.0)]);
+        assert_eq!(points[1], vec![(-0.0, 20.0)]);
+        assert_eq!(points[2], vec![(-0.0, 5.0)]);
+    }
+
+    #[test]
+    fn test_rate_string() {
+        assert_eq!(rate_string(0.4), "0.4/s");
+        assert_eq!(rate_string(12.0), "12.0/s");
+        assert_eq!(rate_string(12345.0), "12.3K/s");
+    }
+
+    #[test]
+    fn test_convert_pressure_points() {
+        use crate::app::data_harvester::pressure::PressureStall;
+
+        let mut data = DataCollection::default();
+        data.timed_data_vec.push((
+            data.current_instant,
+            TimedData {
+                pressure_data: [
+                    Some(PressureStall {
+                        some: 12.5,
+                        full: None,
+                    }),
+                    Some(PressureStall {
+                        some: 3.0,
+                        full: Some(1.0),
+                    }),
+                    None,
+                ],
+                ..Default::default()
+            },
+        ));
+
+        let some = convert_pressure_points(&data, false);
+        assert_eq!(some[0], vec![(-0.0, 12.5)]);
+        assert_eq!(some[1], vec![(-0.0, 3.0)]);
+        assert!(some[2].is_empty());
+
+        let full = convert_pressure_points(&data, true);
+        assert!(full[0].is_empty());
+        assert_eq!(full[1], vec![(-0.0, 1.0)]);
+    }
+
+    #[test]
+    fn test_convert_io_points() {
+        use crate::app::data_harvester::disks::{DiskActivity, IoRate};
+
+        let mut data = DataCollection::default();
+        let mut entry = TimedData::default();
+        entry.io_data.insert(
+            "sda".to_string(),
+            IoRate {
+                read: 2048.0,
+                write: 512.0,
+                activity: Some(DiskActivity {
+                    reads: 4.0,
+                    writes: 1.0,
+                    read_await: Some(2.5),
+                    utilisation: 40.0,
+                    ..Default::default()
+                }),
+            },
+        );
+        data.timed_data_vec.push((data.current_instant, entry));
+
+        let throughput = convert_io_points(&data, "sda", DiskIoGraphMode::Throughput);
+        assert_eq!(throughput[0], vec![(-0.0, 2048.0)]);
+        assert_eq!(throughput[1], vec![(-0.0, 512.0)]);
+
+        let latency = convert_io_points(&data, "sda", DiskIoGraphMode::Latency);
+        assert_eq!(latency[0], vec![(-0.0, 2.5)]);
+        assert_eq!(latency[1], vec![(-0.0, 0.0)]);
+
+        let utilisation = convert_io_points(&data, "sda", DiskIoGraphMode::Utilisation);
+        assert_eq!(utilisation[0], vec![(-0.0, 40.0)]);
+        assert!(utilisation[1].is_empty());
+
+        let sdb = convert_io_points(&data, "sdb", DiskIoGraphMode::Throughput);
+        assert!(sdb[0].is_empty() && sdb[1].is_empty());
+    }
+
+    #[test]
+    fn test_convert_disk_tree() {
+        use crate::app::data_harvester::disks::{BlockDevice, BlockDeviceKind, RaidStatus};
+
+        let device = |name: &str, kind: BlockDeviceKind, parents: &[&str]| BlockDevice {
+            name: name.to_string(),
+            label: None,
+            kind,
+            size: Some(1000),
+            parents: parents.iter().map(|parent| parent.to_string()).collect(),
+            raid: None,
+        };
+        let block_devices = vec![
+            device("sda", BlockDeviceKind::Disk, &[]),
+            device("sda1", BlockDeviceKind::Partition, &["sda"]),
+            device("sda2", BlockDeviceKind::Partition, &["sda"]),
+            BlockDevice {
+                label: Some("luks-root".to_string()),
+                ..device("dm-0", BlockDeviceKind::Crypt, &["sda2"])
+            },
+            BlockDevice {
+                label: Some("vg-root".to_string()),
+                ..device("dm-1", BlockDeviceKind::Lvm, &["dm-0"])
+            },
+            device("sdb", BlockDeviceKind::Disk, &[]),
+            device("sdb1", BlockDeviceKind::Partition, &["sdb"]),
+            device("sdc", BlockDeviceKind::Disk, &[]),
+            device("sdc1", BlockDeviceKind::Partition, &["sdc"]),
+            BlockDevice {
+                raid: Some(RaidStatus {
+                    level: Some("raid1".to_string()),
+                    active: true,
+                    total_devices: Some(2),
+                    working_devices: Some(1),
+                    action: Some("recovery".to_string()),
+                    progress: Some(8.5),
+                }),
+                ..device("md0", BlockDeviceKind::Raid, &["sdb1", "sdc1"])
+            },
+            device("sdd", BlockDeviceKind::Disk, &[]),
+        ];
+
+        let disk = |name: &str, mount_point: &str| DiskWidgetData {
+            name: KString::from_ref(name),
+            mount_point: KString::from_ref(mount_point),
+            free_bytes: Some(400),
+            used_bytes: Some(600),
+            total_bytes: Some(1000),
+            io_read: "0B/s".into(),
+            io_write: "0B/s".into(),
+            io_activity: None,
+            fs_type: None,
+            total_inodes: None,
+            free_inodes: None,
+            read_only: None,
+            prefix: None,
+            label: None,
+            kind: None,
+            time_to_full: None,
+            is_filling_soon: false,
+        };
+        let disk_data = vec![
+            disk("/dev/sda1", "/boot"),
+            disk("/dev/mapper/vg-root", "/"),
+            disk("/dev/mapper/vg-root", "/home"),
+            disk("tmpfs", "/tmp"),
+        ];
+
+        let tree = convert_disk_tree(&disk_data, &block_devices, None);
+        let rows = tree
+            .iter()
+            .map(|row| {
+                (
+                    row.prefixed_name().to_string(),
+                    row.mount_point.to_string(),
+                    row.kind.as_deref().unwrap_or_default().to_string(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let row = |name: &str, mount_point: &str, kind: &str| {
+            (name.to_string(), mount_point.to_string(), kind.to_string())
+        };
+
+        assert_eq!(
+            rows,
+            vec![
+                row("sda", "", "disk"),
+                row("├─ sda1", "/boot", "part"),
+                row("└─ sda2", "", "part"),
+                row("   └─ luks-root", "", "crypt"),
+                row("      └─ vg-root", "/, /home", "lvm"),
+                row("sdb", "", "disk"),
+                row("└─ sdb1", "", "part"),
+                row("   └─ md0", "", "raid1 degraded recovery 8.5%"),
+                row("sdc", "", "disk"),
+                row("└─ sdc1", "", "part"),
+                row("   └─ md0", "", "raid1 degraded recovery 8.5%"),
+                row("tmpfs", "/tmp", ""),
+            ]
+        );
+
+        // Mounted devices use the space of their filesystem, and others use their own size.
+        assert_eq!(tree[1].used_bytes, Some(600));
+        assert_eq!(tree[2].used_bytes, None);
+        assert_eq!(tree[2].total_bytes, Some(1000));
+    }
+
+    #[test]
+    fn test_forecast_time_to_full() {
+        // Samples are 10 seconds apart, built forwards from a base time so that nothing needs to
+        // be subtracted from an `Instant`.  The forecast is made at the time of the last sample.
+        let base_time = Instant::now();
+        let window = Duration::from_secs(3600);
+        let history = |frees: &[u64]| {
+            let samples = frees
+                .iter()
+                .enumerate()
+                .map(|(index, free)| (base_time + Duration::from_secs(10 * index as u64), *free))
+                .collect::<Vec<_>>();
+            let current_time = samples.last().map(|(time, _)| *time).unwrap_or(base_time);
+            (samples, current_time)
+        };
+
+        // Losing 1000 bytes every 10 seconds, with 6000 bytes left.
+        let (filling, current_time) = history(&[11000, 10000, 9000, 8000, 7000, 6000]);
+        assert_eq!(
+            forecast_time_to_full(&filling, current_time, window),
+            Some(Duration::from_secs(60))
+        );
+
+        // Samples from before the window are ignored.
+        assert_eq!(
+            forecast_time_to_full(&filling, current_time, Duration::from_secs(30)),
+            None
+        );
+
+        let (steady, current_time) = history(&[6000, 6000, 6000, 6000, 6000, 6000]);
+        assert_eq!(forecast_time_to_full(&steady, current_time, window), None);
+
+        let (emptying, current_time) = history(&[6000, 7000, 8000, 9000, 10000, 11000]);
+        assert_eq!(forecast_time_to_full(&emptying, current_time, window), None);
+
+        let (too_few, current_time) = history(&[9000, 8000, 7000]);
+        assert_eq!(forecast_time_to_full(&too_few, current_time, window), None);
+    }
+
+    #[test]
+    fn test_convert_kernel_activity_data() {
+        use crate::app::data_harvester::kernel::KernelActivity;
+        use std::time::Duration;
+
+        let mut data = DataCollection::default();
+        let now = data.current_instant;
+        for (age, forks) in [(9000, 100.0), (4000, 2.0), (3000, 4.0), (0, 3.0)] {
+            data.timed_data_vec.push((
+                now - Duration::from_millis(age),
+                TimedData {
+                    kernel_data: Some(KernelActivity {
+                        forks,
+                        running: 1.0,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            ));
+        }
+        data.timed_data_vec.insert(
+            0,
+            (now - Duration::from_millis(10000), TimedData::default()),
+        );
+
+        let rows = convert_kernel_activity_data(&data, 8000, 4);
+        assert_eq!(rows.len(), KernelCounter::ALL.len());
+
+        let forks = rows
+            .iter()
+            .find(|row| row.counter == KernelCounter::Forks)
+            .unwrap();
+        assert_eq!(forks.current, Some(3.0));
+        assert_eq!(forks.average, 3.0);
+        assert_eq!(forks.max, 4.0);
+        assert_eq!(forks.buckets, vec![None, Some(2.0), Some(4.0), Some(3.0)]);
+
+        let running = rows
+            .iter()
+            .find(|row| row.counter == KernelCounter::Running)
+            .unwrap();
+        assert_eq!(running.current, Some(1.0));
+
+        let empty = convert_kernel_activity_data(&DataCollection::default(), 8000, 4);
+        assert_eq!(empty[0].current, None);
+        assert_eq!(empty[0].buckets, vec![None; 4]);
+    }
+
+    #[test]
+    fn test_convert_cpu_heatmap_data() {
+        use crate::app::data_harvester::cpu::CpuData;
+        use std::time::Duration;
+
+        let mut data = DataCollection::default();
+        let now = data.current_instant;
+        data.cpu_harvest = vec![CpuDataType::Avg, CpuDataType::Cpu(0), CpuDataType::Cpu(1)]
+            .into_iter()
+            .map(|data_type| CpuData {
+                data_type,
+                cpu_usage: 50.0,
+                state_usage: None,
+                frequency: None,
+            })
+            .collect();
+
+        for (age, usage) in [(9000, 10.0), (4000, 20.0), (3000, 40.0), (0, 90.0)] {
+            data.timed_data_vec.push((
+                now - Duration::from_millis(age),
+                TimedData {
+                    cpu_data: vec![0.0, usage, usage / 2.0],
+                    ..Default::default()
+                },
+            ));
+        }
+
+        let rows = convert_cpu_heatmap_data(&data, 8000, 4);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].index, 0);
+        assert_eq!(rows[0].current, 50.0);
+        assert_eq!(
+            rows[0].buckets,
+            vec![None, Some(20.0), Some(40.0), Some(90.0)]
+        );
+        assert_eq!(rows[1].index, 1);
+        assert_eq!(
+            rows[1].buckets,
+            vec![None, Some(10.0), Some(20.0), Some(45.0)]
+        );
+
+        assert!(convert_cpu_heatmap_data(&data, 8000, 0)[0]
+            .buckets
+            .is_empty());
+    }
+
+    #[test]
+    fn test_frequency_string() {
+        assert_eq!(frequency_string(800), "800MHz");
+        assert_eq!(frequency_string(3400), "3.40GHz");
+    }
 
     #[test]
     fn test_binary_byte_string() {
@@ -48,6 +48,8 @@ pub mod utils {
     pub mod error;
     pub mod gen_util;
     pub mod logging;
+    #[cfg(test)]
+    pub mod test_utils;
 }
 pub mod canvas;
 pub mod clap;
@@ -77,6 +79,7 @@ pub enum ThreadControlEvent {
     UpdateConfig(Box<app::AppConfigFields>),
     UpdateUsedWidgets(Box<UsedWidgets>),
     UpdateUpdateTime(u64),
+    UpdateShowAllMounts(bool),
 }
 
 pub fn handle_mouse_event(event: MouseEvent, app: &mut App) {
@@ -121,6 +124,16 @@ pub fn handle_key_event_or_break(
             KeyCode::Down => app.on_down_key(),
             KeyCode::Left => app.on_left_key(),
             KeyCode::Right => app.on_right_key(),
+            KeyCode::Char('v') if app.can_toggle_all_mounts() => {
+                // Which mounts are listed is decided by the collection thread, so let it know.
+                let show_all_mounts = !app.app_config_fields.show_all_mounts;
+                if reset_sender
+                    .send(ThreadControlEvent::UpdateShowAllMounts(show_all_mounts))
+                    .is_ok()
+                {
+                    app.app_config_fields.show_all_mounts = show_all_mounts;
+                }
+            }
             KeyCode::Char(caught_char) => app.on_char_key(caught_char),
             KeyCode::Esc => app.on_esc(),
             KeyCode::Enter => app.on_enter(),
@@ -159,6 +172,7 @@ pub fn handle_key_event_or_break(
                 KeyCode::Right => app.move_widget_selection(&WidgetDirection::Right),
                 KeyCode::Up => app.move_widget_selection(&WidgetDirection::Up),
                 KeyCode::Down => app.move_widget_selection(&WidgetDirection::Down),
+                KeyCode::Char('r') if app.is_in_search_widget() => app.reverse_search_history(),
                 KeyCode::Char('r') => {
                     if reset_sender.send(ThreadControlEvent::Reset).is_ok() {
                         app.reset();
@@ -230,6 +244,14 @@ pub fn read_config(config_location: Option<&str>) -> error::Result<Option<PathBu
     Ok(config_path)
 }
 
+/// Returns where the process search history is stored, which is next to the config file.
+pub fn get_search_history_path(config_path: &Option<PathBuf>) -> Option<PathBuf> {
+    config_path
+        .as_ref()
+        .and_then(|path| path.parent())
+        .map(|parent| parent.join(SEARCH_HISTORY_FILE_NAME))
+}
+
 pub fn create_or_get_config(config_path: &Option<PathBuf>) -> error::Result<Config> {
     if let Some(path) = config_path {
         if let Ok(config_string) = fs::read_to_string(path) {
@@ -322,10 +344,7 @@ pub fn panic_hook(panic_info: &PanicInfo<'_>) {
 }
 
 pub fn update_data(app: &mut App) {
-    let data_source = match &app.frozen_state {
-        app::frozen_state::FrozenState::NotFrozen => &app.data_collection,
-        app::frozen_state::FrozenState::Frozen(data) => data,
-    };
+    let data_source = app.frozen_state.data(&app.data_collection);
 
     for proc in app.proc_state.widget_states.values_mut() {
         if proc.force_update_data {
@@ -354,6 +373,9 @@ pub fn update_data(app: &mut App) {
         {
             app.converted_data.gpu_data = convert_gpu_data(data_source);
         }
+        app.converted_data.numa_data = convert_numa_data(data_source);
+        app.converted_data.compressed_data = convert_compressed_data(data_source);
+        app.converted_data.huge_pages_data = convert_huge_pages_data_points(data_source);
         app.mem_state.force_update = None;
     }
 
@@ -425,6 +447,9 @@ pub fn create_collection_thread(
     let temp_type = app_config_fields.temperature_type;
     let use_current_cpu_total = app_config_fields.use_current_cpu_total;
     let show_average_cpu = app_config_fields.show_average_cpu;
+    let cpu_grouping = app_config_fields.cpu_grouping;
+    let use_container_limits = app_config_fields.use_container_limits;
+    let show_all_mounts = app_config_fields.show_all_mounts;
     let update_rate_in_milliseconds = app_config_fields.update_rate_in_milliseconds;
 
     thread::spawn(move || {
@@ -434,6 +459,9 @@ pub fn create_collection_thread(
         data_state.set_temperature_type(temp_type);
         data_state.set_use_current_cpu_total(use_current_cpu_total);
         data_state.set_show_average_cpu(show_average_cpu);
+        data_state.set_cpu_grouping(cpu_grouping);
+        data_state.set_use_container_limits(use_container_limits);
+        data_state.set_show_all_mounts(show_all_mounts);
 
         data_state.init();
 
@@ -466,6 +494,9 @@ pub fn create_collection_thread(
                     ThreadControlEvent::UpdateUpdateTime(new_time) => {
                         update_time = new_time;
                     }
+                    ThreadControlEvent::UpdateShowAllMounts(show_all_mounts) => {
+                        data_state.set_show_all_mounts(show_all_mounts);
+                    }
                 }
             }
             futures::executor::block_on(data_state.update_data());
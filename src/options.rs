@@ -5,15 +5,17 @@ use std::{
     collections::{HashMap, HashSet},
     convert::TryInto,
     str::FromStr,
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 use crate::{
     app::{
+        data_harvester::cpu::CpuGrouping,
         layout_manager::*,
         widgets::{
-            BatteryWidgetState, CpuWidgetState, DiskTableWidget, MemWidgetState, NetWidgetState,
-            ProcWidget, ProcWidgetMode, TempWidgetState,
+            BatteryWidgetState, CpuHeatmapWidgetState, CpuWidgetState, DiskIoWidgetState,
+            DiskTableWidget, KernelWidgetState, MemWidgetState, NetWidgetState,
+            PressureWidgetState, ProcWidget, ProcWidgetMode, SavedProcessFilter, TempWidgetState,
         },
         *,
     },
@@ -38,8 +40,10 @@ pub struct Config {
     pub row: Option<Vec<Row>>,
     pub disk_filter: Option<IgnoreList>,
     pub mount_filter: Option<IgnoreList>,
+    pub fs_filter: Option<IgnoreList>,
     pub temp_filter: Option<IgnoreList>,
     pub net_filter: Option<IgnoreList>,
+    pub process_filter: Option<Vec<ProcessFilter>>,
 }
 
 impl Config {
@@ -57,6 +61,7 @@ impl Config {
 #[derive(Clone, Debug, Default, Deserialize, Serialize, TypedBuilder)]
 pub struct ConfigFlags {
     pub hide_avg_cpu: Option<bool>,
+    pub cpu_group_by: Option<String>,
     pub dot_marker: Option<bool>,
     pub temperature_type: Option<String>,
     pub rate: Option<u64>,
@@ -81,6 +86,7 @@ pub struct ConfigFlags {
     // For built-in colour palettes.
     pub color: Option<String>,
     pub mem_as_value: Option<bool>,
+    pub normalize_load_avg: Option<bool>,
     pub tree: Option<bool>,
     show_table_scroll_position: Option<bool>,
     pub process_command: Option<bool>,
@@ -89,6 +95,10 @@ pub struct ConfigFlags {
     pub network_use_log: Option<bool>,
     pub network_use_binary_prefix: Option<bool>,
     pub enable_gpu_memory: Option<bool>,
+    pub container_limits: Option<bool>,
+    pub all_mounts: Option<bool>,
+    pub forecast_window: Option<u64>,
+    pub forecast_warning: Option<u64>,
 }
 
 #[derive(Clone, Default, Debug, Deserialize, Serialize)]
@@ -115,10 +125,14 @@ pub struct ConfigColours {
     pub all_cpu_color: Option<String>,
     pub avg_cpu_color: Option<String>,
     pub cpu_core_colors: Option<Vec<String>>,
+    pub cpu_heatmap_colors: Option<Vec<String>>,
     pub ram_color: Option<String>,
     pub swap_color: Option<String>,
     pub arc_color: Option<String>,
+    pub huge_pages_color: Option<String>,
     pub gpu_core_colors: Option<Vec<String>>,
+    pub numa_node_colors: Option<Vec<String>>,
+    pub compressed_colors: Option<Vec<String>>,
     pub rx_color: Option<String>,
     pub tx_color: Option<String>,
     pub rx_total_color: Option<String>, // These only affect basic mode.
@@ -134,6 +148,10 @@ pub struct ConfigColours {
     pub high_battery_color: Option<String>,
     pub medium_battery_color: Option<String>,
     pub low_battery_color: Option<String>,
+    pub disk_warning_color: Option<String>,
+    pub search_prefix_color: Option<String>,
+    pub search_operator_color: Option<String>,
+    pub search_value_color: Option<String>,
 }
 
 impl ConfigColours {
@@ -168,6 +186,14 @@ pub struct IgnoreList {
     pub whole_word: bool,
 }
 
+/// A named process query, set with `[[process_filter]]` in the config file.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ProcessFilter {
+    pub name: String,
+    pub query: String,
+    pub default_sort: Option<String>,
+}
+
 pub fn build_app(
     matches: &clap::ArgMatches, config: &mut Config, widget_layout: &BottomLayout,
     default_widget_id: u64, default_widget_type_option: &Option<BottomWidgetType>,
@@ -184,14 +210,22 @@ pub fn build_app(
     let is_case_sensitive = get_app_case_sensitive(matches, config);
     let is_match_whole_word = get_app_match_whole_word(matches, config);
     let is_use_regex = get_app_use_regex(matches, config);
+    let saved_process_filters =
+        get_saved_process_filters(config, is_case_sensitive, is_match_whole_word, is_use_regex)
+            .context("Update 'process_filter' in your config file.")?;
+    let initial_process_filter = get_initial_process_filter(matches, &saved_process_filters)?;
 
     let mut widget_map = HashMap::new();
     let mut cpu_state_map: HashMap<u64, CpuWidgetState> = HashMap::new();
+    let mut cpu_heatmap_state_map: HashMap<u64, CpuHeatmapWidgetState> = HashMap::new();
     let mut mem_state_map: HashMap<u64, MemWidgetState> = HashMap::new();
+    let mut pressure_state_map: HashMap<u64, PressureWidgetState> = HashMap::new();
+    let mut kernel_state_map: HashMap<u64, KernelWidgetState> = HashMap::new();
     let mut net_state_map: HashMap<u64, NetWidgetState> = HashMap::new();
     let mut proc_state_map: HashMap<u64, ProcWidget> = HashMap::new();
     let mut temp_state_map: HashMap<u64, TempWidgetState> = HashMap::new();
     let mut disk_state_map: HashMap<u64, DiskTableWidget> = HashMap::new();
+    let mut disk_io_state_map: HashMap<u64, DiskIoWidgetState> = HashMap::new();
     let mut battery_state_map: HashMap<u64, BatteryWidgetState> = HashMap::new();
 
     let autohide_timer = if autohide_time {
@@ -220,6 +254,15 @@ pub fn build_app(
         temperature_type: get_temperature(matches, config)
             .context("Update 'temperature_type' in your config file.")?,
         show_average_cpu: get_show_average_cpu(matches, config),
+        normalize_load_avg: get_normalize_load_avg(matches, config),
+        cpu_grouping: get_cpu_grouping(matches, config)
+            .context("Update 'cpu_group_by' in your config file.")?,
+        use_container_limits: get_use_container_limits(matches, config),
+        show_all_mounts: get_show_all_mounts(matches, config),
+        forecast_window: get_forecast_window(matches, config)
+            .context("Update 'forecast_window' in your config file.")?,
+        forecast_warning: get_forecast_warning(matches, config)
+            .context("Update 'forecast_warning' in your config file.")?,
         use_dot: get_use_dot(matches, config),
         left_legend: get_use_left_legend(matches, config),
         use_current_cpu_total: get_use_current_cpu_total(matches, config),
@@ -294,12 +337,30 @@ pub fn build_app(
                                 ),
                             );
                         }
+                        CpuHeatmap => {
+                            cpu_heatmap_state_map.insert(
+                                widget.widget_id,
+                                CpuHeatmapWidgetState::init(default_time_value),
+                            );
+                        }
                         Mem => {
                             mem_state_map.insert(
                                 widget.widget_id,
                                 MemWidgetState::init(default_time_value, autohide_timer),
                             );
                         }
+                        Pressure => {
+                            pressure_state_map.insert(
+                                widget.widget_id,
+                                PressureWidgetState::init(default_time_value, autohide_timer),
+                            );
+                        }
+                        Kernel => {
+                            kernel_state_map.insert(
+                                widget.widget_id,
+                                KernelWidgetState::init(default_time_value),
+                            );
+                        }
                         Net => {
                             net_state_map.insert(
                                 widget.widget_id,
@@ -317,19 +378,22 @@ pub fn build_app(
                                 ProcWidgetMode::Normal
                             };
 
-                            proc_state_map.insert(
-                                widget.widget_id,
-                                ProcWidget::new(
-                                    &app_config_fields,
-                                    mode,
-                                    is_case_sensitive,
-                                    is_match_whole_word,
-                                    is_use_regex,
-                                    show_memory_as_values,
-                                    is_default_command,
-                                    colours,
-                                ),
+                            let mut proc_widget = ProcWidget::new(
+                                &app_config_fields,
+                                mode,
+                                is_case_sensitive,
+                                is_match_whole_word,
+                                is_use_regex,
+                                show_memory_as_values,
+                                is_default_command,
+                                colours,
                             );
+                            proc_widget.saved_filters = saved_process_filters.clone();
+                            if initial_process_filter.is_some() {
+                                proc_widget.apply_saved_filter(initial_process_filter);
+                            }
+
+                            proc_state_map.insert(widget.widget_id, proc_widget);
                         }
                         Disk => {
                             disk_state_map.insert(
@@ -337,6 +401,12 @@ pub fn build_app(
                                 DiskTableWidget::new(&app_config_fields, colours),
                             );
                         }
+                        DiskIo => {
+                            disk_io_state_map.insert(
+                                widget.widget_id,
+                                DiskIoWidgetState::init(default_time_value, autohide_timer),
+                            );
+                        }
                         Temp => {
                             temp_state_map.insert(
                                 widget.widget_id,
@@ -379,22 +449,38 @@ pub fn build_app(
         None
     };
 
+    // The disk I/O graph follows the selected disk, so keep the selection visible like the CPU legend.
+    if used_widget_set.contains(&DiskIo) {
+        for disk_widget_state in disk_state_map.values_mut() {
+            disk_widget_state
+                .table
+                .props
+                .show_current_entry_when_unfocused = true;
+        }
+    }
+
     let use_mem = used_widget_set.get(&Mem).is_some() || used_widget_set.get(&BasicMem).is_some();
     let used_widgets = UsedWidgets {
-        use_cpu: used_widget_set.get(&Cpu).is_some() || used_widget_set.get(&BasicCpu).is_some(),
+        use_cpu: used_widget_set.get(&Cpu).is_some()
+            || used_widget_set.contains(&CpuHeatmap)
+            || used_widget_set.get(&BasicCpu).is_some(),
         use_mem,
         use_gpu: use_mem && get_enable_gpu_memory(matches, config),
         use_net: used_widget_set.get(&Net).is_some() || used_widget_set.get(&BasicNet).is_some(),
         use_proc: used_widget_set.get(&Proc).is_some(),
-        use_disk: used_widget_set.get(&Disk).is_some(),
+        use_disk: used_widget_set.contains(&Disk) || used_widget_set.contains(&DiskIo),
         use_temp: used_widget_set.get(&Temp).is_some(),
         use_battery: used_widget_set.get(&Battery).is_some(),
+        use_pressure: used_widget_set.contains(&Pressure),
+        use_kernel: used_widget_set.contains(&Kernel),
     };
 
     let disk_filter =
         get_ignore_list(&config.disk_filter).context("Update 'disk_filter' in your config file")?;
     let mount_filter = get_ignore_list(&config.mount_filter)
         .context("Update 'mount_filter' in your config file")?;
+    let fs_filter =
+        get_ignore_list(&config.fs_filter).context("Update 'fs_filter' in your config file")?;
     let temp_filter =
         get_ignore_list(&config.temp_filter).context("Update 'temp_filter' in your config file")?;
     let net_filter =
@@ -403,10 +489,14 @@ pub fn build_app(
     Ok(App::builder()
         .app_config_fields(app_config_fields)
         .cpu_state(CpuState::init(cpu_state_map))
+        .cpu_heatmap_state(CpuHeatmapState::init(cpu_heatmap_state_map))
         .mem_state(MemState::init(mem_state_map))
+        .pressure_state(PressureState::init(pressure_state_map))
+        .kernel_state(KernelState::init(kernel_state_map))
         .net_state(NetState::init(net_state_map))
         .proc_state(ProcState::init(proc_state_map))
         .disk_state(DiskState::init(disk_state_map))
+        .disk_io_state(DiskIoState::init(disk_io_state_map))
         .temp_state(TempState::init(temp_state_map))
         .battery_state(BatteryState::init(battery_state_map))
         .basic_table_widget_state(basic_table_widget_state)
@@ -416,6 +506,7 @@ pub fn build_app(
         .filters(DataFilters {
             disk_filter,
             mount_filter,
+            fs_filter,
             temp_filter,
             net_filter,
         })
@@ -554,6 +645,20 @@ fn get_show_average_cpu(matches: &clap::ArgMatches, config: &Config) -> bool {
     true
 }
 
+fn get_cpu_grouping(
+    matches: &clap::ArgMatches, config: &Config,
+) -> error::Result<Option<CpuGrouping>> {
+    if let Some(cpu_group_by) = matches.value_of("cpu_group_by") {
+        return CpuGrouping::from_str(cpu_group_by).map(Some);
+    } else if let Some(flags) = &config.flags {
+        if let Some(cpu_group_by) = &flags.cpu_group_by {
+            return CpuGrouping::from_str(cpu_group_by).map(Some);
+        }
+    }
+
+    Ok(None)
+}
+
 fn get_use_dot(matches: &clap::ArgMatches, config: &Config) -> bool {
     if matches.is_present("dot_marker") {
         return true;
@@ -826,6 +931,74 @@ fn get_use_battery(matches: &clap::ArgMatches, config: &Config) -> bool {
     false
 }
 
+fn get_use_container_limits(matches: &clap::ArgMatches, config: &Config) -> bool {
+    if matches.is_present("container_limits") {
+        return true;
+    } else if let Some(flags) = &config.flags {
+        if let Some(container_limits) = flags.container_limits {
+            return container_limits;
+        }
+    }
+    false
+}
+
+fn get_show_all_mounts(matches: &clap::ArgMatches, config: &Config) -> bool {
+    if matches.is_present("all_mounts") {
+        return true;
+    } else if let Some(flags) = &config.flags {
+        if let Some(all_mounts) = flags.all_mounts {
+            return all_mounts;
+        }
+    }
+    false
+}
+
+fn get_forecast_window(matches: &clap::ArgMatches, config: &Config) -> error::Result<Duration> {
+    let minutes = if let Some(minutes) = matches.value_of("forecast_window") {
+        minutes.parse::<u64>().map_err(|_| {
+            BottomError::ConfigError(
+                "could not parse as a valid 64-bit unsigned integer".to_string(),
+            )
+        })?
+    } else if let Some(minutes) = config
+        .flags
+        .as_ref()
+        .and_then(|flags| flags.forecast_window)
+    {
+        minutes
+    } else {
+        DEFAULT_FORECAST_WINDOW_MINUTES
+    };
+
+    if minutes == 0 {
+        return Err(BottomError::ConfigError(
+            "set your forecast window to be at least 1 minute.".to_string(),
+        ));
+    }
+
+    Ok(Duration::from_secs(minutes.saturating_mul(60)))
+}
+
+fn get_forecast_warning(matches: &clap::ArgMatches, config: &Config) -> error::Result<Duration> {
+    let hours = if let Some(hours) = matches.value_of("forecast_warning") {
+        hours.parse::<u64>().map_err(|_| {
+            BottomError::ConfigError(
+                "could not parse as a valid 64-bit unsigned integer".to_string(),
+            )
+        })?
+    } else if let Some(hours) = config
+        .flags
+        .as_ref()
+        .and_then(|flags| flags.forecast_warning)
+    {
+        hours
+    } else {
+        DEFAULT_FORECAST_WARNING_HOURS
+    };
+
+    Ok(Duration::from_secs(hours.saturating_mul(60 * 60)))
+}
+
 fn get_enable_gpu_memory(matches: &clap::ArgMatches, config: &Config) -> bool {
     if cfg!(feature = "gpu") {
         if matches.is_present("enable_gpu_memory") {
@@ -851,6 +1024,83 @@ fn get_no_write(matches: &clap::ArgMatches, config: &Config) -> bool {
     false
 }
 
+fn get_saved_process_filters(
+    config: &Config, is_case_sensitive: bool, is_match_whole_word: bool, is_use_regex: bool,
+) -> error::Result<Vec<SavedProcessFilter>> {
+    let mut saved_filters: Vec<SavedProcessFilter> = Vec::new();
+
+    for filter in config.process_filter.iter().flatten() {
+        if filter.name.is_empty() {
+            return Err(BottomError::ConfigError(
+                "process filters must have a non-empty name.".to_string(),
+            ));
+        } else if saved_filters.iter().any(|saved| saved.name == filter.name) {
+            return Err(BottomError::ConfigError(format!(
+                "there is more than one process filter named \"{}\".",
+                filter.name
+            )));
+        }
+
+        if filter.query.trim().is_empty() {
+            return Err(BottomError::ConfigError(format!(
+                "the process filter \"{}\" has an empty query.",
+                filter.name
+            )));
+        }
+
+        if let Err(err) = query::parse_query(
+            &filter.query,
+            is_match_whole_word,
+            !is_case_sensitive,
+            is_use_regex,
+        ) {
+            let reason = match err.error {
+                BottomError::QueryError(reason) => reason.to_string(),
+                err => err.to_string(),
+            };
+
+            return Err(BottomError::ConfigError(format!(
+                "the process filter \"{}\" has an invalid query: {}",
+                filter.name, reason
+            )));
+        }
+
+        let sort_index = match &filter.default_sort {
+            Some(column) => Some(ProcWidget::column_index_from_name(column).ok_or_else(|| {
+                BottomError::ConfigError(format!(
+                    "\"{}\" is an invalid sort column for the process filter \"{}\".",
+                    column, filter.name
+                ))
+            })?),
+            None => None,
+        };
+
+        saved_filters.push(SavedProcessFilter {
+            name: filter.name.clone(),
+            query: filter.query.clone(),
+            sort_index,
+        });
+    }
+
+    Ok(saved_filters)
+}
+
+fn get_initial_process_filter(
+    matches: &clap::ArgMatches, saved_filters: &[SavedProcessFilter],
+) -> error::Result<Option<usize>> {
+    if let Some(name) = matches.value_of("filter") {
+        match saved_filters.iter().position(|filter| filter.name == name) {
+            Some(index) => Ok(Some(index)),
+            None => Err(BottomError::ConfigError(format!(
+                "could not find a process filter named \"{}\".",
+                name
+            ))),
+        }
+    } else {
+        Ok(None)
+    }
+}
+
 fn get_ignore_list(ignore_list: &Option<IgnoreList>) -> error::Result<Option<Filter>> {
     if let Some(ignore_list) = ignore_list {
         let list: Result<Vec<_>, _> = ignore_list
@@ -926,6 +1176,17 @@ fn get_mem_as_value(matches: &clap::ArgMatches, config: &Config) -> bool {
     false
 }
 
+fn get_normalize_load_avg(matches: &clap::ArgMatches, config: &Config) -> bool {
+    if matches.is_present("normalize_load_avg") {
+        return true;
+    } else if let Some(flags) = &config.flags {
+        if let Some(normalize_load_avg) = flags.normalize_load_avg {
+            return normalize_load_avg;
+        }
+    }
+    false
+}
+
 fn get_is_default_tree(matches: &clap::ArgMatches, config: &Config) -> bool {
     if matches.is_present("tree") {
         return true;
@@ -176,3 +176,17 @@ fn test_battery_flag() {
             ));
     }
 }
+
+#[test]
+fn test_unknown_process_filter() {
+    btm_command()
+        .arg("-C")
+        .arg("./tests/empty_config.toml")
+        .arg("--filter")
+        .arg("nonexistent")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "could not find a process filter named \"nonexistent\"",
+        ));
+}
@@ -141,3 +141,23 @@ fn test_invalid_default_widget_count() {
         .failure()
         .stderr(predicate::str::contains("invalid number"));
 }
+
+#[test]
+fn test_invalid_process_filter_query() {
+    btm_command()
+        .arg("-C")
+        .arg("./tests/invalid_configs/invalid_process_filter_query.toml")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid query"));
+}
+
+#[test]
+fn test_duplicate_process_filter() {
+    btm_command()
+        .arg("-C")
+        .arg("./tests/invalid_configs/duplicate_process_filter.toml")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("more than one process filter"));
+}